@@ -15,36 +15,79 @@ pub struct EDisplayAnsi;
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct EDisplayAscii;
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EDisplayBraille;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EDisplayBlocks;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EDisplayKitty;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EDisplayIterm;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct EDisplay;
 
+/// A display either sizes itself to the terminal (`None`) or uses the geometry
+/// given with `display=ansi:120x40` or `geometry=120,40`
 #[derive(Clone, Debug, PartialEq, Hash, Eq)]
 pub enum DisplayOption {
-    Regis(Node<EDisplayRegis, ()>),
-    Sixel(Node<EDisplaySixel, ()>),
-    Ansi(Node<EDisplayAnsi, ()>),
-    Ascii(Node<EDisplayAscii, ()>),
+    Regis(Node<EDisplayRegis, Option<Geometry<EDisplayRegis>>>),
+    Sixel(Node<EDisplaySixel, Option<Geometry<EDisplaySixel>>>),
+    Ansi(Node<EDisplayAnsi, Option<Geometry<EDisplayAnsi>>>),
+    Ascii(Node<EDisplayAscii, Option<Geometry<EDisplayAscii>>>),
+    Braille(Node<EDisplayBraille, Option<Geometry<EDisplayBraille>>>),
+    Blocks(Node<EDisplayBlocks, Option<Geometry<EDisplayBlocks>>>),
+    Kitty(Node<EDisplayKitty, Option<Geometry<EDisplayKitty>>>),
+    Iterm(Node<EDisplayIterm, Option<Geometry<EDisplayIterm>>>),
 }
 
 impl DisplayOption {
-    pub fn regis(starts: Localization, end: Localization) -> Self {
-        Self::Regis(Node::new(starts, end, ()))
+    pub fn regis(starts: Localization, end: Localization, geometry: Option<(usize, usize)>) -> Self {
+        Self::Regis(Node::new(starts, end, geometry.map(Geometry::from)))
+    }
+    pub fn sixel(starts: Localization, end: Localization, geometry: Option<(usize, usize)>) -> Self {
+        Self::Sixel(Node::new(starts, end, geometry.map(Geometry::from)))
+    }
+    pub fn ascii(starts: Localization, end: Localization, geometry: Option<(usize, usize)>) -> Self {
+        Self::Ascii(Node::new(starts, end, geometry.map(Geometry::from)))
     }
-    pub fn sixel(starts: Localization, end: Localization) -> Self {
-        Self::Sixel(Node::new(starts, end, ()))
+    pub fn ansi(starts: Localization, end: Localization, geometry: Option<(usize, usize)>) -> Self {
+        Self::Ansi(Node::new(starts, end, geometry.map(Geometry::from)))
     }
-    pub fn ascii(starts: Localization, end: Localization) -> Self {
-        Self::Ascii(Node::new(starts, end, ()))
+    pub fn braille(starts: Localization, end: Localization, geometry: Option<(usize, usize)>) -> Self {
+        Self::Braille(Node::new(starts, end, geometry.map(Geometry::from)))
     }
-    pub fn ansi(starts: Localization, end: Localization) -> Self {
-        Self::Ansi(Node::new(starts, end, ()))
+    pub fn blocks(starts: Localization, end: Localization, geometry: Option<(usize, usize)>) -> Self {
+        Self::Blocks(Node::new(starts, end, geometry.map(Geometry::from)))
+    }
+    pub fn kitty(starts: Localization, end: Localization, geometry: Option<(usize, usize)>) -> Self {
+        Self::Kitty(Node::new(starts, end, geometry.map(Geometry::from)))
+    }
+    pub fn iterm(starts: Localization, end: Localization, geometry: Option<(usize, usize)>) -> Self {
+        Self::Iterm(Node::new(starts, end, geometry.map(Geometry::from)))
+    }
+
+    /// Pixel based displays, which need the terminal's size in pixels
+    pub fn is_graphics(&self) -> bool {
+        matches!(self, Self::Regis(_) | Self::Sixel(_) | Self::Kitty(_) | Self::Iterm(_))
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct EOutputPPM;
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputPNG;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputPGM;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputPBM;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputBMP;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct EOutputSVG;
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputPDF;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputEPS;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct EOutputLaTeX;
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct EOutputSixel;
@@ -52,6 +95,24 @@ pub struct EOutputSixel;
 pub struct EOutputRegis;
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct EOutputCSV;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputGnuplot;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputMatplotlib;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputJSON;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputVegaLite;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputNpy;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputOBJ;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputSTL;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputPLY;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EOutputHTML;
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct EOutput;
@@ -80,6 +141,12 @@ impl<T> Geometry<T> {
         }
     }
 }
+impl<T> From<(usize, usize)> for Geometry<T> {
+    fn from((width, height): (usize, usize)) -> Self {
+        Self::new(width, height)
+    }
+}
+
 impl<T> HasSameShape for Geometry<T> {
     fn has_same_shape(&self, other: &Self) -> bool {
         self.width == other.width && self.height == other.height
@@ -96,6 +163,76 @@ impl Default for Geometry<EOutputPPM> {
     }
 }
 
+impl Default for Geometry<EOutputPNG> {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 800,
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl Default for Geometry<EOutputPGM> {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 800,
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl Default for Geometry<EOutputPBM> {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 800,
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl Default for Geometry<EOutputPDF> {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 800,
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl Default for Geometry<EOutputEPS> {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 800,
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl Default for Geometry<EOutputHTML> {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 800,
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl Default for Geometry<EOutputBMP> {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 800,
+            phantom: Default::default(),
+        }
+    }
+}
+
 impl Default for Geometry<EOutputSVG> {
     fn default() -> Self {
         Self {
@@ -136,85 +273,319 @@ impl Default for Geometry<EOutputRegis> {
     }
 }
 
+/// Sample encoding of the Netpbm outputs, `format=ascii` (the default) or `format=binary`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum FormatOption {
+    #[default]
+    Ascii,
+    Binary,
+}
+
+impl HasSameShape for FormatOption {
+    fn has_same_shape(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// What a LaTeX file holds, `latex_mode=document` (the default), `latex_mode=standalone`
+/// or `latex_mode=snippet`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum LatexModeOption {
+    Standalone,
+    Snippet,
+    #[default]
+    Document,
+}
+
+impl HasSameShape for LatexModeOption {
+    fn has_same_shape(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub enum OutputOptions {
-    Ppm(Node<EOutputPPM, (String, Geometry<EOutputPPM>)>),
+    Ppm(Node<EOutputPPM, (String, Geometry<EOutputPPM>, FormatOption)>),
+    Pgm(Node<EOutputPGM, (String, Geometry<EOutputPGM>, FormatOption)>),
+    Pbm(Node<EOutputPBM, (String, Geometry<EOutputPBM>, FormatOption)>),
+    Png(Node<EOutputPNG, (String, Geometry<EOutputPNG>)>),
+    Bmp(Node<EOutputBMP, (String, Geometry<EOutputBMP>)>),
     Svg(Node<EOutputSVG, (String, Geometry<EOutputSVG>)>),
-    LaTeX(Node<EOutputLaTeX, (String, Geometry<EOutputLaTeX>)>),
+    /// File name, page size and whether to compress the page
+    Pdf(Node<EOutputPDF, (String, Geometry<EOutputPDF>, bool)>),
+    Eps(Node<EOutputEPS, (String, Geometry<EOutputEPS>)>),
+    Html(Node<EOutputHTML, (String, Geometry<EOutputHTML>)>),
+    LaTeX(Node<EOutputLaTeX, (String, Geometry<EOutputLaTeX>, LatexModeOption)>),
     Sixel(Node<EOutputSixel, (String, Geometry<EOutputSixel>)>),
     Regis(Node<EOutputRegis, (String, Geometry<EOutputRegis>)>),
     Csv(Node<EOutputCSV, String>),
+    Gnuplot(Node<EOutputGnuplot, String>),
+    Matplotlib(Node<EOutputMatplotlib, String>),
+    Json(Node<EOutputJSON, String>),
+    VegaLite(Node<EOutputVegaLite, String>),
+    Npy(Node<EOutputNpy, String>),
+    Obj(Node<EOutputOBJ, String>),
+    Stl(Node<EOutputSTL, (String, Option<Geometry<EOutputSTL>>, FormatOption)>),
+    Ply(Node<EOutputPLY, String>),
 }
 
 impl OutputOptions {
-    pub fn ppm(start: Localization, end: Localization, var: String) -> Self {
-        Self::Ppm(Node::new(start, end, (var, Geometry::default())))
-    }
-    pub fn ppm_geom(
+    pub fn ppm(
         start: Localization,
         end: Localization,
         var: String,
-        width: usize,
-        height: usize,
+        geometry: Option<(usize, usize)>,
+        format: FormatOption,
     ) -> Self {
-        Self::Ppm(Node::new(start, end, (var, Geometry::new(width, height))))
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Ppm(Node::new(start, end, (var, geometry, format)))
     }
-    pub fn svg(start: Localization, end: Localization, var: String) -> Self {
-        Self::Svg(Node::new(start, end, (var, Geometry::default())))
+    pub fn pgm(
+        start: Localization,
+        end: Localization,
+        var: String,
+        geometry: Option<(usize, usize)>,
+        format: FormatOption,
+    ) -> Self {
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Pgm(Node::new(start, end, (var, geometry, format)))
     }
-    pub fn svg_geom(
+    pub fn pbm(
         start: Localization,
         end: Localization,
         var: String,
-        width: usize,
-        height: usize,
+        geometry: Option<(usize, usize)>,
+        format: FormatOption,
     ) -> Self {
-        Self::Svg(Node::new(start, end, (var, Geometry::new(width, height))))
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Pbm(Node::new(start, end, (var, geometry, format)))
     }
-
-    pub fn latex(start: Localization, end: Localization, var: String) -> Self {
-        Self::LaTeX(Node::new(start, end, (var, Geometry::default())))
+    pub fn bmp(start: Localization, end: Localization, var: String, geometry: Option<(usize, usize)>) -> Self {
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Bmp(Node::new(start, end, (var, geometry)))
+    }
+    pub fn png(start: Localization, end: Localization, var: String, geometry: Option<(usize, usize)>) -> Self {
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Png(Node::new(start, end, (var, geometry)))
+    }
+    pub fn svg(start: Localization, end: Localization, var: String, geometry: Option<(usize, usize)>) -> Self {
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Svg(Node::new(start, end, (var, geometry)))
     }
-    pub fn latex_geom(
+
+    pub fn pdf(
         start: Localization,
         end: Localization,
         var: String,
-        width: usize,
-        height: usize,
+        geometry: Option<(usize, usize)>,
+        compress: bool,
     ) -> Self {
-        Self::LaTeX(Node::new(start, end, (var, Geometry::new(width, height))))
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Pdf(Node::new(start, end, (var, geometry, compress)))
+    }
+
+    pub fn eps(start: Localization, end: Localization, var: String, geometry: Option<(usize, usize)>) -> Self {
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Eps(Node::new(start, end, (var, geometry)))
     }
 
-    pub fn sixel(start: Localization, end: Localization, var: String) -> Self {
-        Self::Sixel(Node::new(start, end, (var, Geometry::default())))
+    pub fn html(start: Localization, end: Localization, var: String, geometry: Option<(usize, usize)>) -> Self {
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Html(Node::new(start, end, (var, geometry)))
     }
 
-    pub fn sixel_geom(
+    pub fn latex(
         start: Localization,
         end: Localization,
         var: String,
-        width: usize,
-        height: usize,
+        geometry: Option<(usize, usize)>,
+        mode: LatexModeOption,
     ) -> Self {
-        Self::Sixel(Node::new(start, end, (var, Geometry::new(width, height))))
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::LaTeX(Node::new(start, end, (var, geometry, mode)))
     }
 
-    pub fn regis(start: Localization, end: Localization, var: String) -> Self {
-        Self::Regis(Node::new(start, end, (var, Geometry::default())))
+    pub fn sixel(start: Localization, end: Localization, var: String, geometry: Option<(usize, usize)>) -> Self {
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Sixel(Node::new(start, end, (var, geometry)))
     }
 
-    pub fn regis_geom(
+    pub fn regis(start: Localization, end: Localization, var: String, geometry: Option<(usize, usize)>) -> Self {
+        let geometry = geometry.map_or_else(Geometry::default, Geometry::from);
+        Self::Regis(Node::new(start, end, (var, geometry)))
+    }
+
+    pub fn csv(start: Localization, end: Localization, var: String) -> Self {
+        Self::Csv(Node::new(start, end, var))
+    }
+
+    pub fn gnuplot(start: Localization, end: Localization, var: String) -> Self {
+        Self::Gnuplot(Node::new(start, end, var))
+    }
+
+    pub fn matplotlib(start: Localization, end: Localization, var: String) -> Self {
+        Self::Matplotlib(Node::new(start, end, var))
+    }
+
+    pub fn json(start: Localization, end: Localization, var: String) -> Self {
+        Self::Json(Node::new(start, end, var))
+    }
+
+    pub fn vegalite(start: Localization, end: Localization, var: String) -> Self {
+        Self::VegaLite(Node::new(start, end, var))
+    }
+
+    pub fn npy(start: Localization, end: Localization, var: String) -> Self {
+        Self::Npy(Node::new(start, end, var))
+    }
+
+    pub fn obj(start: Localization, end: Localization, var: String) -> Self {
+        Self::Obj(Node::new(start, end, var))
+    }
+
+    pub fn stl(
         start: Localization,
         end: Localization,
         var: String,
-        width: usize,
-        height: usize,
+        geometry: Option<(usize, usize)>,
+        format: FormatOption,
     ) -> Self {
-        Self::Regis(Node::new(start, end, (var, Geometry::new(width, height))))
+        Self::Stl(Node::new(start, end, (var, geometry.map(Geometry::from), format)))
     }
 
-    pub fn csv(start: Localization, end: Localization, var: String) -> Self {
-        Self::Csv(Node::new(start, end, var))
+    pub fn ply(start: Localization, end: Localization, var: String) -> Self {
+        Self::Ply(Node::new(start, end, var))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ETitle;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EXLabel;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EYLabel;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EZLabel;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EXRange;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EYRange;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EAutoscale;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EXScale;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EYScale;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EZScale;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EAsymptotes;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EStyle;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ELineWidth;
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EDash;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ScaleOption {
+    #[default]
+    Linear,
+    Log,
+}
+
+impl HasSameShape for ScaleOption {
+    fn has_same_shape(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum StyleOption {
+    Points,
+    #[default]
+    Lines,
+    Both,
+}
+
+impl HasSameShape for StyleOption {
+    fn has_same_shape(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EPlotOption;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlotOption {
+    Title(Node<ETitle, String>),
+    XLabel(Node<EXLabel, String>),
+    YLabel(Node<EYLabel, String>),
+    ZLabel(Node<EZLabel, String>),
+    XRange(Node<EXRange, (f64, f64)>),
+    YRange(Node<EYRange, (f64, f64)>),
+    /// Percentage trimmed at each end, 0 for the full extent
+    Autoscale(Node<EAutoscale, f64>),
+    XScale(Node<EXScale, ScaleOption>),
+    YScale(Node<EYScale, ScaleOption>),
+    ZScale(Node<EZScale, ScaleOption>),
+    /// Draw dashed vertical lines where the curve passes through infinity
+    Asymptotes(Node<EAsymptotes, bool>),
+    /// Draw text plots as points, lines or both
+    Style(Node<EStyle, StyleOption>),
+    /// Width of curves in the image outputs, like SVG's `stroke-width`
+    LineWidth(Node<ELineWidth, f64>),
+    /// Dash and gap lengths of curves in the image outputs, like SVG's
+    /// `stroke-dasharray`; empty for `dash=none`
+    Dash(Node<EDash, Vec<f64>>),
+}
+
+impl PlotOption {
+    pub fn title(start: Localization, end: Localization, text: String) -> Self {
+        Self::Title(Node::new(start, end, text))
+    }
+    pub fn xlabel(start: Localization, end: Localization, text: String) -> Self {
+        Self::XLabel(Node::new(start, end, text))
+    }
+    pub fn ylabel(start: Localization, end: Localization, text: String) -> Self {
+        Self::YLabel(Node::new(start, end, text))
+    }
+    pub fn zlabel(start: Localization, end: Localization, text: String) -> Self {
+        Self::ZLabel(Node::new(start, end, text))
+    }
+    pub fn xrange(start: Localization, end: Localization, min: f64, max: f64) -> Self {
+        Self::XRange(Node::new(start, end, (min, max)))
+    }
+    pub fn yrange(start: Localization, end: Localization, min: f64, max: f64) -> Self {
+        Self::YRange(Node::new(start, end, (min, max)))
+    }
+    pub fn autoscale(start: Localization, end: Localization, percentile: f64) -> Self {
+        Self::Autoscale(Node::new(start, end, percentile))
+    }
+    pub fn xscale(start: Localization, end: Localization, scale: ScaleOption) -> Self {
+        Self::XScale(Node::new(start, end, scale))
+    }
+    pub fn yscale(start: Localization, end: Localization, scale: ScaleOption) -> Self {
+        Self::YScale(Node::new(start, end, scale))
+    }
+    pub fn zscale(start: Localization, end: Localization, scale: ScaleOption) -> Self {
+        Self::ZScale(Node::new(start, end, scale))
+    }
+    pub fn asymptotes(start: Localization, end: Localization, show: bool) -> Self {
+        Self::Asymptotes(Node::new(start, end, show))
+    }
+    pub fn style(start: Localization, end: Localization, style: StyleOption) -> Self {
+        Self::Style(Node::new(start, end, style))
+    }
+    pub fn linewidth(start: Localization, end: Localization, width: f64) -> Self {
+        Self::LineWidth(Node::new(start, end, width))
+    }
+    pub fn dash(start: Localization, end: Localization, lengths: Vec<f64>) -> Self {
+        Self::Dash(Node::new(start, end, lengths))
     }
 }
 
@@ -226,11 +597,53 @@ pub struct ECommandOption;
 pub struct CommandOptions {
     pub display: HashSet<DisplayOption>,
     pub output: HashSet<OutputOptions>,
+    pub title: Option<String>,
+    pub xlabel: Option<String>,
+    pub ylabel: Option<String>,
+    pub zlabel: Option<String>,
+    pub x_view: Option<(f64, f64)>,
+    pub y_view: Option<(f64, f64)>,
+    pub autoscale: Option<f64>,
+    pub x_scale: ScaleOption,
+    pub y_scale: ScaleOption,
+    pub z_scale: ScaleOption,
+    pub asymptotes: bool,
+    pub style: StyleOption,
+    pub linewidth: Option<f64>,
+    pub dash: Vec<f64>,
 }
 
 impl CommandOptions {
-    pub fn new(output: HashSet<OutputOptions>, display: HashSet<DisplayOption>) -> Self {
-        Self { display, output }
+    pub fn new(
+        output: HashSet<OutputOptions>,
+        display: HashSet<DisplayOption>,
+        plot: Vec<PlotOption>,
+    ) -> Self {
+        let mut options = Self {
+            display,
+            output,
+            ..Default::default()
+        };
+        // Later occurrences win, so `title=a title=b` plots with "b"
+        for option in plot {
+            match option {
+                PlotOption::Title(node) => options.title = Some(node.value),
+                PlotOption::XLabel(node) => options.xlabel = Some(node.value),
+                PlotOption::YLabel(node) => options.ylabel = Some(node.value),
+                PlotOption::ZLabel(node) => options.zlabel = Some(node.value),
+                PlotOption::XRange(node) => options.x_view = Some(node.value),
+                PlotOption::YRange(node) => options.y_view = Some(node.value),
+                PlotOption::Autoscale(node) => options.autoscale = Some(node.value),
+                PlotOption::XScale(node) => options.x_scale = node.value,
+                PlotOption::YScale(node) => options.y_scale = node.value,
+                PlotOption::ZScale(node) => options.z_scale = node.value,
+                PlotOption::Asymptotes(node) => options.asymptotes = node.value,
+                PlotOption::Style(node) => options.style = node.value,
+                PlotOption::LineWidth(node) => options.linewidth = Some(node.value),
+                PlotOption::Dash(node) => options.dash = node.value,
+            }
+        }
+        options
     }
 }
 
@@ -5,17 +5,65 @@ use parser_combinator::{either::Either, Parse};
 use crate::{
     command_options::*,
     parser_common::{
-        AnciToken, AsciiAnythingUpToSpace, AsciiToken, Comma, GeometryToken, IntegerToken,
-        ParseErrors, RegisToken, SixelToken, State, With,
+        AnciToken, AsciiAnythingUpToSpace, AsciiToken, BrailleToken, BlocksToken, KittyToken, ItermToken, ByToken, Comma, GeometryToken, IntegerToken,
+        ParseErrors, QuotedString, RegisToken, SixelToken, State, TitleToken, With,
+        XLabelToken, YLabelToken, ZLabelToken, AutoscaleToken, Colon, DoubleToken, FullToken,
+        PercentileToken, XRangeToken, YRangeToken, LinearToken, LogToken, XScaleToken,
+        YScaleToken, ZScaleToken, AsymptotesToken, OnToken, OffToken,
+        StyleToken, PointsToken, LinesToken, BothToken, LineWidthToken, DashToken, NoneToken,
     },
 };
 
 pub type DisplayParseResult<'a> = Result<(DisplayOption, State, Chars<'a>), ParseErrors>;
 
+/// `geometry=WIDTH,HEIGHT`, the size of a display or of a file output
+struct EGeometry;
+impl<'a> Parse<'a, Chars<'a>, State, (usize, usize), ParseErrors> for EGeometry {
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<((usize, usize), State, Chars<'a>), ParseErrors> {
+        GeometryToken
+            .pair(
+                IntegerToken
+                    .triple(Comma, IntegerToken)
+                    .transform(|(a, _, b)| {
+                        (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap())
+                    })
+                    .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)),
+            )
+            .second()
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+/// Explicit display size, either `:WIDTHxHEIGHT` right after the display name or
+/// `geometry=WIDTH,HEIGHT` as for file outputs
+struct EDisplayGeometry;
+impl<'a> Parse<'a, Chars<'a>, State, (usize, usize), ParseErrors> for EDisplayGeometry {
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<((usize, usize), State, Chars<'a>), ParseErrors> {
+        let colon_form = Colon
+            .pair(
+                IntegerToken
+                    .triple(ByToken, IntegerToken)
+                    .transform(|(a, _, b)| {
+                        (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap())
+                    })
+                    .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)),
+            )
+            .second();
+
+        colon_form
+            .or_else(EGeometry)
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
 impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplayRegis {
     fn parse(&self, input: Chars<'a>, state: State) -> DisplayParseResult<'a> {
         RegisToken
-            .transform_with_state(|_, s| DisplayOption::regis(s.start, s.end))
+            .pair(EDisplayGeometry)
+            .transform_with_state(|(_, size), s| DisplayOption::regis(s.start, s.end, Some(size)))
+            .or_else(RegisToken.transform_with_state(|_, s| DisplayOption::regis(s.start, s.end, None)))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
@@ -24,7 +72,9 @@ impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplayReg
 impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplaySixel {
     fn parse(&self, input: Chars<'a>, state: State) -> DisplayParseResult<'a> {
         SixelToken
-            .transform_with_state(|_, s| DisplayOption::sixel(s.start, s.end))
+            .pair(EDisplayGeometry)
+            .transform_with_state(|(_, size), s| DisplayOption::sixel(s.start, s.end, Some(size)))
+            .or_else(SixelToken.transform_with_state(|_, s| DisplayOption::sixel(s.start, s.end, None)))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
@@ -33,7 +83,9 @@ impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplaySix
 impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplayAscii {
     fn parse(&self, input: Chars<'a>, state: State) -> DisplayParseResult<'a> {
         AsciiToken
-            .transform_with_state(|_, s| DisplayOption::ascii(s.start, s.end))
+            .pair(EDisplayGeometry)
+            .transform_with_state(|(_, size), s| DisplayOption::ascii(s.start, s.end, Some(size)))
+            .or_else(AsciiToken.transform_with_state(|_, s| DisplayOption::ascii(s.start, s.end, None)))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
@@ -42,7 +94,53 @@ impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplayAsc
 impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplayAnsi {
     fn parse(&self, input: Chars<'a>, state: State) -> DisplayParseResult<'a> {
         AnciToken
-            .transform_with_state(|_, s| DisplayOption::ansi(s.start, s.end))
+            .pair(EDisplayGeometry)
+            .transform_with_state(|(_, size), s| DisplayOption::ansi(s.start, s.end, Some(size)))
+            .or_else(AnciToken.transform_with_state(|_, s| DisplayOption::ansi(s.start, s.end, None)))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplayBraille {
+    fn parse(&self, input: Chars<'a>, state: State) -> DisplayParseResult<'a> {
+        BrailleToken
+            .pair(EDisplayGeometry)
+            .transform_with_state(|(_, size), s| DisplayOption::braille(s.start, s.end, Some(size)))
+            .or_else(BrailleToken.transform_with_state(|_, s| DisplayOption::braille(s.start, s.end, None)))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplayBlocks {
+    fn parse(&self, input: Chars<'a>, state: State) -> DisplayParseResult<'a> {
+        BlocksToken
+            .pair(EDisplayGeometry)
+            .transform_with_state(|(_, size), s| DisplayOption::blocks(s.start, s.end, Some(size)))
+            .or_else(BlocksToken.transform_with_state(|_, s| DisplayOption::blocks(s.start, s.end, None)))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplayKitty {
+    fn parse(&self, input: Chars<'a>, state: State) -> DisplayParseResult<'a> {
+        KittyToken
+            .pair(EDisplayGeometry)
+            .transform_with_state(|(_, size), s| DisplayOption::kitty(s.start, s.end, Some(size)))
+            .or_else(KittyToken.transform_with_state(|_, s| DisplayOption::kitty(s.start, s.end, None)))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplayIterm {
+    fn parse(&self, input: Chars<'a>, state: State) -> DisplayParseResult<'a> {
+        ItermToken
+            .pair(EDisplayGeometry)
+            .transform_with_state(|(_, size), s| DisplayOption::iterm(s.start, s.end, Some(size)))
+            .or_else(ItermToken.transform_with_state(|_, s| DisplayOption::iterm(s.start, s.end, None)))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
@@ -52,6 +150,10 @@ impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplay {
     fn parse(&self, input: Chars<'a>, state: State) -> DisplayParseResult<'a> {
         EDisplayAnsi
             .or_else(EDisplayAscii)
+            .or_else(EDisplayBraille)
+            .or_else(EDisplayBlocks)
+            .or_else(EDisplayKitty)
+            .or_else(EDisplayIterm)
             .or_else(EDisplaySixel)
             .or_else(EDisplayRegis)
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
@@ -61,183 +163,337 @@ impl<'a> Parse<'a, Chars<'a>, State, DisplayOption, ParseErrors> for EDisplay {
 
 pub type OutputParseResult<'a> = Result<(OutputOptions, State, Chars<'a>), ParseErrors>;
 
-use crate::parser_common::{CsvToken, LatexToken, PpmToken, RegisDToken, SixelDToken, SvgToken};
+use crate::parser_common::{
+    AsciiFormatToken, BinaryFormatToken, BmpToken, CompressToken, CsvToken, DocumentModeToken, EpsToken, FormatToken, GnuplotToken, HtmlToken, JsonToken, MatplotlibToken, NpyToken, ObjToken, PlyToken, StlToken, VegaLiteToken, LatexModeToken, LatexToken,
+    PbmToken, PdfToken, PgmToken, PngToken, PpmToken, RegisDToken, SixelDToken, SnippetModeToken, StandaloneModeToken, SvgToken,
+};
+
+/// `geometry=WIDTH,HEIGHT` and one more option after a file name, each optional
+/// and in either order. The option is `default` unless given; the last of each wins.
+struct EModifiers<P, T> {
+    option: P,
+    default: T,
+}
+
+impl<P, T> EModifiers<P, T> {
+    fn new(option: P, default: T) -> Self {
+        Self { option, default }
+    }
+}
+
+impl<'a, P, T> Parse<'a, Chars<'a>, State, (Option<(usize, usize)>, T), ParseErrors> for EModifiers<P, T>
+where
+    P: Parse<'a, Chars<'a>, State, T, ParseErrors> + Clone,
+    T: Clone,
+{
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<((Option<(usize, usize)>, T), State, Chars<'a>), ParseErrors> {
+        let default = self.default.clone();
+        EGeometry
+            .either(self.option.clone())
+            .zero_or_more()
+            .transform(move |modifiers| {
+                let mut result = (None, default.clone());
+                for modifier in modifiers {
+                    match modifier {
+                        Either::Left(geometry) => result.0 = Some(geometry),
+                        Either::Right(option) => result.1 = option,
+                    }
+                }
+                result
+            })
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+/// `format=ascii|binary`, for the Netpbm and STL outputs
+#[derive(Clone)]
+struct EFormat;
+impl<'a> Parse<'a, Chars<'a>, State, FormatOption, ParseErrors> for EFormat {
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<(FormatOption, State, Chars<'a>), ParseErrors> {
+        FormatToken
+            .pair(
+                AsciiFormatToken
+                    .transform(|_| FormatOption::Ascii)
+                    .or_else(BinaryFormatToken.transform(|_| FormatOption::Binary))
+                    .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)),
+            )
+            .second()
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
 
 impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputPPM {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
         PpmToken
             .pair(AsciiAnythingUpToSpace)
-            .transform_with_state(|(_, var), s| OutputOptions::ppm(s.start, s.end, var))
+            .second()
+            .pair(EModifiers::new(EFormat, FormatOption::default()))
+            .transform_with_state(|(var, (geometry, format)), s| OutputOptions::ppm(s.start, s.end, var, geometry, format))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputSVG {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputPGM {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
-        SvgToken
+        PgmToken
             .pair(AsciiAnythingUpToSpace)
-            .transform_with_state(|(_, var), s| OutputOptions::svg(s.start, s.end, var))
+            .second()
+            .pair(EModifiers::new(EFormat, FormatOption::default()))
+            .transform_with_state(|(var, (geometry, format)), s| OutputOptions::pgm(s.start, s.end, var, geometry, format))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputLaTeX {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputPBM {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
-        LatexToken
+        PbmToken
             .pair(AsciiAnythingUpToSpace)
-            .transform_with_state(|(_, var), s| OutputOptions::latex(s.start, s.end, var))
+            .second()
+            .pair(EModifiers::new(EFormat, FormatOption::default()))
+            .transform_with_state(|(var, (geometry, format)), s| OutputOptions::pbm(s.start, s.end, var, geometry, format))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputSixel {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputBMP {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
-        SixelDToken
+        BmpToken
             .pair(AsciiAnythingUpToSpace)
-            .transform_with_state(|(_, var), s| OutputOptions::sixel(s.start, s.end, var))
+            .second()
+            .pair(EGeometry.zero_or_more())
+            .transform_with_state(|(var, geometries), s| OutputOptions::bmp(s.start, s.end, var, geometries.last().copied()))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputRegis {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputEPS {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
-        RegisDToken
+        EpsToken
             .pair(AsciiAnythingUpToSpace)
-            .transform_with_state(|(_, var), s| OutputOptions::regis(s.start, s.end, var))
+            .second()
+            .pair(EGeometry.zero_or_more())
+            .transform_with_state(|(var, geometries), s| OutputOptions::eps(s.start, s.end, var, geometries.last().copied()))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputCSV {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputHTML {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
-        CsvToken
+        HtmlToken
             .pair(AsciiAnythingUpToSpace)
-            .transform_with_state(|(_, var), s| OutputOptions::csv(s.start, s.end, var))
+            .second()
+            .pair(EGeometry.zero_or_more())
+            .transform_with_state(|(var, geometries), s| OutputOptions::html(s.start, s.end, var, geometries.last().copied()))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-struct EOutputPNGwithGeometry;
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputPNGwithGeometry {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputPNG {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
-        PpmToken
+        PngToken
             .pair(AsciiAnythingUpToSpace)
             .second()
-            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
-            .triple(
-                GeometryToken,
-                IntegerToken
-                    .triple(Comma, IntegerToken)
-                    .transform(|(a, _, b)| {
-                        (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap())
-                    })
-                    .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)),
-            )
-            .transform_with_state(|(var, _, (a, b)), s| {
-                OutputOptions::ppm_geom(s.start, s.end, var, a, b)
-            })
+            .pair(EGeometry.zero_or_more())
+            .transform_with_state(|(var, geometries), s| OutputOptions::png(s.start, s.end, var, geometries.last().copied()))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-struct EOutputJPGwithGeometry;
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputJPGwithGeometry {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputSVG {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
         SvgToken
             .pair(AsciiAnythingUpToSpace)
             .second()
+            .pair(EGeometry.zero_or_more())
+            .transform_with_state(|(var, geometries), s| OutputOptions::svg(s.start, s.end, var, geometries.last().copied()))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
-            .triple(
-                GeometryToken,
-                IntegerToken
-                    .triple(Comma, IntegerToken)
-                    .transform(|(a, _, b)| {
-                        (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap())
-                    })
+            .parse(input, state)
+    }
+}
+
+/// `compress=on|off` after a PDF file name. Pages are compressed unless asked not to.
+#[derive(Clone)]
+struct ECompress;
+impl<'a> Parse<'a, Chars<'a>, State, bool, ParseErrors> for ECompress {
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<(bool, State, Chars<'a>), ParseErrors> {
+        CompressToken
+            .pair(
+                OnToken
+                    .transform(|_| true)
+                    .or_else(OffToken.transform(|_| false))
                     .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)),
             )
-            .transform_with_state(|(var, _, (a, b)), s| {
-                OutputOptions::svg_geom(s.start, s.end, var, a, b)
-            })
+            .second()
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-struct EOutputLatexWithGeometry;
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputLatexWithGeometry {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputPDF {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
-        LatexToken
+        PdfToken
             .pair(AsciiAnythingUpToSpace)
             .second()
+            .pair(EModifiers::new(ECompress, true))
+            .transform_with_state(|(var, (geometry, compress)), s| OutputOptions::pdf(s.start, s.end, var, geometry, compress))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
-            .triple(
-                GeometryToken,
-                IntegerToken
-                    .triple(Comma, IntegerToken)
-                    .transform(|(a, _, b)| {
-                        (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap())
-                    })
+            .parse(input, state)
+    }
+}
+
+/// `latex_mode=standalone|snippet|document` after a LaTeX file name
+#[derive(Clone)]
+struct ELatexMode;
+impl<'a> Parse<'a, Chars<'a>, State, LatexModeOption, ParseErrors> for ELatexMode {
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<(LatexModeOption, State, Chars<'a>), ParseErrors> {
+        LatexModeToken
+            .pair(
+                StandaloneModeToken
+                    .transform(|_| LatexModeOption::Standalone)
+                    .or_else(SnippetModeToken.transform(|_| LatexModeOption::Snippet))
+                    .or_else(DocumentModeToken.transform(|_| LatexModeOption::Document))
                     .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)),
             )
-            .transform_with_state(|(var, _, (a, b)), s| {
-                OutputOptions::latex_geom(s.start, s.end, var, a, b)
-            })
+            .second()
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-struct EOutputSixelWithGeometry;
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputSixelWithGeometry {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputLaTeX {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
-        SixelDToken
+        LatexToken
             .pair(AsciiAnythingUpToSpace)
             .second()
+            .pair(EModifiers::new(ELatexMode, LatexModeOption::default()))
+            .transform_with_state(|(var, (geometry, mode)), s| OutputOptions::latex(s.start, s.end, var, geometry, mode))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
-            .triple(
-                GeometryToken,
-                IntegerToken
-                    .triple(Comma, IntegerToken)
-                    .transform(|(a, _, b)| {
-                        (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap())
-                    })
-                    .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)),
-            )
-            .transform_with_state(|(var, _, (a, b)), s| {
-                OutputOptions::sixel_geom(s.start, s.end, var, a, b)
-            })
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputSixel {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        SixelDToken
+            .pair(AsciiAnythingUpToSpace)
+            .second()
+            .pair(EGeometry.zero_or_more())
+            .transform_with_state(|(var, geometries), s| OutputOptions::sixel(s.start, s.end, var, geometries.last().copied()))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
 }
 
-struct EOutputRegisWithGeometry;
-impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputRegisWithGeometry {
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputRegis {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
         RegisDToken
             .pair(AsciiAnythingUpToSpace)
             .second()
+            .pair(EGeometry.zero_or_more())
+            .transform_with_state(|(var, geometries), s| OutputOptions::regis(s.start, s.end, var, geometries.last().copied()))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
-            .triple(
-                GeometryToken,
-                IntegerToken
-                    .triple(Comma, IntegerToken)
-                    .transform(|(a, _, b)| {
-                        (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap())
-                    })
-                    .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)),
-            )
-            .transform_with_state(|(var, _, (a, b)), s| {
-                OutputOptions::regis_geom(s.start, s.end, var, a, b)
-            })
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputCSV {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        CsvToken
+            .pair(AsciiAnythingUpToSpace)
+            .transform_with_state(|(_, var), s| OutputOptions::csv(s.start, s.end, var))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputGnuplot {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        GnuplotToken
+            .pair(AsciiAnythingUpToSpace)
+            .transform_with_state(|(_, var), s| OutputOptions::gnuplot(s.start, s.end, var))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputMatplotlib {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        MatplotlibToken
+            .pair(AsciiAnythingUpToSpace)
+            .transform_with_state(|(_, var), s| OutputOptions::matplotlib(s.start, s.end, var))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputJSON {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        JsonToken
+            .pair(AsciiAnythingUpToSpace)
+            .transform_with_state(|(_, var), s| OutputOptions::json(s.start, s.end, var))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputVegaLite {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        VegaLiteToken
+            .pair(AsciiAnythingUpToSpace)
+            .transform_with_state(|(_, var), s| OutputOptions::vegalite(s.start, s.end, var))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputNpy {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        NpyToken
+            .pair(AsciiAnythingUpToSpace)
+            .transform_with_state(|(_, var), s| OutputOptions::npy(s.start, s.end, var))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputOBJ {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        ObjToken
+            .pair(AsciiAnythingUpToSpace)
+            .transform_with_state(|(_, var), s| OutputOptions::obj(s.start, s.end, var))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+/// An STL file name with an optional `format=ascii|binary`. A `geometry=` is
+/// accepted like for the other outputs, but an STL mesh has no size in pixels.
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputSTL {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        StlToken
+            .pair(AsciiAnythingUpToSpace)
+            .second()
+            .pair(EModifiers::new(EFormat, FormatOption::default()))
+            .transform_with_state(|(var, (geometry, format)), s| OutputOptions::stl(s.start, s.end, var, geometry, format))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputPLY {
+    fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
+        PlyToken
+            .pair(AsciiAnythingUpToSpace)
+            .transform_with_state(|(_, var), s| OutputOptions::ply(s.start, s.end, var))
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
@@ -246,11 +502,236 @@ impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutputRegi
 impl<'a> Parse<'a, Chars<'a>, State, OutputOptions, ParseErrors> for EOutput {
     fn parse(&self, input: Chars<'a>, state: State) -> OutputParseResult<'a> {
         EOutputCSV
-            .or_else(EOutputLatexWithGeometry.or_else(EOutputLaTeX))
-            .or_else(EOutputJPGwithGeometry.or_else(EOutputSVG))
-            .or_else(EOutputPNGwithGeometry.or_else(EOutputPPM))
-            .or_else(EOutputRegisWithGeometry.or_else(EOutputRegis))
-            .or_else(EOutputSixelWithGeometry.or_else(EOutputSixel))
+            .or_else(EOutputGnuplot)
+            .or_else(EOutputMatplotlib)
+            .or_else(EOutputJSON)
+            .or_else(EOutputVegaLite)
+            .or_else(EOutputNpy)
+            .or_else(EOutputOBJ)
+            .or_else(EOutputSTL)
+            .or_else(EOutputPLY)
+            .or_else(EOutputLaTeX)
+            .or_else(EOutputSVG)
+            .or_else(EOutputPDF)
+            .or_else(EOutputEPS)
+            .or_else(EOutputHTML)
+            .or_else(EOutputPPM)
+            .or_else(EOutputPGM)
+            .or_else(EOutputPBM)
+            .or_else(EOutputBMP)
+            .or_else(EOutputPNG)
+            .or_else(EOutputRegis)
+            .or_else(EOutputSixel)
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+pub type PlotOptionParseResult<'a> = Result<(PlotOption, State, Chars<'a>), ParseErrors>;
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for ETitle {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        TitleToken
+            .pair(QuotedString.or_else(AsciiAnythingUpToSpace))
+            .transform_with_state(|(_, text), s| PlotOption::title(s.start, s.end, text))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EXLabel {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        XLabelToken
+            .pair(QuotedString.or_else(AsciiAnythingUpToSpace))
+            .transform_with_state(|(_, text), s| PlotOption::xlabel(s.start, s.end, text))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EYLabel {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        YLabelToken
+            .pair(QuotedString.or_else(AsciiAnythingUpToSpace))
+            .transform_with_state(|(_, text), s| PlotOption::ylabel(s.start, s.end, text))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EZLabel {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        ZLabelToken
+            .pair(QuotedString.or_else(AsciiAnythingUpToSpace))
+            .transform_with_state(|(_, text), s| PlotOption::zlabel(s.start, s.end, text))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+/// `min:max` bounds of a view window
+struct EViewRange;
+impl<'a> Parse<'a, Chars<'a>, State, (f64, f64), ParseErrors> for EViewRange {
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<((f64, f64), State, Chars<'a>), ParseErrors> {
+        DoubleToken
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .triple(
+                Colon,
+                DoubleToken.with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)),
+            )
+            .transform(|(a, _, b)| (a.parse::<f64>().unwrap(), b.parse::<f64>().unwrap()))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EXRange {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        XRangeToken
+            .pair(EViewRange)
+            .transform_with_state(|(_, (a, b)), s| PlotOption::xrange(s.start, s.end, a, b))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EYRange {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        YRangeToken
+            .pair(EViewRange)
+            .transform_with_state(|(_, (a, b)), s| PlotOption::yrange(s.start, s.end, a, b))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EAutoscale {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        let percentile = PercentileToken
+            .pair(DoubleToken.with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)))
+            .transform(|(_, p)| p.parse::<f64>().unwrap());
+        let full = FullToken.transform(|_| 0.0);
+
+        AutoscaleToken
+            .pair(percentile.or_else(full))
+            .transform_with_state(|(_, p), s| PlotOption::autoscale(s.start, s.end, p))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+/// `linear` or `log`
+struct EScaleValue;
+impl<'a> Parse<'a, Chars<'a>, State, ScaleOption, ParseErrors> for EScaleValue {
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<(ScaleOption, State, Chars<'a>), ParseErrors> {
+        LinearToken
+            .transform(|_| ScaleOption::Linear)
+            .or_else(LogToken.transform(|_| ScaleOption::Log))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EXScale {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        XScaleToken
+            .pair(EScaleValue)
+            .transform_with_state(|(_, scale), s| PlotOption::xscale(s.start, s.end, scale))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EYScale {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        YScaleToken
+            .pair(EScaleValue)
+            .transform_with_state(|(_, scale), s| PlotOption::yscale(s.start, s.end, scale))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EZScale {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        ZScaleToken
+            .pair(EScaleValue)
+            .transform_with_state(|(_, scale), s| PlotOption::zscale(s.start, s.end, scale))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EAsymptotes {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        AsymptotesToken
+            .pair(OnToken.transform(|_| true).or_else(OffToken.transform(|_| false)))
+            .transform_with_state(|(_, show), s| PlotOption::asymptotes(s.start, s.end, show))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EStyle {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        StyleToken
+            .pair(
+                PointsToken
+                    .transform(|_| StyleOption::Points)
+                    .or_else(LinesToken.transform(|_| StyleOption::Lines))
+                    .or_else(BothToken.transform(|_| StyleOption::Both)),
+            )
+            .transform_with_state(|(_, style), s| PlotOption::style(s.start, s.end, style))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for ELineWidth {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        LineWidthToken
+            .pair(DoubleToken.with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end)))
+            .transform_with_state(|(_, width), s| PlotOption::linewidth(s.start, s.end, width.parse::<f64>().unwrap()))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+/// `dash=none` or comma separated dash and gap lengths, e.g. `dash=6,3`
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EDash {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        let lengths = DoubleToken
+            .pair(Comma.pair(DoubleToken).second().zero_or_more())
+            .transform(|(first, rest)| {
+                std::iter::once(first)
+                    .chain(rest)
+                    .map(|length| length.parse::<f64>().unwrap())
+                    .collect::<Vec<f64>>()
+            });
+        DashToken
+            .pair(NoneToken.transform(|_| Vec::new()).or_else(lengths))
+            .transform_with_state(|(_, lengths), s| PlotOption::dash(s.start, s.end, lengths))
+            .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, PlotOption, ParseErrors> for EPlotOption {
+    fn parse(&self, input: Chars<'a>, state: State) -> PlotOptionParseResult<'a> {
+        ETitle
+            .or_else(EXLabel)
+            .or_else(EYLabel)
+            .or_else(EZLabel)
+            .or_else(EXRange)
+            .or_else(EYRange)
+            .or_else(EAutoscale)
+            .or_else(EXScale)
+            .or_else(EYScale)
+            .or_else(EZScale)
+            .or_else(EAsymptotes)
+            .or_else(EStyle)
+            .or_else(ELineWidth)
+            .or_else(EDash)
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
     }
@@ -260,23 +741,27 @@ type CommandOptionParseResult<'a> = Result<(CommandOptions, State, Chars<'a>), P
 
 impl<'a> Parse<'a, Chars<'a>, State, CommandOptions, ParseErrors> for ECommandOption {
     fn parse(&self, input: Chars<'a>, state: State) -> CommandOptionParseResult<'a> {
-        With.pair(EOutput.either(EDisplay).zero_or_more())
+        With.pair(EOutput.either(EDisplay).either(EPlotOption).zero_or_more())
             .second()
             .transform(|x| {
                 let mut output_map = HashSet::new();
                 let mut display_map = HashSet::new();
+                let mut plot_options = Vec::new();
                 for i in x.into_iter() {
                     match i {
-                        Either::Left(output) => {
+                        Either::Left(Either::Left(output)) => {
                             output_map.insert(output);
                         }
-                        Either::Right(display) => {
+                        Either::Left(Either::Right(display)) => {
                             display_map.insert(display);
                         }
+                        Either::Right(plot_option) => {
+                            plot_options.push(plot_option);
+                        }
                     };
                 }
 
-                CommandOptions::new(output_map, display_map)
+                CommandOptions::new(output_map, display_map, plot_options)
             })
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .parse(input, state)
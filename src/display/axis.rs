@@ -0,0 +1,247 @@
+use super::AxisScale;
+
+/// Relative slack when deciding whether a tick lies inside the window, so that ticks
+/// exactly on an edge survive floating point noise
+const EDGE_TOLERANCE: f64 = 1e-9;
+
+/// A labelled tick: its value, its position between the window's minimum (0.0) and
+/// maximum (1.0), and its formatted label
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tick {
+    pub value: f64,
+    pub fraction: f64,
+    pub label: String,
+}
+
+/// Major and minor ticks of one axis window. Every renderer and writer takes its
+/// tick positions and labels from here so they all agree on where ticks go and
+/// how numbers read.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AxisTicks {
+    pub major: Vec<Tick>,
+    /// Positions of the unlabelled minor ticks as fractions of the window
+    pub minor: Vec<f64>,
+}
+
+impl AxisTicks {
+    /// Ticks for the window `min..max` with about `target` major ticks. Linear axes
+    /// step by 1, 2 or 5 times a power of ten; log axes tick every decade (thinned
+    /// on wide windows) with the 2..9 multiples as minor ticks, or use 1-2-5 steps
+    /// when the window spans less than two decades.
+    pub fn new(scale: AxisScale, min: f64, max: f64, target: usize) -> Self {
+        let (lo, hi) = (min.min(max), min.max(max));
+        let (major, minor) = match scale {
+            AxisScale::Linear => linear_ticks(lo, hi, target),
+            AxisScale::Log => log_ticks(lo, hi, target),
+        };
+        let format = match scale {
+            AxisScale::Linear => NumberFormat::for_ticks(&major),
+            AxisScale::Log => NumberFormat::Individual,
+        };
+
+        Self {
+            major: major
+                .into_iter()
+                .map(|value| Tick {
+                    value,
+                    fraction: scale.fraction(value, min, max),
+                    label: format.format(value),
+                })
+                .collect(),
+            minor: minor.into_iter().map(|value| scale.fraction(value, min, max)).collect(),
+        }
+    }
+
+    /// Ticks for a text display, as [`AxisTicks::new`] but with fewer than `target`
+    /// major ticks when two labels would land on the same line. `line` gives the
+    /// line of text a tick fraction falls on.
+    pub fn for_lines(scale: AxisScale, min: f64, max: f64, target: usize, line: impl Fn(f64) -> usize) -> Self {
+        (1..=target)
+            .rev()
+            .map(|target| Self::new(scale, min, max, target))
+            .find(|ticks| ticks.major.windows(2).all(|pair| line(pair[0].fraction) != line(pair[1].fraction)))
+            .unwrap_or_else(|| Self::new(scale, min, max, 1))
+    }
+
+    /// Major tick positions strictly inside the window, where grid lines go
+    pub fn interior(&self) -> impl Iterator<Item = f64> + '_ {
+        self.major
+            .iter()
+            .map(|tick| tick.fraction)
+            .filter(|&fraction| fraction > EDGE_TOLERANCE && fraction < 1.0 - EDGE_TOLERANCE)
+    }
+}
+
+/// Step of 1, 2 or 5 times a power of ten closest to `raw`
+pub fn nice_step(raw: f64) -> f64 {
+    if !raw.is_finite() || raw <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let mantissa = raw / magnitude;
+    let nice = if mantissa < 1.5 {
+        1.0
+    } else if mantissa < 3.5 {
+        2.0
+    } else if mantissa < 7.5 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// Major ticks on multiples of a nice step, and minor ticks dividing each step in
+/// four (steps of 2) or five (steps of 1 and 5)
+fn linear_ticks(min: f64, max: f64, target: usize) -> (Vec<f64>, Vec<f64>) {
+    let span = max - min;
+    if !span.is_finite() || span <= 0.0 {
+        return (vec![min], Vec::new());
+    }
+    let step = nice_step(span / target.max(2).saturating_sub(1).max(1) as f64);
+    let slack = step * EDGE_TOLERANCE;
+
+    let first = ((min - slack) / step).ceil() as i64;
+    let last = ((max + slack) / step).floor() as i64;
+    let major: Vec<f64> = (first..=last).map(|k| snap(k as f64 * step, step)).collect();
+
+    let mantissa = (step / 10f64.powf(step.log10().floor())).round();
+    let subdivisions = if mantissa == 2.0 { 4 } else { 5 };
+    let minor_step = step / subdivisions as f64;
+    let minor_first = ((min - slack) / minor_step).ceil() as i64;
+    let minor_last = ((max + slack) / minor_step).floor() as i64;
+    let minor = (minor_first..=minor_last)
+        .filter(|k| k.rem_euclid(subdivisions) != 0)
+        .map(|k| k as f64 * minor_step)
+        .collect();
+
+    (major, minor)
+}
+
+fn log_ticks(min: f64, max: f64, target: usize) -> (Vec<f64>, Vec<f64>) {
+    let first = min.log10().floor() as i32;
+    let last = max.log10().ceil() as i32;
+    let in_window = |value: &f64| *value >= min * (1.0 - EDGE_TOLERANCE) && *value <= max * (1.0 + EDGE_TOLERANCE);
+    let multiples = |mantissas: &'static [f64]| {
+        (first..=last)
+            .flat_map(move |k| mantissas.iter().map(move |mantissa| mantissa * 10f64.powi(k)))
+            .filter(in_window)
+            .collect::<Vec<f64>>()
+    };
+
+    let decades = multiples(&[1.0]);
+    if decades.len() >= 2 {
+        let stride = decades.len().div_ceil(target.max(2));
+        let major: Vec<f64> = decades.iter().copied().step_by(stride).collect();
+        let minor = if stride == 1 {
+            multiples(&[2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0])
+        } else {
+            decades.into_iter().filter(|value| !major.contains(value)).collect()
+        };
+        return (major, minor);
+    }
+    (multiples(&[1.0, 2.0, 5.0]), multiples(&[3.0, 4.0, 6.0, 7.0, 8.0, 9.0]))
+}
+
+/// Round away the noise of `k * step`, so that ticks print as 0.3 rather than
+/// 0.30000000000000004 and zero is never negative
+fn snap(value: f64, step: f64) -> f64 {
+    if value.abs() < step * EDGE_TOLERANCE {
+        return 0.0;
+    }
+    let digits = (-step.log10().floor()).max(0.0) as i32 + 1;
+    let scale = 10f64.powi(digits.min(15));
+    let snapped = (value * scale).round() / scale;
+    if snapped.is_finite() { snapped } else { value }
+}
+
+/// How tick values print. Plain decimals are used for magnitudes between 0.001
+/// and 10000, SI prefixes (`2.5k`, `40u`) up to tera and down to pico, and
+/// scientific notation beyond that.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberFormat {
+    Fixed { decimals: usize },
+    Si { exponent: i32, decimals: usize },
+    Scientific { exponent: i32, decimals: usize },
+    /// Each value picks its own format, for log axes whose ticks span decades
+    Individual,
+}
+
+const SI_PREFIXES: [(i32, &str); 8] = [
+    (-12, "p"),
+    (-9, "n"),
+    (-6, "u"),
+    (-3, "m"),
+    (3, "k"),
+    (6, "M"),
+    (9, "G"),
+    (12, "T"),
+];
+
+impl NumberFormat {
+    /// One format for a set of evenly spaced ticks: the step decides the number of
+    /// decimals, the largest magnitude decides the notation
+    pub fn for_ticks(ticks: &[f64]) -> Self {
+        let step = ticks
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .fold(f64::INFINITY, f64::min);
+        let largest = ticks.iter().fold(0.0f64, |acc, value| acc.max(value.abs()));
+        let step = if step.is_finite() && step > 0.0 { step } else { largest.max(1.0) };
+        Self::for_magnitude(largest, step)
+    }
+
+    /// Format for numbers up to `largest` in magnitude that differ by `step`
+    pub fn for_magnitude(largest: f64, step: f64) -> Self {
+        let decimals_for = |exponent: i32| (exponent - step.log10().floor() as i32).max(0) as usize;
+        if largest == 0.0 || (1e-3..1e4).contains(&largest) {
+            return NumberFormat::Fixed { decimals: decimals_for(0) };
+        }
+        let exponent = largest.log10().floor() as i32;
+        let engineering = exponent.div_euclid(3) * 3;
+        if SI_PREFIXES.iter().any(|&(si, _)| si == engineering) {
+            NumberFormat::Si { exponent: engineering, decimals: decimals_for(engineering) }
+        } else {
+            NumberFormat::Scientific { exponent, decimals: decimals_for(exponent) }
+        }
+    }
+
+    pub fn format(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return format!("{value}");
+        }
+        match *self {
+            NumberFormat::Fixed { decimals } => trim_negative_zero(format!("{value:.decimals$}")),
+            NumberFormat::Si { exponent, decimals } => {
+                if value == 0.0 {
+                    return "0".to_string();
+                }
+                let prefix = SI_PREFIXES
+                    .iter()
+                    .find(|&&(si, _)| si == exponent)
+                    .map_or("", |&(_, prefix)| prefix);
+                let scaled = value / 10f64.powi(exponent);
+                format!("{}{prefix}", trim_negative_zero(format!("{scaled:.decimals$}")))
+            }
+            NumberFormat::Scientific { exponent, decimals } => {
+                if value == 0.0 {
+                    return "0".to_string();
+                }
+                let mantissa = value / 10f64.powi(exponent);
+                format!("{mantissa:.decimals$}e{exponent}")
+            }
+            NumberFormat::Individual => {
+                let magnitude = value.abs();
+                let step = if magnitude > 0.0 { 10f64.powf(magnitude.log10().floor()) } else { 1.0 };
+                NumberFormat::for_magnitude(magnitude, step).format(value)
+            }
+        }
+    }
+}
+
+fn trim_negative_zero(text: String) -> String {
+    match text.strip_prefix('-') {
+        Some(rest) if rest.chars().all(|ch| ch == '0' || ch == '.') => rest.to_string(),
+        _ => text,
+    }
+}
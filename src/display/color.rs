@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+/// An sRGB color with straight (not premultiplied) alpha
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Rgba {
+    /// An opaque color
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Self { a, ..self }
+    }
+
+    pub fn channels(self) -> (u8, u8, u8) {
+        (self.r, self.g, self.b)
+    }
+
+    /// CSS color value: `#rrggbb` when opaque, `rgba()` otherwise
+    pub fn css(self) -> String {
+        match self.a {
+            255 => format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b),
+            alpha => format!("rgba({},{},{},{:.2})", self.r, self.g, self.b, alpha as f64 / 255.0),
+        }
+    }
+
+    /// This color painted over `below` ("source over" compositing)
+    pub fn over(self, below: Self) -> Self {
+        match self.a {
+            255 => self,
+            0 => below,
+            alpha => {
+                let (src, dst) = (alpha as u32, below.a as u32 * (255 - alpha as u32) / 255);
+                let a = src + dst;
+                let mix = |s: u8, d: u8| ((s as u32 * src + d as u32 * dst + a / 2) / a) as u8;
+                Self { r: mix(self.r, below.r), g: mix(self.g, below.g), b: mix(self.b, below.b), a: a as u8 }
+            }
+        }
+    }
+}
+
+/// The colors the bitmap outputs draw with, by role
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub background: Rgba,
+    /// One color per data series, the first for single series plots
+    pub series: [Rgba; 4],
+    pub grid: Rgba,
+    pub axis: Rgba,
+    /// Tick labels, titles and asymptotes
+    pub text: Rgba,
+    /// Evenly spaced stops of the color scale for heights, from low to high
+    pub colormap: &'static [Rgba],
+}
+
+impl Theme {
+    /// Light lines on black, the colors of the original fixed palette
+    pub const DARK: Self = Self {
+        background: Rgba::new(0, 0, 0),
+        series: [Rgba::new(0, 255, 0), Rgba::new(255, 0, 255), Rgba::new(0, 255, 255), Rgba::new(80, 128, 255)],
+        grid: Rgba::new(64, 64, 64),
+        axis: Rgba::new(255, 255, 0),
+        text: Rgba::new(192, 192, 192),
+        colormap: &VIRIDIS,
+    };
+
+    /// Color for a height `fraction` of the way from the lowest to the highest,
+    /// interpolated between the colormap stops
+    pub fn heat(&self, fraction: f64) -> Rgba {
+        let stops = self.colormap;
+        if stops.len() < 2 {
+            return stops.first().copied().unwrap_or(self.series[0]);
+        }
+        let position = fraction.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+        let index = (position.floor() as usize).min(stops.len() - 2);
+        let t = position - index as f64;
+        let (a, b) = (stops[index], stops[index + 1]);
+        let mix = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * t).round() as u8;
+        Rgba::new(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b))
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+/// Five stops of matplotlib's viridis scale, dark purple to yellow
+const VIRIDIS: [Rgba; 5] = [
+    Rgba::new(68, 1, 84),
+    Rgba::new(59, 82, 139),
+    Rgba::new(33, 145, 140),
+    Rgba::new(94, 201, 98),
+    Rgba::new(253, 231, 37),
+];
+
+/// Reduce an image to at most `max_colors` colors for palette based formats. Images
+/// with few enough colors keep them exactly; others are split by median cut, so busy
+/// regions of the color space get more entries. Returns the palette and the palette
+/// index of every pixel.
+pub fn quantize(pixels: &[Rgba], max_colors: usize) -> (Vec<Rgba>, Vec<usize>) {
+    let mut histogram: HashMap<Rgba, usize> = HashMap::new();
+    for &pixel in pixels {
+        *histogram.entry(pixel.with_alpha(255)).or_default() += 1;
+    }
+    let mut colors: Vec<(Rgba, usize)> = histogram.into_iter().collect();
+    colors.sort_by_key(|&(color, _)| color.channels());
+
+    let mut boxes = vec![colors];
+    while boxes.len() < max_colors.max(1) {
+        // Split the box spanning the widest range of any channel
+        let Some((index, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(index, colors)| (index, widest_channel(colors)))
+            .max_by_key(|&(_, (_, range))| range)
+            .map(|(index, (channel, _))| (index, channel))
+        else {
+            break;
+        };
+
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_by_key(|&(color, _)| channel_value(color, channel));
+        let total: usize = colors.iter().map(|&(_, count)| count).sum();
+        let mut seen = 0;
+        let median = colors
+            .iter()
+            .position(|&(_, count)| {
+                seen += count;
+                seen * 2 >= total
+            })
+            .map_or(1, |position| (position + 1).clamp(1, colors.len() - 1));
+        let upper = colors.split_off(median);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+
+    let mut palette = Vec::with_capacity(boxes.len());
+    let mut lookup = HashMap::new();
+    for colors in &boxes {
+        lookup.extend(colors.iter().map(|&(color, _)| (color, palette.len())));
+        palette.push(average(colors));
+    }
+    let indices = pixels.iter().map(|pixel| lookup[&pixel.with_alpha(255)]).collect();
+    (palette, indices)
+}
+
+/// The channel (0 red, 1 green, 2 blue) with the largest spread in a box, and that spread
+fn widest_channel(colors: &[(Rgba, usize)]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let values = colors.iter().map(|&(color, _)| channel_value(color, channel));
+            let (min, max) = values.fold((u8::MAX, u8::MIN), |(min, max), v| (min.min(v), max.max(v)));
+            (channel, max.saturating_sub(min))
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+fn channel_value(color: Rgba, channel: usize) -> u8 {
+    match channel {
+        0 => color.r,
+        1 => color.g,
+        _ => color.b,
+    }
+}
+
+/// Mean of the colors in a box, weighted by how many pixels have each
+fn average(colors: &[(Rgba, usize)]) -> Rgba {
+    let total = colors.iter().map(|&(_, count)| count).sum::<usize>().max(1);
+    let mean = |channel| {
+        let sum: usize = colors.iter().map(|&(color, count)| channel_value(color, channel) as usize * count).sum();
+        ((sum + total / 2) / total) as u8
+    };
+    Rgba::new(mean(0), mean(1), mean(2))
+}
@@ -0,0 +1,667 @@
+/// Most input bytes one block covers, so a block that does not compress can be
+/// written as a single stored block instead
+const BLOCK_SIZE: usize = 65535;
+/// Distance back the compressor may refer to
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// Earlier positions with the same three byte prefix tried per match search
+const MAX_CHAIN: usize = 128;
+const HASH_BITS: u32 = 15;
+
+/// Base match length and extra bits of length codes 257 to 285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+/// Base distance and extra bits of distance codes 0 to 29
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+/// Order the code length code lengths are sent in a dynamic block header
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const END_OF_BLOCK: usize = 256;
+
+/// zlib stream (RFC 1950) of `data` compressed with deflate (RFC 1951). Repeats are
+/// found with hash chains over a 32 KiB window, and every block is sent with
+/// whichever of dynamic Huffman codes, the fixed codes or no compression is smallest.
+pub fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut out = BitWriter::default();
+    // 32 KiB window, deflate, default compression level; the header is a multiple of 31
+    out.bytes.extend_from_slice(&[0x78, 0x9c]);
+
+    let mut matcher = Matcher::new(data);
+    let mut start = 0;
+    loop {
+        let end = (start + BLOCK_SIZE).min(data.len());
+        let symbols = matcher.symbols(start, end);
+        write_block(&mut out, &symbols, &data[start..end], end == data.len());
+        if end == data.len() {
+            break;
+        }
+        start = end;
+    }
+
+    let mut stream = out.finish();
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+/// Adler-32 checksum that closes a zlib stream
+pub fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/// A byte sent as is, or a copy of `length` bytes from `distance` bytes back
+#[derive(Clone, Copy, Debug)]
+enum Symbol {
+    Literal(u8),
+    Copy { length: u16, distance: u16 },
+}
+
+/// LZ77 match finder remembering, for every position seen so far, the previous
+/// position starting with the same three bytes
+struct Matcher<'a> {
+    data: &'a [u8],
+    head: Vec<Option<usize>>,
+    previous: Vec<Option<usize>>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, head: vec![None; 1 << HASH_BITS], previous: vec![None; WINDOW_SIZE] }
+    }
+
+    fn hash(&self, position: usize) -> usize {
+        let bytes = &self.data[position..position + MIN_MATCH];
+        let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        (value.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn insert(&mut self, position: usize) {
+        if position + MIN_MATCH <= self.data.len() {
+            let hash = self.hash(position);
+            self.previous[position % WINDOW_SIZE] = self.head[hash];
+            self.head[hash] = Some(position);
+        }
+    }
+
+    /// Longest earlier match for the bytes at `position`, not reaching past `end`
+    fn longest_match(&self, position: usize, end: usize) -> Option<(usize, usize)> {
+        let limit = (end - position).min(MAX_MATCH);
+        if limit < MIN_MATCH {
+            return None;
+        }
+        let data = self.data;
+        let mut best: Option<(usize, usize)> = None;
+        let mut candidate = self.head[self.hash(position)];
+        for _ in 0..MAX_CHAIN {
+            let Some(earlier) = candidate.filter(|&earlier| position - earlier <= WINDOW_SIZE) else {
+                break;
+            };
+            let length = data[earlier..earlier + limit]
+                .iter()
+                .zip(&data[position..position + limit])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if length >= MIN_MATCH && best.is_none_or(|(best, _)| length > best) {
+                best = Some((length, position - earlier));
+                if length == limit {
+                    break;
+                }
+            }
+            candidate = self.previous[earlier % WINDOW_SIZE].filter(|&next| next < earlier);
+        }
+        best
+    }
+
+    /// Literals and copies for the bytes from `start` to `end`. A match is only
+    /// taken when the next position does not start a longer one (lazy matching).
+    fn symbols(&mut self, start: usize, end: usize) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        let mut position = start;
+        while position < end {
+            let found = self.longest_match(position, end);
+            self.insert(position);
+            let Some((length, distance)) = found else {
+                symbols.push(Symbol::Literal(self.data[position]));
+                position += 1;
+                continue;
+            };
+            if length < MAX_MATCH && position + 1 < end {
+                if let Some((next, _)) = self.longest_match(position + 1, end) {
+                    if next > length {
+                        symbols.push(Symbol::Literal(self.data[position]));
+                        position += 1;
+                        continue;
+                    }
+                }
+            }
+            symbols.push(Symbol::Copy { length: length as u16, distance: distance as u16 });
+            for skipped in position + 1..position + length {
+                self.insert(skipped);
+            }
+            position += length;
+        }
+        symbols
+    }
+}
+
+/// Code and extra bits (count, value) of a copy length
+fn length_code(length: u16) -> (usize, u8, u16) {
+    let index = LENGTH_BASE.partition_point(|&base| base <= length) - 1;
+    (257 + index, LENGTH_EXTRA[index], length - LENGTH_BASE[index])
+}
+
+/// Code and extra bits (count, value) of a copy distance
+fn distance_code(distance: u16) -> (usize, u8, u16) {
+    let index = DISTANCE_BASE.partition_point(|&base| base <= distance) - 1;
+    (index, DISTANCE_EXTRA[index], distance - DISTANCE_BASE[index])
+}
+
+/// Write one block in the cheapest of the three block types
+fn write_block(out: &mut BitWriter, symbols: &[Symbol], raw: &[u8], last: bool) {
+    let mut literal_counts = vec![0usize; 286];
+    let mut distance_counts = vec![0usize; 30];
+    literal_counts[END_OF_BLOCK] = 1;
+    for &symbol in symbols {
+        match symbol {
+            Symbol::Literal(byte) => literal_counts[byte as usize] += 1,
+            Symbol::Copy { length, distance } => {
+                literal_counts[length_code(length).0] += 1;
+                distance_counts[distance_code(distance).0] += 1;
+            }
+        }
+    }
+
+    let dynamic = DynamicCodes::new(&literal_counts, &distance_counts);
+    let (fixed_literals, fixed_distances) = fixed_lengths();
+    let dynamic_bits = dynamic.header_bits() + data_bits(symbols, &dynamic.literal_lengths, &dynamic.distance_lengths);
+    let fixed_bits = data_bits(symbols, &fixed_literals, &fixed_distances);
+    let stored_bits = 8 * (raw.len() + 5);
+
+    out.write_bits(u32::from(last), 1);
+    if stored_bits <= fixed_bits.min(dynamic_bits) {
+        out.write_bits(0, 2);
+        out.align();
+        let length = raw.len() as u16;
+        out.bytes.extend_from_slice(&length.to_le_bytes());
+        out.bytes.extend_from_slice(&(!length).to_le_bytes());
+        out.bytes.extend_from_slice(raw);
+    } else if fixed_bits <= dynamic_bits {
+        out.write_bits(1, 2);
+        write_symbols(out, symbols, &fixed_literals, &fixed_distances);
+    } else {
+        out.write_bits(2, 2);
+        dynamic.write_header(out);
+        write_symbols(out, symbols, &dynamic.literal_lengths, &dynamic.distance_lengths);
+    }
+}
+
+/// Code lengths of the fixed Huffman codes for literals/lengths and distances
+fn fixed_lengths() -> (Vec<u8>, Vec<u8>) {
+    let literals = (0..288)
+        .map(|symbol| match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        })
+        .collect();
+    (literals, vec![5; 30])
+}
+
+/// Size in bits of the symbols and their extra bits, end of block included
+fn data_bits(symbols: &[Symbol], literal_lengths: &[u8], distance_lengths: &[u8]) -> usize {
+    let mut bits = literal_lengths[END_OF_BLOCK] as usize;
+    for &symbol in symbols {
+        bits += match symbol {
+            Symbol::Literal(byte) => literal_lengths[byte as usize] as usize,
+            Symbol::Copy { length, distance } => {
+                let (length, length_extra, _) = length_code(length);
+                let (distance, distance_extra, _) = distance_code(distance);
+                (literal_lengths[length] + length_extra + distance_lengths[distance] + distance_extra) as usize
+            }
+        };
+    }
+    bits
+}
+
+fn write_symbols(out: &mut BitWriter, symbols: &[Symbol], literal_lengths: &[u8], distance_lengths: &[u8]) {
+    let literal_codes = canonical_codes(literal_lengths);
+    let distance_codes = canonical_codes(distance_lengths);
+    for &symbol in symbols {
+        match symbol {
+            Symbol::Literal(byte) => out.write_bits(literal_codes[byte as usize], literal_lengths[byte as usize]),
+            Symbol::Copy { length, distance } => {
+                let (code, extra_bits, extra) = length_code(length);
+                out.write_bits(literal_codes[code], literal_lengths[code]);
+                out.write_bits(extra as u32, extra_bits);
+                let (code, extra_bits, extra) = distance_code(distance);
+                out.write_bits(distance_codes[code], distance_lengths[code]);
+                out.write_bits(extra as u32, extra_bits);
+            }
+        }
+    }
+    out.write_bits(literal_codes[END_OF_BLOCK], literal_lengths[END_OF_BLOCK]);
+}
+
+/// Huffman codes fitted to one block, and the run length encoded code lengths
+/// that describe them in the block header
+struct DynamicCodes {
+    literal_lengths: Vec<u8>,
+    distance_lengths: Vec<u8>,
+    /// Code length symbols (0 to 18) with their extra bits (count, value)
+    length_symbols: Vec<(usize, u8, u8)>,
+    length_code_lengths: Vec<u8>,
+    /// Code length code lengths sent, in `CODE_LENGTH_ORDER`
+    length_code_count: usize,
+}
+
+impl DynamicCodes {
+    fn new(literal_counts: &[usize], distance_counts: &[usize]) -> Self {
+        let literal_lengths = huffman_lengths(literal_counts, 15);
+        let distance_lengths = huffman_lengths(distance_counts, 15);
+
+        let literal_count = 257 + literal_lengths[257..].iter().rposition(|&length| length > 0).map_or(0, |last| last + 1);
+        let distance_count = distance_lengths.iter().rposition(|&length| length > 0).map_or(1, |last| last + 1);
+        let all_lengths = [&literal_lengths[..literal_count], &distance_lengths[..distance_count]].concat();
+        let length_symbols = run_length_encode(&all_lengths);
+
+        let mut length_symbol_counts = vec![0usize; 19];
+        for &(symbol, _, _) in &length_symbols {
+            length_symbol_counts[symbol] += 1;
+        }
+        let length_code_lengths = huffman_lengths(&length_symbol_counts, 7);
+        let length_code_count = CODE_LENGTH_ORDER
+            .iter()
+            .rposition(|&symbol| length_code_lengths[symbol] > 0)
+            .map_or(4, |last| (last + 1).max(4));
+
+        Self {
+            literal_lengths: literal_lengths[..literal_count].to_vec(),
+            distance_lengths: distance_lengths[..distance_count].to_vec(),
+            length_symbols,
+            length_code_lengths,
+            length_code_count,
+        }
+    }
+
+    fn header_bits(&self) -> usize {
+        let symbols: usize = self
+            .length_symbols
+            .iter()
+            .map(|&(symbol, extra_bits, _)| (self.length_code_lengths[symbol] + extra_bits) as usize)
+            .sum();
+        5 + 5 + 4 + 3 * self.length_code_count + symbols
+    }
+
+    fn write_header(&self, out: &mut BitWriter) {
+        out.write_bits((self.literal_lengths.len() - 257) as u32, 5);
+        out.write_bits((self.distance_lengths.len() - 1) as u32, 5);
+        out.write_bits((self.length_code_count - 4) as u32, 4);
+        for &symbol in &CODE_LENGTH_ORDER[..self.length_code_count] {
+            out.write_bits(self.length_code_lengths[symbol] as u32, 3);
+        }
+        let codes = canonical_codes(&self.length_code_lengths);
+        for &(symbol, extra_bits, extra) in &self.length_symbols {
+            out.write_bits(codes[symbol], self.length_code_lengths[symbol]);
+            out.write_bits(extra as u32, extra_bits);
+        }
+    }
+}
+
+/// Code lengths as code length symbols: 16 repeats the previous length 3 to 6
+/// times, 17 and 18 give runs of 3 to 10 and 11 to 138 zeros
+fn run_length_encode(lengths: &[u8]) -> Vec<(usize, u8, u8)> {
+    let mut symbols = Vec::new();
+    let mut index = 0;
+    while index < lengths.len() {
+        let length = lengths[index];
+        let run = lengths[index..].iter().take_while(|&&other| other == length).count();
+        if length == 0 && run >= 11 {
+            let run = run.min(138);
+            symbols.push((18, 7, (run - 11) as u8));
+            index += run;
+        } else if length == 0 && run >= 3 {
+            symbols.push((17, 3, (run - 3) as u8));
+            index += run;
+        } else if length > 0 && run >= 4 {
+            symbols.push((length as usize, 0, 0));
+            let repeats = (run - 1).min(6);
+            symbols.push((16, 2, (repeats - 3) as u8));
+            index += 1 + repeats;
+        } else {
+            symbols.push((length as usize, 0, 0));
+            index += 1;
+        }
+    }
+    symbols
+}
+
+/// Huffman code lengths for symbol counts, none longer than `limit`. Unused
+/// symbols get no code; at least two symbols always get one, as a lone code
+/// would be incomplete. Codes too long are avoided by flattening the counts.
+fn huffman_lengths(counts: &[usize], limit: u8) -> Vec<u8> {
+    let mut counts = counts.to_vec();
+    for index in 0..2 {
+        if counts.iter().filter(|&&count| count > 0).count() < 2 && counts[index] == 0 {
+            counts[index] = 1;
+        }
+    }
+
+    loop {
+        let lengths = unlimited_huffman_lengths(&counts);
+        if lengths.iter().all(|&length| length <= limit) {
+            return lengths;
+        }
+        for count in counts.iter_mut().filter(|count| **count > 0) {
+            *count = (*count >> 1) | 1;
+        }
+    }
+}
+
+/// Depth of every used symbol in a Huffman tree, built by repeatedly joining the
+/// two least frequent subtrees
+fn unlimited_huffman_lengths(counts: &[usize]) -> Vec<u8> {
+    use std::{cmp::Reverse, collections::BinaryHeap};
+
+    // Nodes below `counts.len()` are symbols, the rest joined subtrees
+    let mut parents: Vec<usize> = vec![usize::MAX; counts.len()];
+    let mut heap: BinaryHeap<Reverse<(usize, usize)>> = counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count > 0)
+        .map(|(symbol, &count)| Reverse((count, symbol)))
+        .collect();
+    while heap.len() > 1 {
+        let (Some(Reverse((a_count, a))), Some(Reverse((b_count, b)))) = (heap.pop(), heap.pop()) else {
+            break;
+        };
+        let node = parents.len();
+        parents.push(usize::MAX);
+        parents[a] = node;
+        parents[b] = node;
+        heap.push(Reverse((a_count + b_count, node)));
+    }
+
+    (0..counts.len())
+        .map(|symbol| {
+            if counts[symbol] == 0 {
+                return 0;
+            }
+            let (mut depth, mut node) = (0, symbol);
+            while parents[node] != usize::MAX {
+                node = parents[node];
+                depth += 1;
+            }
+            depth
+        })
+        .collect()
+}
+
+/// Canonical Huffman codes for code lengths, bit reversed since deflate sends
+/// codes starting with their most significant bit
+fn canonical_codes(lengths: &[u8]) -> Vec<u32> {
+    let max_length = lengths.iter().copied().max().unwrap_or(0) as usize;
+    let mut length_counts = vec![0u32; max_length + 1];
+    for &length in lengths.iter().filter(|&&length| length > 0) {
+        length_counts[length as usize] += 1;
+    }
+    let mut next_code = vec![0u32; max_length + 1];
+    for length in 1..=max_length {
+        next_code[length] = (next_code[length - 1] + length_counts[length - 1]) << 1;
+    }
+
+    lengths
+        .iter()
+        .map(|&length| {
+            if length == 0 {
+                return 0;
+            }
+            let code = next_code[length as usize];
+            next_code[length as usize] += 1;
+            code.reverse_bits() >> (32 - length)
+        })
+        .collect()
+}
+
+/// Packs values into bytes starting from the least significant bit
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    count: u8,
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, value: u32, count: u8) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Pad with zero bits to the next byte boundary
+    fn align(&mut self) {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer = 0;
+            self.count = 0;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Reads bits from the least significant end of each byte, as deflate packs them
+    struct BitReader<'a> {
+        data: &'a [u8],
+        position: usize,
+    }
+
+    impl BitReader<'_> {
+        fn bit(&mut self) -> u32 {
+            let bit = (self.data[self.position / 8] >> (self.position % 8)) & 1;
+            self.position += 1;
+            bit as u32
+        }
+
+        fn bits(&mut self, count: u8) -> u32 {
+            (0..count).fold(0, |value, i| value | self.bit() << i)
+        }
+
+        fn align(&mut self) {
+            self.position = self.position.div_ceil(8) * 8;
+        }
+    }
+
+    /// Canonical Huffman decoding table: how many codes have each length, and the
+    /// symbols in code order
+    struct Decoder {
+        counts: [u16; 16],
+        symbols: Vec<u16>,
+    }
+
+    impl Decoder {
+        fn new(lengths: &[u8]) -> Self {
+            let mut counts = [0u16; 16];
+            for &length in lengths {
+                counts[length as usize] += 1;
+            }
+            counts[0] = 0;
+            let mut symbols: Vec<u16> = (0..lengths.len() as u16).filter(|&s| lengths[s as usize] > 0).collect();
+            symbols.sort_by_key(|&s| lengths[s as usize]);
+            Self { counts, symbols }
+        }
+
+        fn decode(&self, reader: &mut BitReader) -> usize {
+            let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+            for length in 1..16 {
+                code |= reader.bit() as i32;
+                let count = self.counts[length] as i32;
+                if code - first < count {
+                    return self.symbols[(index + code - first) as usize] as usize;
+                }
+                index += count;
+                first = (first + count) << 1;
+                code <<= 1;
+            }
+            panic!("invalid Huffman code");
+        }
+    }
+
+    /// Decompress a zlib stream, checking its header and Adler-32, and return the
+    /// data with the type of every block
+    pub(crate) fn inflate(stream: &[u8]) -> (Vec<u8>, Vec<u32>) {
+        assert_eq!(stream[0] & 0x0f, 8, "deflate method");
+        assert_eq!(u16::from_be_bytes([stream[0], stream[1]]) % 31, 0, "header check bits");
+        let mut reader = BitReader { data: &stream[..stream.len() - 4], position: 16 };
+        let (mut data, mut types) = (Vec::new(), Vec::new());
+        loop {
+            let last = reader.bit() == 1;
+            let kind = reader.bits(2);
+            types.push(kind);
+            match kind {
+                0 => {
+                    reader.align();
+                    let start = reader.position / 8;
+                    let length = u16::from_le_bytes([stream[start], stream[start + 1]]);
+                    let complement = u16::from_le_bytes([stream[start + 2], stream[start + 3]]);
+                    assert_eq!(length, !complement);
+                    data.extend_from_slice(&stream[start + 4..start + 4 + length as usize]);
+                    reader.position = (start + 4 + length as usize) * 8;
+                }
+                1 => {
+                    let (literals, distances) = fixed_lengths();
+                    inflate_block(&mut reader, &mut data, &Decoder::new(&literals), &Decoder::new(&distances));
+                }
+                2 => {
+                    let literal_count = reader.bits(5) as usize + 257;
+                    let distance_count = reader.bits(5) as usize + 1;
+                    let code_length_count = reader.bits(4) as usize + 4;
+                    let mut code_length_lengths = [0u8; 19];
+                    for &symbol in &CODE_LENGTH_ORDER[..code_length_count] {
+                        code_length_lengths[symbol] = reader.bits(3) as u8;
+                    }
+                    let code_lengths = Decoder::new(&code_length_lengths);
+                    let mut lengths = Vec::new();
+                    while lengths.len() < literal_count + distance_count {
+                        match code_lengths.decode(&mut reader) {
+                            symbol @ 0..=15 => lengths.push(symbol as u8),
+                            16 => {
+                                let previous = *lengths.last().expect("repeat with nothing before");
+                                let repeat = 3 + reader.bits(2) as usize;
+                                lengths.extend(std::iter::repeat_n(previous, repeat));
+                            }
+                            17 => lengths.extend(std::iter::repeat_n(0, 3 + reader.bits(3) as usize)),
+                            _ => lengths.extend(std::iter::repeat_n(0, 11 + reader.bits(7) as usize)),
+                        }
+                    }
+                    let literals = Decoder::new(&lengths[..literal_count]);
+                    let distances = Decoder::new(&lengths[literal_count..]);
+                    inflate_block(&mut reader, &mut data, &literals, &distances);
+                }
+                _ => panic!("reserved block type"),
+            }
+            if last {
+                break;
+            }
+        }
+        let checksum = u32::from_be_bytes(stream[stream.len() - 4..].try_into().unwrap());
+        assert_eq!(checksum, adler32(&data), "Adler-32 of the data");
+        (data, types)
+    }
+
+    fn inflate_block(reader: &mut BitReader, data: &mut Vec<u8>, literals: &Decoder, distances: &Decoder) {
+        loop {
+            let symbol = literals.decode(reader);
+            match symbol {
+                0..=255 => data.push(symbol as u8),
+                END_OF_BLOCK => return,
+                _ => {
+                    let index = symbol - 257;
+                    let length = LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index]) as usize;
+                    let index = distances.decode(reader);
+                    let distance = DISTANCE_BASE[index] as usize + reader.bits(DISTANCE_EXTRA[index]) as usize;
+                    for _ in 0..length {
+                        data.push(data[data.len() - distance]);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Bytes from a linear congruential generator, which deflate cannot shrink
+    fn noise(length: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491u32;
+        (0..length)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn adler32_known_values() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"a"), 0x0062_0062);
+        assert_eq!(adler32(b"123456789"), 0x091e_01de);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        // Long enough that the sums are reduced modulo 65521 along the way
+        assert_eq!(adler32(&[0xff; 100_000]), 0x149a_302c);
+    }
+
+    #[test]
+    fn stored_blocks_round_trip() {
+        let data = noise(1000);
+        let (inflated, types) = inflate(&zlib(&data));
+        assert_eq!(types, [0]);
+        assert_eq!(inflated, data);
+    }
+
+    #[test]
+    fn fixed_huffman_block_round_trips() {
+        let data = b"a plot, a plot, a plot of sin(x)";
+        let (inflated, types) = inflate(&zlib(data));
+        assert_eq!(types, [1]);
+        assert_eq!(inflated, data);
+    }
+
+    #[test]
+    fn dynamic_huffman_blocks_round_trip() {
+        let data: Vec<u8> = (0..200_000u64).map(|i| b"termplot"[(i * i % 7) as usize]).collect();
+        let (inflated, types) = inflate(&zlib(&data));
+        assert!(types.len() > 1, "more input than one block covers");
+        assert!(types.contains(&2));
+        assert_eq!(inflated, data);
+    }
+
+    #[test]
+    fn empty_input_round_trips() {
+        assert_eq!(inflate(&zlib(b"")).0, b"");
+    }
+}
@@ -0,0 +1,538 @@
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::{
+    axis::NumberFormat, PlotBounds, PlotSettings, PlotStyle, Point3D, Rgba, Segments, Stroke, SurfaceBounds, Theme,
+};
+
+/// Room around the plot for tick labels and the title, shrunk for small images
+const MARGIN: usize = 50;
+
+/// What a path of a figure draws, which decides how it looks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathKind {
+    Grid,
+    Tick,
+    Axis,
+    Asymptote,
+    Series,
+    /// Dots on the samples
+    Markers,
+}
+
+impl PathKind {
+    pub fn class(self) -> &'static str {
+        match self {
+            Self::Grid => "grid",
+            Self::Tick => "tick",
+            Self::Axis => "axis",
+            Self::Asymptote => "asymptote",
+            Self::Series => "series",
+            Self::Markers => "markers",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextKind {
+    TickLabel,
+    Title,
+    Label,
+}
+
+impl TextKind {
+    pub fn class(self) -> &'static str {
+        match self {
+            Self::TickLabel => "tick-label",
+            Self::Title => "title",
+            Self::Label => "label",
+        }
+    }
+
+    /// Font size in pixels (or points)
+    pub fn size(self) -> f64 {
+        match self {
+            Self::TickLabel => 10.0,
+            Self::Title => 16.0,
+            Self::Label => 12.0,
+        }
+    }
+
+    /// Tick labels are monospaced so that columns of numbers line up
+    pub fn monospace(self) -> bool {
+        self == Self::TickLabel
+    }
+}
+
+/// Which point of a text its position is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    Middle,
+    End,
+}
+
+impl Anchor {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Middle => "middle",
+            Self::End => "end",
+        }
+    }
+
+    /// How much of the text's width lies before the anchor point
+    pub fn fraction(self) -> f64 {
+        match self {
+            Self::Middle => 0.5,
+            Self::End => 1.0,
+        }
+    }
+}
+
+/// How the paths of one kind are stroked
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathStyle {
+    pub color: Rgba,
+    pub width: f64,
+    /// Alternating dash and gap lengths, empty for a solid line
+    pub dash: Vec<f64>,
+    /// Round joins and caps rather than mitered joins and flat ends
+    pub round: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Element {
+    /// Polylines with the style of their kind. A polyline of a single point is a dot.
+    Path(PathKind, Vec<Vec<(f64, f64)>>),
+    /// Filled cells of a surface in drawing order, back to front
+    Surface(Vec<(Vec<(f64, f64)>, Rgba)>),
+    Text {
+        kind: TextKind,
+        /// Anchor point on the baseline
+        position: (f64, f64),
+        anchor: Anchor,
+        /// Read from bottom to top, as a Y axis label
+        vertical: bool,
+        text: String,
+    },
+}
+
+/// A data point and where it is drawn, for the readout of interactive outputs
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub position: (f64, f64),
+    /// X and Y, and Z on surfaces
+    pub values: Vec<f64>,
+}
+
+/// A plot laid out for the vector outputs: what to draw and where, in image
+/// coordinates with the origin at the top left and Y growing downwards. The SVG,
+/// PDF and PostScript writers only translate it to their format.
+#[derive(Clone, Debug)]
+pub struct Figure {
+    pub width: usize,
+    pub height: usize,
+    pub title: String,
+    /// A sentence saying what the plot shows, for screen readers and metadata
+    pub description: String,
+    pub theme: Theme,
+    pub elements: Vec<Element>,
+    /// The data points in view
+    pub samples: Vec<Sample>,
+    stroke: Stroke,
+    /// (margin, plot_width, plot_height)
+    layout: (usize, usize, usize),
+}
+
+impl Figure {
+    fn new(width: usize, height: usize, settings: &PlotSettings, description: String) -> Self {
+        // The margin never takes more than half of either side, so tiny images still get a plot area
+        let margin = MARGIN.min(width.min(height) / 4);
+        Self {
+            width,
+            height,
+            title: settings.title.clone().unwrap_or_else(|| "Plot".to_string()),
+            description,
+            theme: settings.theme,
+            elements: Vec::new(),
+            samples: Vec::new(),
+            stroke: settings.stroke(),
+            layout: (margin, (width - 2 * margin).max(1), (height - 2 * margin).max(1)),
+        }
+    }
+
+    pub fn plot(
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Self {
+        let bounds = PlotBounds::for_plot(&x_result.0, &y_result.0, settings);
+        let description = match &bounds {
+            Some(bounds) => format!(
+                "Plot of {} samples, X from {}, Y from {}",
+                y_result.0.len(),
+                describe_range(bounds.x_min, bounds.x_max),
+                describe_range(bounds.y_min, bounds.y_max)
+            ),
+            None => "No finite values to plot".to_string(),
+        };
+
+        let mut figure = Self::new(width, height, settings, description);
+        if let Some(bounds) = bounds {
+            figure.axes(&bounds);
+            let points: Vec<(f64, f64)> = x_result.0.iter().copied().zip(y_result.0.iter().copied()).collect();
+            let segments = Segments::split(&points, &bounds);
+            figure.series(&segments, &bounds, settings.asymptotes, settings.style);
+        }
+        figure.labels(settings, false);
+        figure
+    }
+
+    pub fn parametric(result: &Parametric2DResult, width: usize, height: usize, settings: &PlotSettings) -> Self {
+        let bounds = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings);
+        let description = match &bounds {
+            Some(bounds) => format!(
+                "Parametric curve of {} samples, X from {}, Y from {}",
+                result.len(),
+                describe_range(bounds.x_min, bounds.x_max),
+                describe_range(bounds.y_min, bounds.y_max)
+            ),
+            None => "No finite parametric values to plot".to_string(),
+        };
+
+        let mut figure = Self::new(width, height, settings, description);
+        if let Some(bounds) = bounds {
+            figure.axes(&bounds);
+            let points: Vec<(f64, f64)> = result
+                .x_values
+                .iter()
+                .copied()
+                .zip(result.y_values.iter().copied())
+                .collect();
+            let segments = Segments::split_curve(&points, &bounds);
+            figure.series(&segments, &bounds, false, settings.style);
+        }
+        figure.labels(settings, false);
+        figure
+    }
+
+    pub fn surface(result: &Expression3dResult, width: usize, height: usize, settings: &PlotSettings) -> Self {
+        let bounds = SurfaceBounds::for_surface(result, settings);
+        let description = match &bounds {
+            Some(bounds) => format!(
+                "Surface over a {} by {} grid, X from {}, Y from {}, Z from {}",
+                result.x_len(),
+                result.y_len(),
+                describe_range(bounds.x_min, bounds.x_max),
+                describe_range(bounds.y_min, bounds.y_max),
+                describe_range(bounds.z_min, bounds.z_max)
+            ),
+            None => "No finite 3D surface values to plot".to_string(),
+        };
+
+        let mut figure = Self::new(width, height, settings, description);
+        if let Some(bounds) = bounds {
+            figure.surface_cells(result, &bounds);
+            figure.surface_axes(&bounds);
+        }
+        figure.labels(settings, true);
+        figure
+    }
+
+    pub fn style(&self, kind: PathKind) -> PathStyle {
+        let theme = &self.theme;
+        let (color, width, dash, round) = match kind {
+            PathKind::Grid => (theme.grid, 1.0, Vec::new(), false),
+            PathKind::Tick => (theme.axis, 1.0, Vec::new(), false),
+            PathKind::Axis => (theme.axis, 2.0, Vec::new(), false),
+            PathKind::Asymptote => (theme.text.with_alpha(153).over(theme.background), 1.0, vec![6.0, 4.0], false),
+            PathKind::Series => (theme.series[0], self.stroke.width, self.stroke.dash.clone(), true),
+            PathKind::Markers => (theme.series[0], self.stroke.width + 2.0, Vec::new(), true),
+        };
+        PathStyle { color, width, dash, round }
+    }
+
+    fn text(&mut self, kind: TextKind, position: (f64, f64), anchor: Anchor, text: &str) {
+        let text = text.to_string();
+        self.elements.push(Element::Text { kind, position, anchor, vertical: false, text });
+    }
+
+    /// Grid lines at the major ticks, minor tick marks, the two axes and the tick labels
+    fn axes(&mut self, bounds: &PlotBounds) {
+        let (margin, plot_width, plot_height) = self.layout;
+        let x_axis = bounds.x_axis(6);
+        let y_axis = bounds.y_axis(6);
+        let (left, right) = (margin as f64, (margin + plot_width) as f64);
+        let (top, bottom) = (margin as f64, (margin + plot_height) as f64);
+        let to_x = |fraction: f64| left + fraction * plot_width as f64;
+        let to_y = |fraction: f64| top + (1.0 - fraction) * plot_height as f64;
+
+        let mut grid: Vec<Vec<(f64, f64)>> = x_axis.interior().map(|f| vec![(to_x(f), top), (to_x(f), bottom)]).collect();
+        grid.extend(y_axis.interior().map(|f| vec![(left, to_y(f)), (right, to_y(f))]));
+        if !grid.is_empty() {
+            self.elements.push(Element::Path(PathKind::Grid, grid));
+        }
+
+        let mut ticks: Vec<Vec<(f64, f64)>> = x_axis.minor.iter().map(|&f| vec![(to_x(f), bottom), (to_x(f), bottom - 4.0)]).collect();
+        ticks.extend(y_axis.minor.iter().map(|&f| vec![(left, to_y(f)), (left + 4.0, to_y(f))]));
+        if !ticks.is_empty() {
+            self.elements.push(Element::Path(PathKind::Tick, ticks));
+        }
+
+        self.elements.push(Element::Path(PathKind::Axis, vec![vec![(left, top), (left, bottom), (right, bottom)]]));
+
+        for tick in &y_axis.major {
+            self.text(TextKind::TickLabel, (left - 5.0, to_y(tick.fraction) + 3.0), Anchor::End, &tick.label);
+        }
+        for tick in &x_axis.major {
+            self.text(TextKind::TickLabel, (to_x(tick.fraction), bottom + 15.0), Anchor::Middle, &tick.label);
+        }
+    }
+
+    /// The series as polylines through the consecutive samples of each piece, clipped
+    /// to the window and broken wherever it leaves it, a dot on every visible sample
+    /// (as the plot style asks) and optionally the asymptotes
+    fn series(&mut self, segments: &Segments, bounds: &PlotBounds, asymptotes: bool, style: PlotStyle) {
+        let (margin, plot_width, plot_height) = self.layout;
+        let to_image = |(x, y): (f64, f64)| {
+            (
+                margin as f64 + bounds.x_fraction(x) * plot_width as f64,
+                margin as f64 + (1.0 - bounds.y_fraction(y)) * plot_height as f64,
+            )
+        };
+
+        if asymptotes && !segments.asymptotes.is_empty() {
+            let lines = segments
+                .asymptotes
+                .iter()
+                .map(|&x_val| {
+                    let x = margin as f64 + bounds.x_fraction(x_val) * plot_width as f64;
+                    vec![(x, margin as f64), (x, (margin + plot_height) as f64)]
+                })
+                .collect();
+            self.elements.push(Element::Path(PathKind::Asymptote, lines));
+        }
+
+        let mut polylines: Vec<Vec<(f64, f64)>> = Vec::new();
+        if style != PlotStyle::Points {
+            for piece in &segments.pieces {
+                let mut pen = None;
+                for pair in piece.windows(2) {
+                    let Some((start, end)) = bounds.clip_segment(pair[0], pair[1]) else {
+                        pen = None;
+                        continue;
+                    };
+                    let (start, end) = (to_image(start), to_image(end));
+                    match polylines.last_mut() {
+                        Some(polyline) if pen == Some(start) => polyline.push(end),
+                        _ => polylines.push(vec![start, end]),
+                    }
+                    pen = Some(end);
+                }
+            }
+        }
+        if !polylines.is_empty() {
+            self.elements.push(Element::Path(PathKind::Series, polylines));
+        }
+
+        self.samples = segments
+            .pieces
+            .iter()
+            .flatten()
+            .filter(|&&(x, y)| bounds.contains(x, y))
+            .map(|&(x, y)| Sample { position: to_image((x, y)), values: vec![x, y] })
+            .collect();
+
+        let dots: Vec<Vec<(f64, f64)>> = segments
+            .pieces
+            .iter()
+            .filter(|piece| style != PlotStyle::Lines || piece.len() == 1)
+            .flatten()
+            .filter(|&&(x, y)| bounds.contains(x, y))
+            .map(|&point| vec![to_image(point)])
+            .collect();
+        if !dots.is_empty() {
+            self.elements.push(Element::Path(PathKind::Markers, dots));
+        }
+    }
+
+    /// Image position of a point of the surface box. The isometric box reaches from a
+    /// tenth of the plot height above the plot area down to 70% of it, which the
+    /// bitmaps clamp; here it is moved down to sit inside the area instead.
+    fn isometric(&self, point: &Point3D, bounds: &SurfaceBounds) -> (f64, f64) {
+        let (margin, plot_width, plot_height) = self.layout;
+        let (x, y) = point.isometric(plot_width as f64, plot_height as f64, bounds);
+        (margin as f64 + x, margin as f64 + y + 0.15 * plot_height as f64)
+    }
+
+    /// One filled quadrilateral per grid cell whose corners are all defined and in
+    /// view, colored by the cell's mean height and ordered from the back corner of
+    /// the box to the front so nearer cells cover farther ones. Every grid point in
+    /// view is also a sample.
+    fn surface_cells(&mut self, result: &Expression3dResult, bounds: &SurfaceBounds) {
+        let flat = bounds.z_max - bounds.z_min <= f64::EPSILON;
+        let corner = |x_idx: usize, y_idx: usize| {
+            let point = Point3D::new(result.x_values[x_idx], result.y_values[y_idx], result.get_z(x_idx, y_idx)?);
+            bounds.contains(&point).then_some(point)
+        };
+
+        for y_idx in 0..result.y_len() {
+            for x_idx in 0..result.x_len() {
+                if let Some(point) = corner(x_idx, y_idx) {
+                    let sample = Sample { position: self.isometric(&point, bounds), values: vec![point.x, point.y, point.z] };
+                    self.samples.push(sample);
+                }
+            }
+        }
+
+        let mut cells = Vec::new();
+        for y_idx in 0..result.y_len().saturating_sub(1) {
+            for x_idx in 0..result.x_len().saturating_sub(1) {
+                let corners = [(x_idx, y_idx), (x_idx + 1, y_idx), (x_idx + 1, y_idx + 1), (x_idx, y_idx + 1)];
+                let Some(points) = corners.iter().map(|&(x, y)| corner(x, y)).collect::<Option<Vec<Point3D>>>() else {
+                    continue;
+                };
+                // The viewer looks from the low X, low Y corner
+                let depth: f64 = points
+                    .iter()
+                    .map(|point| {
+                        bounds.x_scale.fraction(point.x, bounds.x_min, bounds.x_max)
+                            + bounds.y_scale.fraction(point.y, bounds.y_min, bounds.y_max)
+                    })
+                    .sum();
+                let height = points.iter().map(|point| bounds.z_fraction(point.z)).sum::<f64>() / 4.0;
+                cells.push((depth, height, points));
+            }
+        }
+        cells.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let polygons = cells
+            .into_iter()
+            .map(|(_, height, points)| {
+                let color = if flat { self.theme.series[0] } else { self.theme.heat(height) };
+                (points.iter().map(|point| self.isometric(point, bounds)).collect(), color)
+            })
+            .collect();
+        self.elements.push(Element::Surface(polygons));
+    }
+
+    /// The X, Y and Z edges of the box from its front bottom corner, with tick marks
+    /// and labels at the major ticks of each
+    fn surface_axes(&mut self, bounds: &SurfaceBounds) {
+        let origin = self.isometric(&Point3D::new(bounds.x_min, bounds.y_min, bounds.z_min), bounds);
+        let edges = [
+            Point3D::new(bounds.x_max, bounds.y_min, bounds.z_min),
+            Point3D::new(bounds.x_min, bounds.y_max, bounds.z_min),
+            Point3D::new(bounds.x_min, bounds.y_min, bounds.z_max),
+        ];
+        let edges = edges.iter().map(|end| vec![origin, self.isometric(end, bounds)]).collect();
+        self.elements.push(Element::Path(PathKind::Axis, edges));
+
+        // Labels sit below the floor edges and to the left of the vertical one
+        let axes = [
+            (bounds.x_axis(5), Anchor::Middle, (0.0, 14.0)),
+            (bounds.y_axis(5), Anchor::Middle, (0.0, 14.0)),
+            (bounds.z_axis(5), Anchor::End, (-6.0, 3.0)),
+        ];
+        let mut ticks = Vec::new();
+        for (index, (axis, anchor, (dx, dy))) in axes.iter().enumerate() {
+            for tick in &axis.major {
+                let point = match index {
+                    0 => Point3D::new(tick.value, bounds.y_min, bounds.z_min),
+                    1 => Point3D::new(bounds.x_min, tick.value, bounds.z_min),
+                    _ => Point3D::new(bounds.x_min, bounds.y_min, tick.value),
+                };
+                let (x, y) = self.isometric(&point, bounds);
+                ticks.push(vec![(x, y), (x, y + 4.0)]);
+                self.text(TextKind::TickLabel, (x + dx, y + dy), *anchor, &tick.label);
+            }
+        }
+        if !ticks.is_empty() {
+            self.elements.push(Element::Path(PathKind::Tick, ticks));
+        }
+    }
+
+    /// Title centered in the top margin, X label in the bottom margin, Y label turned
+    /// in the left margin. Surfaces have no flat Y axis, so their labels go into one
+    /// legend line.
+    fn labels(&mut self, settings: &PlotSettings, include_z: bool) {
+        let (width, height, margin) = (self.width as f64, self.height as f64, self.layout.0 as f64);
+        let bottom = (height - 10.0).max(0.0);
+
+        if let Some(title) = &settings.title {
+            self.text(TextKind::Title, (width / 2.0, (margin / 2.0).floor() + 6.0), Anchor::Middle, title);
+        }
+
+        if include_z {
+            let labels: Vec<String> = settings
+                .axis_labels(true)
+                .into_iter()
+                .map(|(axis, label)| format!("{axis}: {label}"))
+                .collect();
+            if !labels.is_empty() {
+                self.text(TextKind::Label, (width / 2.0, bottom), Anchor::Middle, &labels.join(", "));
+            }
+            return;
+        }
+
+        if let Some(xlabel) = &settings.xlabel {
+            self.text(TextKind::Label, (width / 2.0, bottom), Anchor::Middle, xlabel);
+        }
+        if let Some(ylabel) = &settings.ylabel {
+            self.elements.push(Element::Text {
+                kind: TextKind::Label,
+                position: (12.0, height / 2.0),
+                anchor: Anchor::Middle,
+                vertical: true,
+                text: ylabel.clone(),
+            });
+        }
+    }
+}
+
+/// A coordinate or length for the vector formats, rounded to hundredths of a pixel
+/// and without trailing zeros
+pub fn coordinate(value: f64) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded == 0.0 { "0".to_string() } else { format!("{rounded}") }
+}
+
+/// Color operands of PostScript's `setrgbcolor` and PDF's `rg`/`RG`, ignoring alpha
+pub fn ps_color(color: Rgba) -> String {
+    let channel = |value: u8| coordinate(value as f64 / 255.0);
+    format!("{} {} {}", channel(color.r), channel(color.g), channel(color.b))
+}
+
+/// PostScript or PDF string literal, kept to ASCII with octal escapes for the
+/// Latin-1 range. Other characters become `?`. The codes match both the Latin-1
+/// encoding of the EPS fonts and PDF's WinAnsi encoding.
+pub fn ps_string(text: &str) -> String {
+    let mut escaped = String::from("(");
+    for ch in text.chars() {
+        match ch {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            ' '..='~' => escaped.push(ch),
+            '\u{a0}'..='\u{ff}' => escaped.push_str(&format!("\\{:03o}", ch as u32)),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped.push(')');
+    escaped
+}
+
+/// "a to b" with as many decimals as the range needs, for the description
+fn describe_range(min: f64, max: f64) -> String {
+    let largest = min.abs().max(max.abs());
+    let step = match (max - min).abs() / 100.0 {
+        step if step > 0.0 => step,
+        _ => largest.max(1.0) / 100.0,
+    };
+    let format = NumberFormat::for_magnitude(largest, step);
+    format!("{} to {}", format.format(min), format.format(max))
+}
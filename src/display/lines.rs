@@ -0,0 +1,171 @@
+use super::{axis::Tick, PlotBounds, PlotStyle, Segments};
+
+/// Character cells are about twice as tall as they are wide, so a step of one row
+/// looks as long as a step of two columns
+const CELL_ASPECT: f64 = 2.0;
+/// Lines flatter than this (as seen on screen, rise over run) use the flat glyph
+const FLAT: f64 = 0.414;
+/// Lines steeper than this use the vertical glyph
+const STEEP: f64 = 2.414;
+/// Flat lines this far down their cell use the low glyph, so that gentle slopes
+/// step down through `-` and `_` rather than jumping whole rows
+const LOW: f64 = 0.75;
+
+/// Characters a text renderer draws lines with, picked by the slope of the line
+/// as it appears on screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineGlyphs {
+    /// Nearly horizontal
+    pub flat: char,
+    /// Nearly horizontal, along the bottom of the cell
+    pub low: char,
+    /// Going up to the right
+    pub rising: char,
+    /// Going down to the right
+    pub falling: char,
+    /// Nearly vertical
+    pub steep: char,
+}
+
+impl LineGlyphs {
+    pub const ASCII: Self = Self { flat: '-', low: '_', rising: '/', falling: '\\', steep: '|' };
+    pub const UNICODE: Self = Self { flat: '─', low: '─', rising: '╱', falling: '╲', steep: '│' };
+    pub const HEAVY: Self = Self { flat: '━', low: '━', rising: '╱', falling: '╲', steep: '┃' };
+
+    /// The same character whatever the slope, for dotted lines
+    pub const fn uniform(ch: char) -> Self {
+        Self { flat: ch, low: ch, rising: ch, falling: ch, steep: ch }
+    }
+
+    /// Glyph for a line moving `dx` columns and `dy` rows (rows grow downwards),
+    /// passing `fraction` of the way down its cell
+    pub fn for_slope(&self, dx: f64, dy: f64, fraction: f64) -> char {
+        let run = dx.abs();
+        let rise = dy.abs() * CELL_ASPECT;
+        if rise <= run * FLAT {
+            if fraction >= LOW { self.low } else { self.flat }
+        } else if rise >= run * STEEP {
+            self.steep
+        } else if (dx > 0.0) != (dy > 0.0) {
+            self.rising
+        } else {
+            self.falling
+        }
+    }
+}
+
+/// Cells covered by the line from `from` to `to`, given in fractional
+/// (column, row) coordinates where cell `(c, r)` spans `c..c+1` and `r..r+1`,
+/// each with the glyph for the line's slope. Cells left of or above the grid are
+/// skipped; the caller checks the right and bottom edges.
+pub fn line_cells(from: (f64, f64), to: (f64, f64), glyphs: &LineGlyphs) -> Vec<(usize, usize, char)> {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let steps = dx.abs().max(dy.abs()).ceil().max(1.0) as usize;
+
+    let mut cells: Vec<(usize, usize, char)> = Vec::with_capacity(steps + 1);
+    for i in 0..=steps {
+        let t = i as f64 / steps as f64;
+        let (x, y) = (from.0 + dx * t, from.1 + dy * t);
+        if !(x >= 0.0 && y >= 0.0) {
+            continue;
+        }
+        let (column, row) = (x as usize, y as usize);
+        if cells.last().is_some_and(|&(c, r, _)| (c, r) == (column, row)) {
+            continue;
+        }
+        cells.push((column, row, glyphs.for_slope(dx, dy, y.fract())));
+    }
+    cells
+}
+
+/// Where the data area of a text plot sits in its grid: `data_width` columns
+/// starting at column `left`, over all `height` rows of a `width` wide grid
+#[derive(Clone, Copy, Debug)]
+pub struct TextArea {
+    pub left: usize,
+    pub data_width: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl TextArea {
+    pub fn new(left: usize, data_width: usize, width: usize, height: usize) -> Self {
+        Self { left, data_width, width, height }
+    }
+
+    /// Fractional cell coordinates of a data point
+    fn position(&self, bounds: &PlotBounds, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.left as f64 + bounds.x_fraction(x) * self.data_width as f64,
+            (1.0 - bounds.y_fraction(y)) * self.height.saturating_sub(1) as f64,
+        )
+    }
+}
+
+/// Draw one series on a text grid: lines between the samples of each piece,
+/// clipped to the window, and/or `point` at every visible sample. `mark` is
+/// called with the column, row and character of every cell to set, all inside
+/// the grid. A piece of a single sample is always drawn as a point, so it does not
+/// vanish in line mode.
+pub fn draw_series(
+    segments: &Segments,
+    bounds: &PlotBounds,
+    area: TextArea,
+    style: PlotStyle,
+    glyphs: &LineGlyphs,
+    point: char,
+    mut mark: impl FnMut(usize, usize, char),
+) {
+    let mut set = |column: usize, row: usize, ch: char| {
+        if column < area.width && row < area.height {
+            mark(column, row, ch);
+        }
+    };
+
+    if style != PlotStyle::Points {
+        for piece in &segments.pieces {
+            for pair in piece.windows(2) {
+                if let Some((start, end)) = bounds.clip_segment(pair[0], pair[1]) {
+                    let cells = line_cells(area.position(bounds, start), area.position(bounds, end), glyphs);
+                    for (column, row, ch) in cells {
+                        set(column, row, ch);
+                    }
+                }
+            }
+        }
+    }
+
+    for piece in &segments.pieces {
+        if style == PlotStyle::Lines && piece.len() > 1 {
+            continue;
+        }
+        for &(x, y) in piece.iter().filter(|&&(x, y)| bounds.contains(x, y)) {
+            let (column, row) = area.position(bounds, (x, y));
+            set(column as usize, row as usize, point);
+        }
+    }
+}
+
+/// "X:" footer line of a text plot with each tick label centered under its column.
+/// A tick at fraction 0.0 sits at `first_column` and one at 1.0 `span` columns
+/// further; labels that would touch another or run past `length` are left out.
+pub fn x_tick_line(ticks: &[Tick], first_column: usize, span: usize, length: usize) -> String {
+    let mut line: Vec<char> = "X: ".chars().collect();
+    line.resize(length.max(line.len()), ' ');
+
+    for tick in ticks {
+        let label = &tick.label;
+        let column = first_column + (tick.fraction * span as f64).round() as usize;
+        let start = column.saturating_sub(label.len() / 2).max(3);
+        let end = start + label.chars().count();
+        if end > line.len() || line[start - 1] != ' ' || line[start..end].iter().any(|&ch| ch != ' ') {
+            continue;
+        }
+        line.splice(start..end, label.chars());
+    }
+
+    let mut line: String = line.into_iter().collect();
+    line.truncate(line.trim_end().len());
+    line.push('\n');
+    line
+}
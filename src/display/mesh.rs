@@ -0,0 +1,83 @@
+use crate::values::Expression3dResult;
+
+use super::{PlotSettings, Rgba, SurfaceBounds};
+
+/// A surface as a triangle mesh, shared by the 3D model outputs. Vertices are the
+/// grid points in the space the axes are drawn in, so a log axis gives log
+/// coordinates.
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<[f64; 3]>,
+    /// Unit normal of every vertex, averaged over the triangles around it
+    pub normals: Vec<[f64; 3]>,
+    /// Color of every vertex on the theme's color scale for heights
+    pub colors: Vec<Rgba>,
+    /// Vertex indices of every triangle, counterclockwise seen from above
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl Mesh {
+    /// Two triangles per grid cell. Points that are undefined, or that an axis
+    /// cannot show, have no vertex, and the triangles that would use them are left
+    /// out, so they become holes. A cell missing one corner keeps the triangle on
+    /// the other side of its diagonal.
+    pub fn surface(result: &Expression3dResult, settings: &PlotSettings) -> Self {
+        let mut mesh = Self::default();
+        let Some(bounds) = SurfaceBounds::for_surface(result, settings) else { return mesh };
+
+        let mut index = vec![vec![None; result.x_len()]; result.y_len()];
+        for (y_idx, &y) in result.y_values.iter().enumerate() {
+            for (x_idx, &x) in result.x_values.iter().enumerate() {
+                let Some(z) = result.get_z(x_idx, y_idx) else { continue };
+                if !(settings.x_scale.accepts(x) && settings.y_scale.accepts(y) && settings.z_scale.accepts(z)) {
+                    continue;
+                }
+                index[y_idx][x_idx] = Some(mesh.vertices.len());
+                mesh.vertices.push([settings.x_scale.forward(x), settings.y_scale.forward(y), settings.z_scale.forward(z)]);
+                mesh.colors.push(settings.theme.heat(bounds.z_fraction(z)));
+            }
+        }
+
+        for y_idx in 1..result.y_len() {
+            for x_idx in 1..result.x_len() {
+                let corner = |dx: usize, dy: usize| index[y_idx - 1 + dy][x_idx - 1 + dx];
+                for triangle in [[corner(0, 0), corner(1, 0), corner(1, 1)], [corner(0, 0), corner(1, 1), corner(0, 1)]] {
+                    if let [Some(a), Some(b), Some(c)] = triangle {
+                        mesh.triangles.push([a, b, c]);
+                    }
+                }
+            }
+        }
+
+        // Each triangle adds its normal scaled by its area, so large faces count more
+        let mut sums = vec![[0.0; 3]; mesh.vertices.len()];
+        for triangle in &mesh.triangles {
+            let normal = mesh.cross(triangle);
+            for &vertex in triangle {
+                for axis in 0..3 {
+                    sums[vertex][axis] += normal[axis];
+                }
+            }
+        }
+        mesh.normals = sums.into_iter().map(|sum| unit(sum).unwrap_or([0.0, 0.0, 1.0])).collect();
+        mesh
+    }
+
+    /// Unit normal of a triangle, pointing up for an upward facing surface
+    pub fn face_normal(&self, triangle: &[usize; 3]) -> [f64; 3] {
+        unit(self.cross(triangle)).unwrap_or([0.0, 0.0, 1.0])
+    }
+
+    /// Cross product of two edges of a triangle, twice its area in length
+    fn cross(&self, &[a, b, c]: &[usize; 3]) -> [f64; 3] {
+        let (a, b, c) = (self.vertices[a], self.vertices[b], self.vertices[c]);
+        let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]]
+    }
+}
+
+fn unit(vector: [f64; 3]) -> Option<[f64; 3]> {
+    let length = vector.iter().map(|component| component * component).sum::<f64>().sqrt();
+    (length > 0.0 && length.is_finite()).then(|| vector.map(|component| component / length))
+}
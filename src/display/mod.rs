@@ -3,16 +3,34 @@ use std::error::Error;
 use crate::{
     values::{ExpressionRange1dResult, Expression3dResult},
     parametric2d::Parametric2DResult,
+    range::Range,
 };
 
+pub mod axis;
+pub mod base64;
+pub mod color;
+pub mod deflate;
+pub mod figure;
+pub mod png;
+pub mod lines;
+pub mod mesh;
 pub mod renderers;
+pub mod segments;
+pub mod stroke;
+pub mod terminal;
 pub mod writers;
 pub mod utils;
 
 // Re-export all the public types for convenience
-pub use renderers::{AsciiRenderer, AnsiRenderer, RegisRenderer, SixelRenderer};
-pub use writers::{CsvWriter, PpmWriter, SvgWriter, LatexWriter, SixelWriter, RegisWriter};
-pub use utils::{Bitmap, Point3D, SurfaceBounds};
+pub use axis::AxisTicks;
+pub use color::{Rgba, Theme};
+pub use lines::{LineGlyphs, TextArea};
+pub use segments::Segments;
+pub use stroke::Stroke;
+pub use renderers::{AsciiRenderer, AnsiRenderer, BlocksRenderer, BrailleRenderer, ItermRenderer, KittyRenderer, RegisRenderer, SixelRenderer};
+pub use writers::{BmpWriter, CsvWriter, JsonWriter, VegaLiteWriter, NpyWriter, ObjWriter, PlyWriter, StlWriter, NetpbmFormat, PbmWriter, PgmWriter, PpmWriter, PngWriter, SvgWriter, PdfWriter, EpsWriter, HtmlWriter, LatexMode, LatexWriter, GnuplotWriter, MatplotlibWriter, SixelWriter, RegisWriter};
+pub use terminal::{ColorDepth, TerminalSize};
+pub use utils::{Bitmap, PlotData, Point3D, SurfaceBounds};
 
 #[derive(Clone, Copy, Debug)]
 pub struct PlotConfig {
@@ -63,11 +81,252 @@ pub struct PlotBounds {
     pub x_max: f64,
     pub y_min: f64,
     pub y_max: f64,
+    pub x_scale: AxisScale,
+    pub y_scale: AxisScale,
 }
 
 impl PlotBounds {
     pub fn new(x_min: f64, x_max: f64, y_min: f64, y_max: f64) -> Self {
-        Self { x_min, x_max, y_min, y_max }
+        Self { x_min, x_max, y_min, y_max, x_scale: AxisScale::Linear, y_scale: AxisScale::Linear }
+    }
+
+    /// Window for a function plot: X spans the samples, Y covers the finite samples
+    /// inside that span with 10% headroom. `xrange=`/`yrange=` replace either side.
+    /// Returns `None` when there is nothing finite to show.
+    pub fn for_plot(x_values: &[f64], y_values: &[f64], settings: &PlotSettings) -> Option<Self> {
+        let (x_min, x_max) =
+            settings.x_scale.window(settings.x_view, x_values.iter().copied(), Autoscale::Full, 0.0)?;
+        let visible = x_values
+            .iter()
+            .zip(y_values)
+            .filter(|(&x, _)| x >= x_min && x <= x_max)
+            .map(|(_, &y)| y);
+        let (y_min, y_max) = settings.y_scale.window(settings.y_view, visible, settings.autoscale, 0.1)?;
+        Some(Self { x_min, x_max, y_min, y_max, x_scale: settings.x_scale, y_scale: settings.y_scale })
+    }
+
+    /// Window for a parametric curve: both axes cover the finite points with 10% headroom
+    /// unless `xrange=`/`yrange=` fix them
+    pub fn for_parametric(x_values: &[f64], y_values: &[f64], settings: &PlotSettings) -> Option<Self> {
+        let (x_min, x_max) =
+            settings.x_scale.window(settings.x_view, x_values.iter().copied(), settings.autoscale, 0.1)?;
+        let visible = x_values
+            .iter()
+            .zip(y_values)
+            .filter(|(&x, _)| x >= x_min && x <= x_max)
+            .map(|(_, &y)| y);
+        let (y_min, y_max) = settings.y_scale.window(settings.y_view, visible, settings.autoscale, 0.1)?;
+        Some(Self { x_min, x_max, y_min, y_max, x_scale: settings.x_scale, y_scale: settings.y_scale })
+    }
+
+    /// Ticks along X, about `target` of them labelled
+    pub fn x_axis(&self, target: usize) -> AxisTicks {
+        AxisTicks::new(self.x_scale, self.x_min, self.x_max, target)
+    }
+
+    /// Ticks up Y, about `target` of them labelled
+    pub fn y_axis(&self, target: usize) -> AxisTicks {
+        AxisTicks::new(self.y_scale, self.y_min, self.y_max, target)
+    }
+
+    /// Ticks up Y for a text display, no two labels on the same line
+    pub fn y_axis_lines(&self, target: usize, line: impl Fn(f64) -> usize) -> AxisTicks {
+        AxisTicks::for_lines(self.y_scale, self.y_min, self.y_max, target, line)
+    }
+
+    /// Whether a point lies inside the window. Non-finite points never do, nor do
+    /// non-positive ones on a log axis.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        self.x_scale.accepts(x)
+            && self.y_scale.accepts(y)
+            && x >= self.x_min
+            && x <= self.x_max
+            && y >= self.y_min
+            && y <= self.y_max
+    }
+
+    /// Position of `x` across the window, 0.0 at the left edge and 1.0 at the right
+    pub fn x_fraction(&self, x: f64) -> f64 {
+        self.x_scale.fraction(x, self.x_min, self.x_max)
+    }
+
+    /// Position of `y` up the window, 0.0 at the bottom edge and 1.0 at the top
+    pub fn y_fraction(&self, y: f64) -> f64 {
+        self.y_scale.fraction(y, self.y_min, self.y_max)
+    }
+
+    /// Clip the segment `a`-`b` to the window (Liang-Barsky). Returns `None` when
+    /// nothing of it is visible or an endpoint cannot be shown. On log axes the
+    /// segment is clipped as it appears on screen, i.e. in log space.
+    pub fn clip_segment(&self, a: (f64, f64), b: (f64, f64)) -> Option<((f64, f64), (f64, f64))> {
+        let (start, end) = self.screen_space().clip_linear(
+            (self.x_scale.forward(a.0), self.y_scale.forward(a.1)),
+            (self.x_scale.forward(b.0), self.y_scale.forward(b.1)),
+        )?;
+        Some((
+            (self.x_scale.inverse(start.0), self.y_scale.inverse(start.1)),
+            (self.x_scale.inverse(end.0), self.y_scale.inverse(end.1)),
+        ))
+    }
+
+    /// The window with both axes mapped to the linear space they are drawn in
+    fn screen_space(&self) -> Self {
+        Self::new(
+            self.x_scale.forward(self.x_min),
+            self.x_scale.forward(self.x_max),
+            self.y_scale.forward(self.y_min),
+            self.y_scale.forward(self.y_max),
+        )
+    }
+
+    fn clip_linear(&self, a: (f64, f64), b: (f64, f64)) -> Option<((f64, f64), (f64, f64))> {
+        if !(a.0.is_finite() && a.1.is_finite() && b.0.is_finite() && b.1.is_finite()) {
+            return None;
+        }
+
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let mut t_enter = 0.0_f64;
+        let mut t_exit = 1.0_f64;
+        let edges = [
+            (-dx, a.0 - self.x_min),
+            (dx, self.x_max - a.0),
+            (-dy, a.1 - self.y_min),
+            (dy, self.y_max - a.1),
+        ];
+        for (p, q) in edges {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else {
+                let t = q / p;
+                if p < 0.0 {
+                    t_enter = t_enter.max(t);
+                } else {
+                    t_exit = t_exit.min(t);
+                }
+            }
+        }
+
+        if t_enter > t_exit {
+            return None;
+        }
+        Some((
+            (a.0 + t_enter * dx, a.1 + t_enter * dy),
+            (a.0 + t_exit * dx, a.1 + t_exit * dy),
+        ))
+    }
+}
+
+/// How the plot window is fitted to the data when no explicit range is given
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Autoscale {
+    /// Smallest and largest finite value
+    #[default]
+    Full,
+    /// Ignore the given percentage of finite values at each end, so a few samples
+    /// near a pole do not flatten everything else
+    Percentile(f64),
+}
+
+impl Autoscale {
+    /// Extent of the finite values, or `None` if there are none
+    pub fn extent(&self, values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+        let mut finite: Vec<f64> = values.filter(|value| value.is_finite()).collect();
+        if finite.is_empty() {
+            return None;
+        }
+
+        match *self {
+            Autoscale::Full => Some(
+                finite
+                    .iter()
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v))),
+            ),
+            Autoscale::Percentile(percent) => {
+                finite.sort_by(f64::total_cmp);
+                let last = (finite.len() - 1) as f64;
+                let fraction = (percent / 100.0).clamp(0.0, 0.5);
+                let lo = (fraction * last).floor() as usize;
+                let hi = ((1.0 - fraction) * last).ceil() as usize;
+                Some((finite[lo], finite[hi.max(lo)]))
+            }
+        }
+    }
+}
+
+/// How data values are mapped onto an axis
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AxisScale {
+    #[default]
+    Linear,
+    /// Base-10 logarithmic; only positive values can be shown
+    Log,
+}
+
+impl AxisScale {
+    /// Whether a value can be placed on this axis at all
+    pub fn accepts(&self, value: f64) -> bool {
+        match self {
+            AxisScale::Linear => value.is_finite(),
+            AxisScale::Log => value.is_finite() && value > 0.0,
+        }
+    }
+
+    /// Value in the linear space the axis is drawn in
+    pub fn forward(&self, value: f64) -> f64 {
+        match self {
+            AxisScale::Linear => value,
+            AxisScale::Log => value.log10(),
+        }
+    }
+
+    pub fn inverse(&self, value: f64) -> f64 {
+        match self {
+            AxisScale::Linear => value,
+            AxisScale::Log => 10f64.powf(value),
+        }
+    }
+
+    /// Position of `value` between `min` (0.0) and `max` (1.0)
+    pub fn fraction(&self, value: f64, min: f64, max: f64) -> f64 {
+        let (lo, hi) = (self.forward(min), self.forward(max));
+        (self.forward(value) - lo) / (hi - lo)
+    }
+
+    /// Axis extent: the view when one is given and usable, otherwise the autoscaled
+    /// extent of the values this axis accepts, padded by `padding` of its drawn span.
+    /// A view with one endpoint a log axis cannot show keeps the other one and
+    /// starts at the smallest value drawn instead.
+    pub fn window(
+        &self,
+        view: Option<(f64, f64)>,
+        values: impl Iterator<Item = f64>,
+        autoscale: Autoscale,
+        padding: f64,
+    ) -> Option<(f64, f64)> {
+        let drawn = values.filter(|&value| self.accepts(value)).map(|value| self.forward(value));
+        let (lo, hi) = match view {
+            Some((lo, hi)) if self.accepts(lo) && self.accepts(hi) => widen((self.forward(lo), self.forward(hi)), 0.0),
+            Some((lo, hi)) if self.accepts(lo) || self.accepts(hi) => {
+                let kept = if self.accepts(lo) { lo } else { hi };
+                widen((drawn.min_by(f64::total_cmp)?, self.forward(kept)), 0.0)
+            }
+            _ => widen(autoscale.extent(drawn)?, padding),
+        };
+        Some((self.inverse(lo), self.inverse(hi)))
+    }
+}
+
+/// Pad an extent by `fraction` of its span on both sides. A degenerate extent is
+/// opened up by one unit so that it can still be mapped onto the screen.
+pub fn widen((lo, hi): (f64, f64), fraction: f64) -> (f64, f64) {
+    let (lo, hi) = (lo.min(hi), lo.max(hi));
+    let span = hi - lo;
+    if span > f64::EPSILON {
+        (lo - span * fraction, hi + span * fraction)
+    } else {
+        (lo - 1.0, hi + 1.0)
     }
 }
 
@@ -75,54 +334,89 @@ impl PlotBounds {
 pub struct Plot3DStyle {
     pub plot_width: usize,
     pub plot_height: usize,
-    pub axis_char: char,
+    pub glyphs: LineGlyphs,
     pub axis_color: u8,
 }
 
 impl Plot3DStyle {
-    pub fn new(plot_width: usize, plot_height: usize, axis_char: char, axis_color: u8) -> Self {
-        Self { plot_width, plot_height, axis_char, axis_color }
+    pub fn new(plot_width: usize, plot_height: usize, glyphs: LineGlyphs, axis_color: u8) -> Self {
+        Self { plot_width, plot_height, glyphs, axis_color }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct LabelConfig {
-    pub margin: usize,
-    pub plot_width: usize,
-    pub plot_height: usize,
-    pub x_min: f64,
-    pub x_max: f64,
-    pub y_min: f64,
-    pub y_max: f64,
+/// How the text renderers draw a series: a mark at every sample, lines joining
+/// the samples, or both
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlotStyle {
+    Points,
+    #[default]
+    Lines,
+    Both,
 }
 
-impl LabelConfig {
-    pub fn new(margin: usize, plot_width: usize, plot_height: usize, x_min: f64, x_max: f64, y_min: f64, y_max: f64) -> Self {
-        Self { margin, plot_width, plot_height, x_min, x_max, y_min, y_max }
-    }
+/// Presentation settings shared by every renderer and writer
+#[derive(Clone, Debug, Default)]
+pub struct PlotSettings {
+    /// The plotted expression as written, for the data exports
+    pub expression: String,
+    /// The ranges the expression was sampled over, X first
+    pub ranges: Vec<Range>,
+    pub title: Option<String>,
+    pub xlabel: Option<String>,
+    pub ylabel: Option<String>,
+    pub zlabel: Option<String>,
+    pub x_view: Option<(f64, f64)>,
+    pub y_view: Option<(f64, f64)>,
+    pub autoscale: Autoscale,
+    pub x_scale: AxisScale,
+    pub y_scale: AxisScale,
+    pub z_scale: AxisScale,
+    /// Draw dashed vertical asymptotes where a curve passes through infinity
+    pub asymptotes: bool,
+    pub style: PlotStyle,
+    /// Width of curves in the image outputs, `None` for the default
+    pub linewidth: Option<f64>,
+    /// Dash and gap lengths of curves in the image outputs, empty for solid lines
+    pub dash: Vec<f64>,
+    /// Colors of the bitmap outputs
+    pub theme: Theme,
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct Label3DConfig {
-    pub margin: usize,
-    pub plot_width: usize,
-    pub plot_height: usize,
-    pub x_min: f64,
-    pub x_max: f64,
-    pub y_min: f64,
-    pub y_max: f64,
-    pub z_min: f64,
-    pub z_max: f64,
-}
+impl PlotSettings {
+    /// How the image outputs draw curves
+    pub fn stroke(&self) -> Stroke {
+        Stroke::new(self.linewidth, &self.dash)
+    }
 
-impl Label3DConfig {
-    pub fn new(layout: (usize, usize, usize), x_range: (f64, f64), y_range: (f64, f64), z_range: (f64, f64)) -> Self {
-        Self { 
-            margin: layout.0, plot_width: layout.1, plot_height: layout.2,
-            x_min: x_range.0, x_max: x_range.1,
-            y_min: y_range.0, y_max: y_range.1,
-            z_min: z_range.0, z_max: z_range.1
+    /// Axis labels that were set, paired with their axis name, for one-line legends
+    pub fn axis_labels(&self, include_z: bool) -> Vec<(&'static str, &str)> {
+        let mut labels = vec![("X", self.xlabel.as_deref()), ("Y", self.ylabel.as_deref())];
+        if include_z {
+            labels.push(("Z", self.zlabel.as_deref()));
+        }
+        labels
+            .into_iter()
+            .filter_map(|(axis, label)| label.map(|label| (axis, label)))
+            .collect()
+    }
+
+    /// Header text of the text renderers: the plot title when one is set, otherwise
+    /// the renderer's own name
+    pub fn heading(&self, default: &str) -> String {
+        self.title.clone().unwrap_or_else(|| default.to_string())
+    }
+
+    /// One footer line naming each labelled axis, or nothing when no labels are set
+    pub fn label_footer(&self, include_z: bool) -> String {
+        let labels = self.axis_labels(include_z);
+        if labels.is_empty() {
+            return String::new();
         }
+        let labels: Vec<String> = labels
+            .into_iter()
+            .map(|(axis, label)| format!("{axis}: {label}"))
+            .collect();
+        format!("Axes: {}\n", labels.join(", "))
     }
 }
 
@@ -134,6 +428,7 @@ pub trait DisplayRenderer {
         width: usize,
         height: usize,
         x_range: &ExpressionRange1dResult,
+        settings: &PlotSettings,
     ) -> String;
 
     fn render_parametric(
@@ -141,6 +436,7 @@ pub trait DisplayRenderer {
         result: &Parametric2DResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> String;
 
     fn render_surface3d(
@@ -148,6 +444,7 @@ pub trait DisplayRenderer {
         result: &Expression3dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> String;
 }
 
@@ -160,6 +457,7 @@ pub trait OutputWriter {
         y_result: &ExpressionRange1dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>>;
 
     fn write_parametric(
@@ -168,6 +466,7 @@ pub trait OutputWriter {
         result: &Parametric2DResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>>;
 
     fn write_surface3d(
@@ -176,5 +475,6 @@ pub trait OutputWriter {
         result: &Expression3dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>>;
-}
\ No newline at end of file
+}
@@ -0,0 +1,180 @@
+use super::deflate;
+
+/// Bytes per pixel of 8-bit RGB
+const BYTES_PER_PIXEL: usize = 3;
+
+/// Encode packed 8-bit RGB rows as a PNG file. Every scanline gets the filter that
+/// leaves the smallest differences, which is what lets deflate find the long runs
+/// of a plot's flat background and straight lines.
+pub fn encode_rgb(width: usize, height: usize, rgb: &[u8]) -> Vec<u8> {
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8 bit RGB, deflate, adaptive filtering, no interlace
+    write_chunk(&mut png, b"IHDR", &header);
+
+    let stride = width * BYTES_PER_PIXEL;
+    let mut scanlines = Vec::with_capacity(height * (stride + 1));
+    let mut previous = vec![0; stride];
+    for row in rgb.chunks(stride.max(1)).take(height) {
+        let (filter, filtered) = Filter::ALL
+            .iter()
+            .map(|&filter| (filter, filter.apply(row, &previous)))
+            .min_by_key(|(_, filtered)| filtered.iter().map(|&byte| (byte as i8).unsigned_abs() as usize).sum::<usize>())
+            .unwrap_or((Filter::None, row.to_vec()));
+        scanlines.push(filter as u8);
+        scanlines.extend_from_slice(&filtered);
+        previous = row.to_vec();
+    }
+    write_chunk(&mut png, b"IDAT", &deflate::zlib(&scanlines));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// The scanline filters of PNG, each predicting a byte from its neighbours to the
+/// left (a), above (b) and above left (c) and storing the difference
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Filter {
+    None = 0,
+    Sub = 1,
+    Up = 2,
+    Average = 3,
+    Paeth = 4,
+}
+
+impl Filter {
+    const ALL: [Self; 5] = [Self::None, Self::Sub, Self::Up, Self::Average, Self::Paeth];
+
+    fn apply(self, row: &[u8], previous: &[u8]) -> Vec<u8> {
+        (0..row.len())
+            .map(|i| {
+                let a = if i >= BYTES_PER_PIXEL { row[i - BYTES_PER_PIXEL] } else { 0 };
+                let b = previous[i];
+                let c = if i >= BYTES_PER_PIXEL { previous[i - BYTES_PER_PIXEL] } else { 0 };
+                let prediction = match self {
+                    Self::None => 0,
+                    Self::Sub => a,
+                    Self::Up => b,
+                    Self::Average => ((a as u16 + b as u16) / 2) as u8,
+                    Self::Paeth => paeth(a, b, c),
+                };
+                row[i].wrapping_sub(prediction)
+            })
+            .collect()
+    }
+}
+
+/// Whichever neighbour is closest to `a + b - c`, preferring left, then above
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let estimate = a as i16 + b as i16 - c as i16;
+    let (da, db, dc) = ((estimate - a as i16).abs(), (estimate - b as i16).abs(), (estimate - c as i16).abs());
+    if da <= db && da <= dc {
+        a
+    } else if db <= dc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Length, type, data and CRC of the type and data
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    png.extend_from_slice(&crc32(&[kind.as_slice(), data]).to_be_bytes());
+}
+
+/// CRC-32 (ISO-HDLC polynomial) over consecutive byte slices
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let table = crc_table();
+    let mut crc = !0u32;
+    for &byte in parts.iter().flat_map(|part| part.iter()) {
+        crc = table[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// CRC of every byte value, so the checksum takes one lookup per byte
+fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    for (byte, entry) in table.iter_mut().enumerate() {
+        let mut crc = byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+        *entry = crc;
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::deflate::tests::inflate;
+
+    /// Type and data of every chunk, checking each chunk's CRC on the way
+    fn chunks(png: &[u8]) -> Vec<([u8; 4], &[u8])> {
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let mut chunks = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let kind: [u8; 4] = rest[4..8].try_into().unwrap();
+            let data = &rest[8..8 + length];
+            let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+            assert_eq!(crc, crc32(&[&kind, data]), "CRC of {}", String::from_utf8_lossy(&kind));
+            chunks.push((kind, data));
+            rest = &rest[12 + length..];
+        }
+        chunks
+    }
+
+    #[test]
+    fn crc32_known_values() {
+        assert_eq!(crc32(&[b""]), 0);
+        assert_eq!(crc32(&[b"123456789"]), 0xcbf4_3926);
+        assert_eq!(crc32(&[b"1234", b"56789"]), 0xcbf4_3926);
+        assert_eq!(crc32(&[b"IEND"]), 0xae42_6082);
+    }
+
+    #[test]
+    fn chunks_have_valid_crcs_and_the_pixels_survive() {
+        let (width, height) = (7, 5);
+        let rgb: Vec<u8> = (0..width * height * BYTES_PER_PIXEL).map(|i| (i * 37 % 251) as u8).collect();
+        let png = encode_rgb(width, height, &rgb);
+
+        let chunks = chunks(&png);
+        let kinds: Vec<&[u8; 4]> = chunks.iter().map(|(kind, _)| kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 7, 0, 0, 0, 5, 8, 2, 0, 0, 0]);
+
+        // Undo each scanline's filter and compare with the input
+        let (scanlines, _) = inflate(chunks[1].1);
+        let stride = width * BYTES_PER_PIXEL;
+        assert_eq!(scanlines.len(), height * (stride + 1));
+        let mut pixels: Vec<u8> = Vec::new();
+        let mut previous = vec![0u8; stride];
+        for line in scanlines.chunks(stride + 1) {
+            let mut row = vec![0u8; stride];
+            for i in 0..stride {
+                let a = if i >= BYTES_PER_PIXEL { row[i - BYTES_PER_PIXEL] } else { 0 };
+                let (b, c) = (previous[i], if i >= BYTES_PER_PIXEL { previous[i - BYTES_PER_PIXEL] } else { 0 });
+                let prediction = match line[0] {
+                    0 => 0,
+                    1 => a,
+                    2 => b,
+                    3 => ((a as u16 + b as u16) / 2) as u8,
+                    4 => paeth(a, b, c),
+                    filter => panic!("unknown filter {filter}"),
+                };
+                row[i] = line[1 + i].wrapping_add(prediction);
+            }
+            pixels.extend_from_slice(&row);
+            previous = row;
+        }
+        assert_eq!(pixels, rgb);
+    }
+}
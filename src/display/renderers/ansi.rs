@@ -3,10 +3,34 @@ use crate::{
     parametric2d::Parametric2DResult,
 };
 
-use super::super::{DisplayRenderer, Point3D, SurfaceBounds, PlotConfig, Plot3DConfig, PlotBounds, Plot3DStyle};
+use super::super::{
+    lines::{draw_series, line_cells, x_tick_line}, Autoscale, AxisScale, DisplayRenderer, LineGlyphs, Point3D, SurfaceBounds, PlotConfig,
+    Plot3DConfig, PlotBounds, Plot3DStyle, PlotSettings, Segments, TextArea,
+};
+
+/// Color codes given to successive series: green, magenta, cyan, then blue
+const SERIES_COLORS: [u8; 4] = [1, 9, 10, 11];
 
 #[derive(Clone, Debug)]
-pub struct AnsiRenderer;
+pub struct AnsiRenderer {
+    /// Print the escapes for colors and bold text; without them the plot keeps its
+    /// box-drawing lines
+    pub color: bool,
+}
+
+impl AnsiRenderer {
+    pub fn new(color: bool) -> Self {
+        Self { color }
+    }
+
+    /// The output as is, or without its escapes when colors are off
+    fn finish(&self, output: String) -> String {
+        match self.color {
+            true => output,
+            false => strip_sgr(&output),
+        }
+    }
+}
 
 impl DisplayRenderer for AnsiRenderer {
     fn render(
@@ -15,124 +39,157 @@ impl DisplayRenderer for AnsiRenderer {
         width: usize,
         height: usize,
         x_range: &ExpressionRange1dResult,
+        settings: &PlotSettings,
+    ) -> String {
+        self.finish(self.colored_render(result, width, height, x_range, settings))
+    }
+
+    fn render_parametric(
+        &self,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        self.finish(self.colored_render_parametric(result, width, height, settings))
+    }
+
+    fn render_surface3d(
+        &self,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        self.finish(self.colored_render_surface3d(result, width, height, settings))
+    }
+}
+
+/// The plots with their colors, which `finish` takes out again when they are off
+impl AnsiRenderer {
+    fn colored_render(
+        &self,
+        result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        x_range: &ExpressionRange1dResult,
+        settings: &PlotSettings,
     ) -> String {
         if result.0.is_empty() {
             return "\x1b[31mNo data to plot\x1b[0m".to_string();
         }
 
-        let min_val = result.min();
-        let max_val = result.max();
-        let x_min = x_range.min();
-        let x_max = x_range.max();
-
-        if (max_val - min_val).abs() < f64::EPSILON {
-            return format!(
-                "\x1b[33mConstant value: {:.3}\x1b[0m (all {} points)\nX range: [{:.2}, {:.2}]\n{}",
-                min_val,
-                result.0.len(),
-                x_min,
-                x_max,
-                " ".repeat(width / 2) + &"\x1b[32m●\x1b[0m".repeat(result.0.len().min(width))
-            );
-        }
+        let Some((min_val, max_val)) = Autoscale::Full.extent(result.0.iter().copied()) else {
+            return "\x1b[31mNo finite values to plot\x1b[0m".to_string();
+        };
+        // A constant function is still drawn at its real X positions, under a note
+        let note = if (max_val - min_val).abs() < f64::EPSILON && settings.y_view.is_none() {
+            format!("\x1b[33mConstant value: {:.3}\x1b[0m (all {} points)\n", min_val, result.0.len())
+        } else {
+            String::new()
+        };
+
+        let Some(bounds) = PlotBounds::for_plot(&x_range.0, &result.0, settings) else {
+            return "\x1b[31mNo finite values to plot\x1b[0m".to_string();
+        };
 
         let mut grid = vec![vec![' '; width]; height];
         let mut colors = vec![vec![0u8; width]; height];
-        let padding = (max_val - min_val) * 0.1;
-        let plot_min = min_val - padding;
-        let plot_max = max_val + padding;
-        let plot_range = plot_max - plot_min;
-
-        // Plot data points
         let data_width = width.saturating_sub(6);
-        for (i, &value) in result.0.iter().enumerate() {
-            let x_pos = if result.0.len() > 1 {
-                5 + (i * data_width / (result.0.len() - 1))
-            } else {
-                width / 2
-            };
-
-            if x_pos < width {
-                let normalized_y = (value - plot_min) / plot_range;
-                let y_pos = ((1.0 - normalized_y) * (height - 1) as f64) as usize;
-                let y_pos = y_pos.min(height - 1);
-                grid[y_pos][x_pos] = '●';
-                colors[y_pos][x_pos] = 1;
+
+        let points: Vec<(f64, f64)> = x_range.0.iter().copied().zip(result.0.iter().copied()).collect();
+        let segments = Segments::split(&points, &bounds);
+
+        // Dashed asymptotes behind the data
+        if settings.asymptotes {
+            for &x_val in &segments.asymptotes {
+                let x_pos = 5 + (bounds.x_fraction(x_val) * data_width as f64) as usize;
+                for y in (0..height).step_by(2).filter(|_| x_pos < width) {
+                    grid[y][x_pos] = '┆';
+                    colors[y][x_pos] = 2;
+                }
             }
         }
 
+        // Draw the data inside the window
+        let color = series_color(0);
+        draw_series(
+            &segments,
+            &bounds,
+            TextArea::new(5, data_width, width, height),
+            settings.style,
+            &LineGlyphs::UNICODE,
+            '●',
+            |x, y, ch| {
+                grid[y][x] = ch;
+                colors[y][x] = color;
+            },
+        );
+
         // Add axes and labels
-        add_ansi_axes(&mut grid, &mut colors, width, height, plot_min, plot_max);
-        format_ansi_output(
+        add_ansi_axes(&mut grid, &mut colors, width, height, &bounds);
+        note + &format_ansi_output(
             grid,
             colors,
-            PlotConfig::new(width, height, result.0.len(), x_min, x_max, min_val, max_val),
-            x_range,
+            PlotConfig::new(width, height, result.0.len(), bounds.x_min, bounds.x_max, bounds.y_min, bounds.y_max),
+            &bounds,
+            settings,
         )
     }
 
-    fn render_parametric(
+    fn colored_render_parametric(
         &self,
         result: &Parametric2DResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> String {
         if result.is_empty() {
             return "\x1b[31mNo parametric data to plot\x1b[0m".to_string();
         }
 
-        let x_min = result.x_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let x_max = result.x_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        let y_min = result.y_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let y_max = result.y_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-
-        let x_range = x_max - x_min;
-        let y_range = y_max - y_min;
-
-        if x_range.abs() < f64::EPSILON || y_range.abs() < f64::EPSILON {
-            return format!(
-                "\x1b[33mParametric plot: X=[{x_min:.3}, {x_max:.3}], Y=[{y_min:.3}, {y_max:.3}] (constant values)\x1b[0m"
-            );
-        }
+        let Some(bounds) = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings) else {
+            return "\x1b[31mNo finite parametric values to plot\x1b[0m".to_string();
+        };
 
         let mut grid = vec![vec![' '; width]; height];
         let mut colors = vec![vec![0u8; width]; height];
-        let padding_x = x_range * 0.1;
-        let padding_y = y_range * 0.1;
-        let plot_x_min = x_min - padding_x;
-        let plot_x_max = x_max + padding_x;
-        let plot_y_min = y_min - padding_y;
-        let plot_y_max = y_max + padding_y;
-        let plot_x_range = plot_x_max - plot_x_min;
-        let plot_y_range = plot_y_max - plot_y_min;
-
-        // Plot parametric data points
+
+        // Draw the curve inside the window
         let data_width = width.saturating_sub(6);
-        for (&x_val, &y_val) in result.x_values.iter().zip(result.y_values.iter()) {
-            let x_pos = 5 + ((x_val - plot_x_min) / plot_x_range * data_width as f64) as usize;
-            let y_pos = ((plot_y_max - y_val) / plot_y_range * (height - 1) as f64) as usize;
-            
-            if x_pos < width && y_pos < height {
-                grid[y_pos][x_pos] = '●';
-                colors[y_pos][x_pos] = 1;
-            }
-        }
+        let points: Vec<(f64, f64)> = result.x_values.iter().copied().zip(result.y_values.iter().copied()).collect();
+        let color = series_color(0);
+        draw_series(
+            &Segments::split_curve(&points, &bounds),
+            &bounds,
+            TextArea::new(5, data_width, width, height),
+            settings.style,
+            &LineGlyphs::UNICODE,
+            '●',
+            |x, y, ch| {
+                grid[y][x] = ch;
+                colors[y][x] = color;
+            },
+        );
 
         // Add axes and labels for parametric plots
-        add_ansi_parametric_axes(&mut grid, &mut colors, width, height, PlotBounds::new(plot_x_min, plot_x_max, plot_y_min, plot_y_max));
+        add_ansi_parametric_axes(&mut grid, &mut colors, width, height, &bounds);
         
         format_ansi_parametric_output(
             grid,
             colors,
-            PlotConfig::new(width, height, result.len(), x_min, x_max, y_min, y_max),
+            PlotConfig::new(width, height, result.len(), bounds.x_min, bounds.x_max, bounds.y_min, bounds.y_max),
+            &bounds,
+            settings,
         )
     }
 
-    fn render_surface3d(
+    fn colored_render_surface3d(
         &self,
         result: &Expression3dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> String {
         if result.is_empty() {
             return "\x1b[31mNo 3D surface data to plot\x1b[0m".to_string();
@@ -142,8 +199,11 @@ impl DisplayRenderer for AnsiRenderer {
         let mut colors = vec![vec![0u8; width]; height];
         
         // Create a colorized ANSI 3D projection
-        let z_min = result.z_min();
-        let z_max = result.z_max();
+        let Some(bounds) = SurfaceBounds::for_surface(result, settings) else {
+            return "\x1b[31mNo finite 3D surface values to plot\x1b[0m".to_string();
+        };
+        let z_min = bounds.z_min;
+        let z_max = bounds.z_max;
         let z_range = z_max - z_min;
         
         if z_range.abs() < f64::EPSILON {
@@ -151,7 +211,6 @@ impl DisplayRenderer for AnsiRenderer {
         }
         
         // Create isometric projection for ANSI 3D visualization
-        let bounds = SurfaceBounds::from_surface(result);
         let data_width = width.saturating_sub(6);
         let data_height = height.saturating_sub(4);
         
@@ -163,6 +222,9 @@ impl DisplayRenderer for AnsiRenderer {
                 
                 // Create 3D point and project to isometric coordinates
                 let point_3d = Point3D::new(x_val, y_val, z_val);
+                if !bounds.contains(&point_3d) {
+                    continue;
+                }
                 let (iso_x, iso_y) = point_3d.to_isometric(data_width, data_height, &bounds);
                 
                 // Apply margin offset
@@ -170,7 +232,7 @@ impl DisplayRenderer for AnsiRenderer {
                 let screen_y = 2 + iso_y;
                 
                 if screen_x < width && screen_y < height {
-                    let normalized_z = (z_val - z_min) / z_range;
+                    let normalized_z = bounds.z_fraction(z_val);
                     let (char_val, color_val) = if normalized_z < 0.2 {
                         ('·', 4) // dim
                     } else if normalized_z < 0.4 {
@@ -197,10 +259,11 @@ impl DisplayRenderer for AnsiRenderer {
             Plot3DConfig::new(
                 (width, height),
                 result.x_len() * result.y_len(),
-                (result.x_min(), result.x_max()),
-                (result.y_min(), result.y_max()),
+                (bounds.x_min, bounds.x_max),
+                (bounds.y_min, bounds.y_max),
                 (z_min, z_max),
             ),
+            settings,
         )
     }
 }
@@ -210,13 +273,11 @@ fn add_ansi_axes(
     colors: &mut [Vec<u8>],
     width: usize,
     height: usize,
-    plot_min: f64,
-    plot_max: f64,
+    bounds: &PlotBounds,
 ) {
     // Zero line
-    if plot_min <= 0.0 && plot_max >= 0.0 {
-        let plot_range = plot_max - plot_min;
-        let zero_y = ((1.0 - (0.0 - plot_min) / plot_range) * (height - 1) as f64) as usize;
+    if bounds.y_scale == AxisScale::Linear && bounds.y_min <= 0.0 && bounds.y_max >= 0.0 {
+        let zero_y = ((1.0 - bounds.y_fraction(0.0)) * height.saturating_sub(1) as f64) as usize;
         if zero_y < height {
             for x in 5..width {
                 if grid[zero_y][x] == ' ' {
@@ -235,38 +296,65 @@ fn add_ansi_axes(
         }
     }
 
-    // Y-axis labels
-    for i in 0..5 {
-        let y = i * (height - 1) / 4;
-        let value = plot_max - (i as f64 / 4.0) * (plot_max - plot_min);
-        let label = format!("{value:4.1}");
-        for (j, ch) in label.chars().enumerate() {
-            if j < 4 && y < height {
-                grid[y][j] = ch;
-                colors[y][j] = 2;
-            }
+    add_ansi_y_labels(grid, colors, height, bounds);
+}
+
+/// Tick labels right-aligned against the Y axis, with a `┤` on the axis at each tick
+fn add_ansi_y_labels(grid: &mut [Vec<char>], colors: &mut [Vec<u8>], height: usize, bounds: &PlotBounds) {
+    let line = |fraction: f64| ((1.0 - fraction) * height.saturating_sub(1) as f64 + 1e-9) as usize;
+    for tick in bounds.y_axis_lines(5, line).major {
+        let y = line(tick.fraction);
+        if y >= height {
+            continue;
+        }
+        for (j, ch) in format!("{:>5}", tick.label).chars().enumerate().take(5) {
+            grid[y][j] = ch;
+            colors[y][j] = 2;
+        }
+        if grid[y][5] == '│' {
+            grid[y][5] = '┤';
         }
     }
 }
 
+/// The shared "X:" footer line with its prefix and tick labels colored
+fn ansi_x_tick_line(width: usize, bounds: &PlotBounds) -> String {
+    let line = x_tick_line(&bounds.x_axis(5).major, 6, width.saturating_sub(6), width + 2);
+    let labels: Vec<String> = line
+        .trim_end()
+        .trim_start_matches("X: ")
+        .split(' ')
+        .map(|label| match label {
+            "" => String::new(),
+            label => format!("\x1b[93m{label}\x1b[0m"),
+        })
+        .collect();
+    format!("\x1b[37mX: \x1b[0m{}\n", labels.join(" "))
+}
+
 fn format_ansi_output(
     grid: Vec<Vec<char>>,
     colors: Vec<Vec<u8>>,
     config: PlotConfig,
-    x_range: &ExpressionRange1dResult,
+    bounds: &PlotBounds,
+    settings: &PlotSettings,
 ) -> String {
     let mut output = format!(
-        "\x1b[36m┌─ ANSI Plot: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}] ─┐\x1b[0m\n",
+        "\x1b[36m┌─ {}: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}] ─┐\x1b[0m\n",
+        ansi_heading("ANSI Plot", settings),
         config.data_points, config.x_min, config.x_max, config.y_min, config.y_max
     );
 
-    for (row, color_row) in grid.into_iter().zip(colors.into_iter()) {
+    for (row, color_row) in grid.into_iter().zip(colors) {
         output.push_str("\x1b[36m│\x1b[0m");
-        for (ch, color) in row.into_iter().zip(color_row.into_iter()) {
+        for (ch, color) in row.into_iter().zip(color_row) {
             match color {
                 1 => output.push_str(&format!("\x1b[92m{ch}\x1b[0m")), // Green data
                 2 => output.push_str(&format!("\x1b[37m{ch}\x1b[0m")), // White axes
                 3 => output.push_str(&format!("\x1b[93m{ch}\x1b[0m")), // Yellow zero line
+                9 => output.push_str(&format!("\x1b[95m{ch}\x1b[0m")), // Magenta data
+                10 => output.push_str(&format!("\x1b[96m{ch}\x1b[0m")), // Cyan data
+                11 => output.push_str(&format!("\x1b[94m{ch}\x1b[0m")), // Blue data
                 _ => output.push(ch),
             }
         }
@@ -277,47 +365,31 @@ fn format_ansi_output(
     output.push_str(&"─".repeat(config.width + 1));
     output.push_str("┘\x1b[0m\n");
 
-    // X-axis labels
-    output.push_str("\x1b[37mX: \x1b[0m");
-    let num_markers = 5.min(config.data_points);
-    let data_width = config.width.saturating_sub(6);
+    output.push_str(&ansi_x_tick_line(config.width, bounds));
+    output.push_str(&ansi_label_footer(settings, false));
 
-    for i in 0..num_markers {
-        let x_index = if num_markers > 1 {
-            i * (config.data_points - 1) / (num_markers - 1)
-        } else {
-            0
-        };
-
-        let marker_pos = if num_markers > 1 {
-            5 + (i * data_width / (num_markers - 1))
-        } else {
-            config.width / 2
-        };
-
-        let x_value = if x_index < x_range.0.len() {
-            x_range.0[x_index]
-        } else {
-            config.x_max
-        };
-
-        if i == 0 {
-            output.push_str(&" ".repeat(marker_pos.saturating_sub(3)));
-        } else {
-            let prev_pos = if num_markers > 1 {
-                5 + ((i - 1) * data_width / (num_markers - 1))
-            } else {
-                config.width / 2
-            };
-            let spacing = marker_pos.saturating_sub(prev_pos).saturating_sub(4);
-            output.push_str(&" ".repeat(spacing));
-        }
+    output
+}
 
-        output.push_str(&format!("\x1b[93m{x_value:.1}\x1b[0m"));
+/// The shared header text, in bold when it is the plot title
+fn ansi_heading(default: &str, settings: &PlotSettings) -> String {
+    match settings.title {
+        Some(_) => format!("\x1b[1m{}\x1b[22m", settings.heading(default)),
+        None => settings.heading(default),
     }
-    output.push('\n');
+}
 
-    output
+/// One footer line naming each labelled axis, or nothing when no labels are set
+fn ansi_label_footer(settings: &PlotSettings, include_z: bool) -> String {
+    let labels = settings.axis_labels(include_z);
+    if labels.is_empty() {
+        return String::new();
+    }
+    let labels: Vec<String> = labels
+        .into_iter()
+        .map(|(axis, label)| format!("\x1b[37m{axis}:\x1b[0m \x1b[96m{label}\x1b[0m"))
+        .collect();
+    format!("\x1b[37mAxes:\x1b[0m {}\n", labels.join(", "))
 }
 
 fn add_ansi_parametric_axes(
@@ -325,12 +397,11 @@ fn add_ansi_parametric_axes(
     colors: &mut [Vec<u8>],
     width: usize,
     height: usize,
-    bounds: PlotBounds,
+    bounds: &PlotBounds,
 ) {
     // X-axis (check if 0 is in range)
-    if bounds.y_min <= 0.0 && bounds.y_max >= 0.0 {
-        let plot_y_range = bounds.y_max - bounds.y_min;
-        let zero_y = ((bounds.y_max - 0.0) / plot_y_range * (height - 1) as f64) as usize;
+    if bounds.y_scale == AxisScale::Linear && bounds.y_min <= 0.0 && bounds.y_max >= 0.0 {
+        let zero_y = ((1.0 - bounds.y_fraction(0.0)) * height.saturating_sub(1) as f64) as usize;
         if zero_y < height {
             for x in 5..width {
                 if grid[zero_y][x] == ' ' {
@@ -342,9 +413,8 @@ fn add_ansi_parametric_axes(
     }
 
     // Y-axis (check if 0 is in range)
-    if bounds.x_min <= 0.0 && bounds.x_max >= 0.0 {
-        let plot_x_range = bounds.x_max - bounds.x_min;
-        let zero_x = 5 + ((0.0 - bounds.x_min) / plot_x_range * (width - 6) as f64) as usize;
+    if bounds.x_scale == AxisScale::Linear && bounds.x_min <= 0.0 && bounds.x_max >= 0.0 {
+        let zero_x = 5 + (bounds.x_fraction(0.0) * width.saturating_sub(6) as f64) as usize;
         if zero_x < width {
             for y in 0..height {
                 if grid[y][zero_x] == ' ' {
@@ -356,36 +426,32 @@ fn add_ansi_parametric_axes(
     }
 
     // Y-axis labels on the left
-    for i in 0..5 {
-        let y = i * (height - 1) / 4;
-        let value = bounds.y_max - (i as f64 / 4.0) * (bounds.y_max - bounds.y_min);
-        let label = format!("{value:4.1}");
-        for (j, ch) in label.chars().enumerate() {
-            if j < 4 && y < height {
-                grid[y][j] = ch;
-                colors[y][j] = 2;
-            }
-        }
-    }
+    add_ansi_y_labels(grid, colors, height, bounds);
 }
 
 fn format_ansi_parametric_output(
     grid: Vec<Vec<char>>,
     colors: Vec<Vec<u8>>,
     config: PlotConfig,
+    bounds: &PlotBounds,
+    settings: &PlotSettings,
 ) -> String {
     let mut output = format!(
-        "\x1b[36m┌─ ANSI Parametric Plot: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}] ─┐\x1b[0m\n",
+        "\x1b[36m┌─ {}: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}] ─┐\x1b[0m\n",
+        ansi_heading("ANSI Parametric Plot", settings),
         config.data_points, config.x_min, config.x_max, config.y_min, config.y_max
     );
 
-    for (row, color_row) in grid.into_iter().zip(colors.into_iter()) {
+    for (row, color_row) in grid.into_iter().zip(colors) {
         output.push_str("\x1b[36m│\x1b[0m");
-        for (ch, color) in row.into_iter().zip(color_row.into_iter()) {
+        for (ch, color) in row.into_iter().zip(color_row) {
             match color {
                 1 => output.push_str(&format!("\x1b[92m{ch}\x1b[0m")), // Green data
                 2 => output.push_str(&format!("\x1b[37m{ch}\x1b[0m")), // White axes
                 3 => output.push_str(&format!("\x1b[93m{ch}\x1b[0m")), // Yellow zero line
+                9 => output.push_str(&format!("\x1b[95m{ch}\x1b[0m")), // Magenta data
+                10 => output.push_str(&format!("\x1b[96m{ch}\x1b[0m")), // Cyan data
+                11 => output.push_str(&format!("\x1b[94m{ch}\x1b[0m")), // Blue data
                 _ => output.push(ch),
             }
         }
@@ -396,18 +462,8 @@ fn format_ansi_parametric_output(
     output.push_str(&"─".repeat(config.width + 1));
     output.push_str("┘\x1b[0m\n");
 
-    // X-axis labels
-    output.push_str("\x1b[37mX: \x1b[0m");
-    for i in 0..5 {
-        let x_value = config.x_min + (i as f64 / 4.0) * (config.x_max - config.x_min);
-        if i == 0 {
-            output.push_str("  ");
-        } else {
-            output.push_str("              ");
-        }
-        output.push_str(&format!("\x1b[93m{x_value:.1}\x1b[0m"));
-    }
-    output.push('\n');
+    output.push_str(&ansi_x_tick_line(config.width, bounds));
+    output.push_str(&ansi_label_footer(settings, false));
 
     output
 }
@@ -416,15 +472,17 @@ fn format_ansi_surface3d_output(
     grid: Vec<Vec<char>>,
     colors: Vec<Vec<u8>>,
     config: Plot3DConfig,
+    settings: &PlotSettings,
 ) -> String {
     let mut output = format!(
-        "\x1b[36m┌─ ANSI 3D Surface: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}], Z:[{:.2}, {:.2}] ─┐\x1b[0m\n",
+        "\x1b[36m┌─ {}: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}], Z:[{:.2}, {:.2}] ─┐\x1b[0m\n",
+        ansi_heading("ANSI 3D Surface", settings),
         config.data_points, config.x_min, config.x_max, config.y_min, config.y_max, config.z_min, config.z_max
     );
 
-    for (row, color_row) in grid.into_iter().zip(colors.into_iter()) {
+    for (row, color_row) in grid.into_iter().zip(colors) {
         output.push_str("\x1b[36m│\x1b[0m");
-        for (ch, color) in row.into_iter().zip(color_row.into_iter()) {
+        for (ch, color) in row.into_iter().zip(color_row) {
             match color {
                 1 => output.push_str(&format!("\x1b[92m{ch}\x1b[0m")), // Green
                 2 => output.push_str(&format!("\x1b[94m{ch}\x1b[0m")), // Blue
@@ -443,6 +501,7 @@ fn format_ansi_surface3d_output(
     output.push_str("\x1b[36m└");
     output.push_str(&"─".repeat(config.width + 1));
     output.push_str("\x1b[36m┘\x1b[0m\n");
+    output.push_str(&ansi_label_footer(settings, true));
 
     output
 }
@@ -466,77 +525,58 @@ fn draw_ansi_3d_axes(grid: &mut [Vec<char>], colors: &mut [Vec<u8>], width: usiz
     ];
     
     let box_color = 2; // Gray for wireframe
-    let box_style = Plot3DStyle::new(data_width, data_height, '·', box_color);
-    
+    let box_style = Plot3DStyle::new(data_width, data_height, LineGlyphs::uniform('·'), box_color);
+
     // Draw the wireframe box
-    // Bottom face
-    draw_ansi_3d_line(grid, colors, &corners[0], &corners[1], bounds, Plot3DStyle::new(data_width, data_height, '·', box_color));
-    draw_ansi_3d_line(grid, colors, &corners[1], &corners[3], bounds, box_style);
-    draw_ansi_3d_line(grid, colors, &corners[3], &corners[2], bounds, box_style);
-    draw_ansi_3d_line(grid, colors, &corners[2], &corners[0], bounds, box_style);
-    
-    // Top face
-    draw_ansi_3d_line(grid, colors, &corners[4], &corners[5], bounds, box_style);
-    draw_ansi_3d_line(grid, colors, &corners[5], &corners[7], bounds, box_style);
-    draw_ansi_3d_line(grid, colors, &corners[7], &corners[6], bounds, box_style);
-    draw_ansi_3d_line(grid, colors, &corners[6], &corners[4], bounds, box_style);
-    
-    // Vertical edges
-    draw_ansi_3d_line(grid, colors, &corners[0], &corners[4], bounds, box_style);
-    draw_ansi_3d_line(grid, colors, &corners[1], &corners[5], bounds, box_style);
-    draw_ansi_3d_line(grid, colors, &corners[2], &corners[6], bounds, box_style);
-    draw_ansi_3d_line(grid, colors, &corners[3], &corners[7], bounds, box_style);
-    
-    // Draw main coordinate axes with distinct colored characters
-    // X-axis - use '━' character with red color (6)
-    draw_ansi_3d_line(grid, colors, &corners[0], &corners[1], bounds, Plot3DStyle::new(data_width, data_height, '━', 6));
-    
-    // Y-axis - use '┃' character with green color (7)
-    draw_ansi_3d_line(grid, colors, &corners[0], &corners[2], bounds, Plot3DStyle::new(data_width, data_height, '┃', 7));
-    
-    // Z-axis - use '▲' character with cyan color (8) for upward direction
-    draw_ansi_3d_line(grid, colors, &corners[0], &corners[4], bounds, Plot3DStyle::new(data_width, data_height, '▲', 8));
+    let edges = [
+        (0, 1), (1, 3), (3, 2), (2, 0), // Bottom face
+        (4, 5), (5, 7), (7, 6), (6, 4), // Top face
+        (0, 4), (1, 5), (2, 6), (3, 7), // Vertical edges
+    ];
+    for (from, to) in edges {
+        draw_ansi_3d_line(grid, colors, &corners[from], &corners[to], bounds, box_style);
+    }
+
+    // Draw the main coordinate axes as heavy lines: X red (6), Y green (7), Z cyan (8)
+    for (axis_end, color) in [(1, 6), (2, 7), (4, 8)] {
+        draw_ansi_3d_line(grid, colors, &corners[0], &corners[axis_end], bounds,
+                          Plot3DStyle::new(data_width, data_height, LineGlyphs::HEAVY, color));
+    }
 }
 
 fn draw_ansi_3d_line(grid: &mut [Vec<char>], colors: &mut [Vec<u8>], start: &Point3D, end: &Point3D, bounds: &SurfaceBounds,
                      style: Plot3DStyle) {
     let (start_x, start_y) = start.to_isometric(style.plot_width, style.plot_height, bounds);
     let (end_x, end_y) = end.to_isometric(style.plot_width, style.plot_height, bounds);
-    
-    // Apply margin offset
-    let start_screen_x = 5 + start_x;
-    let start_screen_y = 2 + start_y;
-    let end_screen_x = 5 + end_x;
-    let end_screen_y = 2 + end_y;
-    
-    // Simple line drawing using Bresenham-like algorithm
-    let dx = end_screen_x.abs_diff(start_screen_x);
-    let dy = end_screen_y.abs_diff(start_screen_y);
-    let x_step = if start_screen_x < end_screen_x { 1i32 } else { -1i32 };
-    let y_step = if start_screen_y < end_screen_y { 1i32 } else { -1i32 };
-
-    let mut error = dx as i32 - dy as i32;
-    let mut x = start_screen_x as i32;
-    let mut y = start_screen_y as i32;
-
-    loop {
-        if x >= 0 && x < grid[0].len() as i32 && y >= 0 && y < grid.len() as i32 {
-            grid[y as usize][x as usize] = style.axis_char;
-            colors[y as usize][x as usize] = style.axis_color;
-        }
 
-        if x == end_screen_x as i32 && y == end_screen_y as i32 {
-            break;
-        }
+    // Apply margin offset, aiming at the middle of each cell
+    let from = (5.0 + start_x as f64 + 0.5, 2.0 + start_y as f64 + 0.5);
+    let to = (5.0 + end_x as f64 + 0.5, 2.0 + end_y as f64 + 0.5);
 
-        let e2 = 2 * error;
-        if e2 > -(dy as i32) {
-            error -= dy as i32;
-            x += x_step;
+    for (x, y, ch) in line_cells(from, to, &style.glyphs) {
+        if y < grid.len() && x < grid[y].len() {
+            grid[y][x] = ch;
+            colors[y][x] = style.axis_color;
         }
-        if e2 < dx as i32 {
-            error += dx as i32;
-            y += y_step;
+    }
+}
+
+/// Text without its SGR escapes, `ESC [ ... m`
+fn strip_sgr(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                chars.by_ref().find(|&c| c == 'm');
+            }
+            c => plain.push(c),
         }
     }
-}
\ No newline at end of file
+    plain
+}
+
+/// Color code for the `index`th series of a plot, cycling through the data colors
+fn series_color(index: usize) -> u8 {
+    SERIES_COLORS[index % SERIES_COLORS.len()]
+}
@@ -0,0 +1,302 @@
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{
+    axis::Tick, lines::x_tick_line, terminal::ColorDepth, AxisScale, Bitmap, DisplayRenderer, PlotBounds, PlotSettings, Point3D, Rgba,
+    Segments, Stroke, SurfaceBounds, Theme,
+};
+
+/// Columns left of the plot for the Y tick labels
+const LABEL_WIDTH: usize = 6;
+/// Cells in the color bar under a heatmap
+const COLOR_BAR_WIDTH: usize = 24;
+
+/// Prints `▀` cells whose foreground and background colors are two pixels stacked
+/// on top of each other, doubling the vertical resolution of the text grid. Surfaces
+/// are shown from above as a heatmap.
+#[derive(Clone, Debug)]
+pub struct BlocksRenderer {
+    /// Colors the terminal shows; `None` prints every pixel that is not background
+    /// as the top or bottom half block, and dithers the heatmap
+    pub depth: Option<ColorDepth>,
+}
+
+impl BlocksRenderer {
+    pub fn new(depth: Option<ColorDepth>) -> Self {
+        Self { depth }
+    }
+}
+
+impl DisplayRenderer for BlocksRenderer {
+    fn render(
+        &self,
+        result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        x_range: &ExpressionRange1dResult,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.0.is_empty() {
+            return "No data to plot".to_string();
+        }
+
+        let Some(bounds) = PlotBounds::for_plot(&x_range.0, &result.0, settings) else {
+            return "No finite values to plot".to_string();
+        };
+
+        let points: Vec<(f64, f64)> = x_range.0.iter().copied().zip(result.0.iter().copied()).collect();
+        let pixels = plot_pixels(&Segments::split(&points, &bounds), &bounds, (width, height), settings.asymptotes, settings);
+
+        let heading = format!(
+            "{}: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}]",
+            settings.heading("Blocks Plot"),
+            result.0.len(), bounds.x_min, bounds.x_max, bounds.y_min, bounds.y_max
+        );
+        let mut output = self.format_output(&pixels, &settings.theme, &heading, &bounds.y_axis_lines(5, |fraction| tick_line(fraction, pixels.len())).major);
+        output.push_str(&blocks_x_tick_line(pixels[0].len(), &bounds.x_axis(5).major));
+        output.push_str(&settings.label_footer(false));
+        output
+    }
+
+    fn render_parametric(
+        &self,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.is_empty() {
+            return "No parametric data to plot".to_string();
+        }
+
+        let Some(bounds) = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings) else {
+            return "No finite parametric values to plot".to_string();
+        };
+
+        let points: Vec<(f64, f64)> = result.x_values.iter().copied().zip(result.y_values.iter().copied()).collect();
+        let pixels = plot_pixels(&Segments::split_curve(&points, &bounds), &bounds, (width, height), false, settings);
+
+        let heading = format!(
+            "{}: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}]",
+            settings.heading("Blocks Parametric Plot"),
+            result.len(), bounds.x_min, bounds.x_max, bounds.y_min, bounds.y_max
+        );
+        let mut output = self.format_output(&pixels, &settings.theme, &heading, &bounds.y_axis_lines(5, |fraction| tick_line(fraction, pixels.len())).major);
+        output.push_str(&blocks_x_tick_line(pixels[0].len(), &bounds.x_axis(5).major));
+        output.push_str(&settings.label_footer(false));
+        output
+    }
+
+    fn render_surface3d(
+        &self,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.is_empty() {
+            return "No 3D surface data to plot".to_string();
+        }
+
+        let Some(bounds) = SurfaceBounds::for_surface(result, settings) else {
+            return "No finite 3D surface values to plot".to_string();
+        };
+
+        let pixels = heatmap_pixels(result, &bounds, (width, height), &settings.theme, self.depth.is_none());
+
+        let heading = format!(
+            "{}: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}], Z:[{:.2}, {:.2}]",
+            settings.heading("Blocks Heatmap"),
+            result.x_len() * result.y_len(),
+            bounds.x_min, bounds.x_max, bounds.y_min, bounds.y_max, bounds.z_min, bounds.z_max
+        );
+        let mut output = self.format_output(&pixels, &settings.theme, &heading, &bounds.y_axis_lines(5, |fraction| tick_line(fraction, pixels.len())).major);
+        output.push_str(&blocks_x_tick_line(pixels[0].len(), &bounds.x_axis(5).major));
+        output.push_str(&self.color_bar(&bounds, &settings.theme));
+        output.push_str(&settings.label_footer(true));
+        output
+    }
+}
+
+impl BlocksRenderer {
+    /// Frame the pixels, two rows per line of text, with Y tick labels on the left.
+    /// Colors are only set again when they change along a line. Without colors, grid
+    /// lines are left out so they cannot be told apart from the curve.
+    fn format_output(&self, pixels: &[Vec<Rgba>], theme: &Theme, heading: &str, y_ticks: &[Tick]) -> String {
+        let columns = pixels.first().map_or(0, Vec::len);
+        let rows = pixels.len() / 2;
+
+        let mut labels = vec![None; rows];
+        for tick in y_ticks {
+            if let Some(label @ None) = labels.get_mut(tick_line(tick.fraction, pixels.len())) {
+                *label = Some(tick.label.as_str());
+            }
+        }
+
+        let ink = |color: Rgba| color != theme.background && color != theme.grid;
+        let mut output = format!("┌─ {heading} ─┐\n");
+        for (row, label) in labels.into_iter().enumerate() {
+            output.push('│');
+            output.push_str(&format!("{:>5} ", label.unwrap_or("")));
+            let mut current = None;
+            for colors in pixels[2 * row].iter().copied().zip(pixels[2 * row + 1].iter().copied()) {
+                let Some(depth) = self.depth else {
+                    output.push(half_block(ink(colors.0), ink(colors.1)));
+                    continue;
+                };
+                let (top, bottom) = (depth.sgr(colors.0.channels(), false), depth.sgr(colors.1.channels(), true));
+                let sgr = format!("\x1b[{top};{bottom}m");
+                if current.as_ref() != Some(&sgr) {
+                    output.push_str(&sgr);
+                    current = Some(sgr);
+                }
+                output.push('▀');
+            }
+            if current.is_some() {
+                output.push_str("\x1b[0m");
+            }
+            output.push_str("│\n");
+        }
+
+        output.push('└');
+        output.push_str(&"─".repeat(columns + LABEL_WIDTH));
+        output.push_str("┘\n");
+        output
+    }
+
+    /// "Z:" line showing the heatmap colors, or its dithering, from the lowest to the
+    /// highest height
+    fn color_bar(&self, bounds: &SurfaceBounds, theme: &Theme) -> String {
+        let mut bar = format!("Z: {:.2} ", bounds.z_min);
+        for i in 0..COLOR_BAR_WIDTH {
+            let fraction = i as f64 / (COLOR_BAR_WIDTH - 1) as f64;
+            match self.depth {
+                Some(depth) => bar.push_str(&format!("\x1b[{}m█", depth.sgr(theme.heat(fraction).channels(), false))),
+                None => bar.push(half_block(dithered(fraction, i, 0), dithered(fraction, i, 1))),
+            }
+        }
+        if self.depth.is_some() {
+            bar.push_str("\x1b[0m");
+        }
+        bar.push_str(&format!(" {:.2}\n", bounds.z_max));
+        bar
+    }
+}
+
+/// Draw a series on a canvas of one pixel per column and two per row, over the grid
+fn plot_pixels(
+    segments: &Segments,
+    bounds: &PlotBounds,
+    (width, height): (usize, usize),
+    asymptotes: bool,
+    settings: &PlotSettings,
+) -> Vec<Vec<Rgba>> {
+    let mut canvas = Bitmap::new(width.saturating_sub(LABEL_WIDTH).max(1), height.max(1) * 2, settings.theme);
+    canvas.antialias = false;
+    draw_grid(&mut canvas, bounds);
+    canvas.plot_samples(segments, bounds, (0, canvas.width, canvas.height), asymptotes, settings.style, &Stroke::solid(1.0));
+    canvas.data
+}
+
+/// Line of text, two rows of pixels each, holding a tick at `fraction` up `height` rows
+fn tick_line(fraction: f64, height: usize) -> usize {
+    ((1.0 - fraction) * height.saturating_sub(1) as f64).round() as usize / 2
+}
+
+/// Grid lines at the labelled ticks, and the axes along the left and bottom edges
+fn draw_grid(canvas: &mut Bitmap, bounds: &PlotBounds) {
+    let (width, height) = (canvas.width, canvas.height);
+    let (grid, axis) = (canvas.theme.grid, canvas.theme.axis);
+    for tick in bounds.x_axis(5).major {
+        let x = (tick.fraction * width.saturating_sub(1) as f64).round() as usize;
+        for y in 0..height {
+            canvas.set_pixel(x, y, grid);
+        }
+    }
+    for tick in bounds.y_axis_lines(5, |fraction| tick_line(fraction, height)).major {
+        let y = ((1.0 - tick.fraction) * height.saturating_sub(1) as f64).round() as usize;
+        for x in 0..width {
+            canvas.set_pixel(x, y, grid);
+        }
+    }
+    for x in 0..width {
+        canvas.set_pixel(x, height - 1, axis);
+    }
+    for y in 0..height {
+        canvas.set_pixel(0, y, axis);
+    }
+}
+
+/// The surface seen from above: every pixel takes the color of the height at the
+/// nearest grid point, the background where the surface is undefined or out of view.
+/// Dithered, pixels are either drawn or left as background, more of them the higher
+/// the surface.
+fn heatmap_pixels(
+    result: &Expression3dResult,
+    bounds: &SurfaceBounds,
+    (width, height): (usize, usize),
+    theme: &Theme,
+    dither: bool,
+) -> Vec<Vec<Rgba>> {
+    let (width, height) = (width.saturating_sub(LABEL_WIDTH).max(1), height.max(1) * 2);
+
+    // Nearest grid index for each pixel column and row, in the space the axes are drawn in
+    let nearest = |values: &[f64], scale: AxisScale, min: f64, max: f64, fraction: f64| {
+        let target = scale.forward(min) + fraction * (scale.forward(max) - scale.forward(min));
+        (0..values.len()).min_by(|&a, &b| {
+            let da = (scale.forward(values[a]) - target).abs();
+            let db = (scale.forward(values[b]) - target).abs();
+            da.total_cmp(&db)
+        })
+    };
+    let columns: Vec<Option<usize>> = (0..width)
+        .map(|px| nearest(&result.x_values, bounds.x_scale, bounds.x_min, bounds.x_max, (px as f64 + 0.5) / width as f64))
+        .collect();
+    let rows: Vec<Option<usize>> = (0..height)
+        .map(|py| nearest(&result.y_values, bounds.y_scale, bounds.y_min, bounds.y_max, 1.0 - (py as f64 + 0.5) / height as f64))
+        .collect();
+
+    rows.iter()
+        .enumerate()
+        .map(|(py, &y_idx)| {
+            columns
+                .iter()
+                .enumerate()
+                .map(|(px, &x_idx)| {
+                    let point = x_idx.zip(y_idx).and_then(|(x_idx, y_idx)| {
+                        let z = result.get_z(x_idx, y_idx)?;
+                        Some(Point3D::new(result.x_values[x_idx], result.y_values[y_idx], z))
+                    });
+                    match point.filter(|point| bounds.contains(point)).map(|point| bounds.z_fraction(point.z)) {
+                        Some(fraction) if !dither || dithered(fraction, px, py) => theme.heat(fraction),
+                        _ => theme.background,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Whether a pixel of a shade `fraction` of the way to full is drawn, by the 2x2
+/// ordered dither matrix
+fn dithered(fraction: f64, x: usize, y: usize) -> bool {
+    const THRESHOLDS: [[f64; 2]; 2] = [[0.125, 0.625], [0.875, 0.375]];
+    fraction > THRESHOLDS[y % 2][x % 2]
+}
+
+/// The half block showing which of the two pixels of a cell are drawn
+fn half_block(top: bool, bottom: bool) -> char {
+    match (top, bottom) {
+        (true, true) => '█',
+        (true, false) => '▀',
+        (false, true) => '▄',
+        (false, false) => ' ',
+    }
+}
+
+/// X tick labels under the pixel columns, which start right of the Y labels
+fn blocks_x_tick_line(columns: usize, ticks: &[Tick]) -> String {
+    x_tick_line(ticks, 1 + LABEL_WIDTH, columns.saturating_sub(1), columns + LABEL_WIDTH + 2)
+}
@@ -0,0 +1,238 @@
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{lines::x_tick_line, terminal::ColorDepth, AxisScale, Bitmap, DisplayRenderer, PlotBounds, PlotSettings, Rgba, Segments, Stroke, SurfaceBounds};
+
+/// Dots per character cell across and down
+const DOTS_X: usize = 2;
+const DOTS_Y: usize = 4;
+/// Bit of each dot of a cell in its U+2800 pattern, indexed by [row][column]
+const DOT_BITS: [[u32; DOTS_X]; DOTS_Y] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Renders onto a pixel canvas of 2x4 dots per character cell and prints it with
+/// Unicode Braille patterns, for four times the resolution of the ASCII renderer
+/// on terminals without graphics
+#[derive(Clone, Debug)]
+pub struct BrailleRenderer {
+    /// Color each cell with ANSI escapes after the dots it holds
+    pub color: bool,
+}
+
+impl BrailleRenderer {
+    pub fn new(color: bool) -> Self {
+        Self { color }
+    }
+}
+
+impl DisplayRenderer for BrailleRenderer {
+    fn render(
+        &self,
+        result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        x_range: &ExpressionRange1dResult,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.0.is_empty() {
+            return "No data to plot".to_string();
+        }
+
+        let Some(bounds) = PlotBounds::for_plot(&x_range.0, &result.0, settings) else {
+            return "No finite values to plot".to_string();
+        };
+
+        let mut canvas = braille_canvas(width, height, settings);
+        let layout = (0, canvas.width, canvas.height);
+        draw_zero_lines(&mut canvas, &bounds, false);
+
+        let points: Vec<(f64, f64)> = x_range.0.iter().copied().zip(result.0.iter().copied()).collect();
+        let segments = Segments::split(&points, &bounds);
+        canvas.plot_samples(&segments, &bounds, layout, settings.asymptotes, settings.style, &Stroke::solid(1.0));
+
+        let heading = format!(
+            "{}: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}]",
+            settings.heading("Braille Plot"),
+            result.0.len(), bounds.x_min, bounds.x_max, bounds.y_min, bounds.y_max
+        );
+        self.format_output(&canvas, &heading, Some(&bounds), settings)
+    }
+
+    fn render_parametric(
+        &self,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.is_empty() {
+            return "No parametric data to plot".to_string();
+        }
+
+        let Some(bounds) = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings) else {
+            return "No finite parametric values to plot".to_string();
+        };
+
+        let mut canvas = braille_canvas(width, height, settings);
+        let layout = (0, canvas.width, canvas.height);
+        draw_zero_lines(&mut canvas, &bounds, true);
+
+        let points: Vec<(f64, f64)> = result.x_values.iter().copied().zip(result.y_values.iter().copied()).collect();
+        let segments = Segments::split_curve(&points, &bounds);
+        canvas.plot_samples(&segments, &bounds, layout, false, settings.style, &Stroke::solid(1.0));
+
+        let heading = format!(
+            "{}: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}]",
+            settings.heading("Braille Parametric Plot"),
+            result.len(), bounds.x_min, bounds.x_max, bounds.y_min, bounds.y_max
+        );
+        self.format_output(&canvas, &heading, Some(&bounds), settings)
+    }
+
+    fn render_surface3d(
+        &self,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.is_empty() {
+            return "No 3D surface data to plot".to_string();
+        }
+
+        let Some(bounds) = SurfaceBounds::for_surface(result, settings) else {
+            return "No finite 3D surface values to plot".to_string();
+        };
+        if (bounds.z_max - bounds.z_min).abs() < f64::EPSILON {
+            return format!("3D Surface: constant Z = {:.3}", bounds.z_min);
+        }
+
+        let mut canvas = braille_canvas(width, height, settings);
+        let layout = (0, canvas.width, canvas.height);
+        canvas.plot_surface3d(result, &bounds, layout);
+
+        let heading = format!(
+            "{}: {} points, X:[{:.2}, {:.2}], Y:[{:.2}, {:.2}], Z:[{:.2}, {:.2}]",
+            settings.heading("Braille 3D Surface"),
+            result.x_len() * result.y_len(),
+            bounds.x_min, bounds.x_max, bounds.y_min, bounds.y_max, bounds.z_min, bounds.z_max
+        );
+        self.format_output(&canvas, &heading, None, settings)
+    }
+}
+
+impl BrailleRenderer {
+    /// Frame the canvas with a heading, Y tick labels on the left and X tick labels
+    /// underneath (when `bounds` is given), and the axis label footer
+    fn format_output(
+        &self,
+        canvas: &Bitmap,
+        heading: &str,
+        bounds: Option<&PlotBounds>,
+        settings: &PlotSettings,
+    ) -> String {
+        let columns = canvas.width / DOTS_X;
+        let rows = canvas.height / DOTS_Y;
+
+        // Y tick labels on the cell row holding the tick's dot, at most one per row
+        let row = |fraction: f64| ((1.0 - fraction) * canvas.height.saturating_sub(1) as f64).round() as usize / DOTS_Y;
+        let mut y_labels = vec![None; rows];
+        for tick in bounds.map(|bounds| bounds.y_axis_lines(5, row).major).unwrap_or_default() {
+            if let Some(label @ None) = y_labels.get_mut(row(tick.fraction)) {
+                *label = Some(tick.label);
+            }
+        }
+
+        let mut output = format!("┌─ {heading} ─┐\n");
+        for (row, label) in y_labels.into_iter().enumerate() {
+            output.push('│');
+            match label {
+                Some(label) => output.push_str(&format!("{label:>5}┤")),
+                None if bounds.is_some() => output.push_str("     │"),
+                None => output.push_str("      "),
+            }
+            for column in 0..columns {
+                let (pattern, color) = braille_cell(canvas, column, row);
+                match color.filter(|_| self.color) {
+                    // The nearest of the basic colors, which every color terminal has
+                    Some(color) => {
+                        let code = ColorDepth::Ansi16.sgr(color.channels(), false);
+                        output.push_str(&format!("\x1b[{code}m{pattern}\x1b[0m"));
+                    }
+                    None => output.push(pattern),
+                }
+            }
+            output.push_str("│\n");
+        }
+
+        output.push('└');
+        output.push_str(&"─".repeat(columns + 6));
+        output.push_str("┘\n");
+
+        if let Some(bounds) = bounds {
+            output.push_str(&x_tick_line(&bounds.x_axis(5).major, 7, columns.saturating_sub(1), columns + 8));
+        }
+        output.push_str(&settings.label_footer(bounds.is_none()));
+
+        output
+    }
+}
+
+/// Aliased canvas of dots for a `width` by `height` text plot, less the Y label
+/// column, and never smaller than one cell
+fn braille_canvas(width: usize, height: usize, settings: &PlotSettings) -> Bitmap {
+    let mut canvas = Bitmap::new(width.saturating_sub(6).max(1) * DOTS_X, height.max(1) * DOTS_Y, settings.theme);
+    canvas.antialias = false;
+    canvas
+}
+
+/// Braille pattern for one cell and the color that claims it, if any dot is set
+fn braille_cell(canvas: &Bitmap, column: usize, row: usize) -> (char, Option<Rgba>) {
+    let mut pattern = 0u32;
+    let mut counts: Vec<(Rgba, usize)> = Vec::new();
+    for (dy, bits) in DOT_BITS.iter().enumerate() {
+        for (dx, &bit) in bits.iter().enumerate() {
+            let (x, y) = (column * DOTS_X + dx, row * DOTS_Y + dy);
+            if canvas.is_background(x, y) {
+                continue;
+            }
+            pattern |= bit;
+            let color = canvas.get_pixel(x, y);
+            match counts.iter_mut().find(|(seen, _)| *seen == color) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((color, 1)),
+            }
+        }
+    }
+
+    // The most common color wins. Ties go to data, then labels and asymptotes, then
+    // axes, then grid lines, then any other color.
+    let theme = &canvas.theme;
+    let priority = [theme.series[0], theme.text, theme.axis, theme.grid];
+    let rank = |color: Rgba| priority.iter().position(|&claim| claim == color).unwrap_or(priority.len());
+    let color = counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(rank(b.0).cmp(&rank(a.0))))
+        .map(|(color, _)| color);
+    (char::from_u32(0x2800 + pattern).unwrap_or(' '), color)
+}
+
+/// Dotted lines along y = 0 and, for parametric plots, x = 0, when they are in view
+fn draw_zero_lines(canvas: &mut Bitmap, bounds: &PlotBounds, vertical: bool) {
+    let (width, height) = (canvas.width, canvas.height);
+    let axis = canvas.theme.axis;
+    if bounds.y_scale == AxisScale::Linear && bounds.y_min <= 0.0 && bounds.y_max >= 0.0 {
+        let y = ((1.0 - bounds.y_fraction(0.0)) * height.saturating_sub(1) as f64).round() as usize;
+        for x in (0..width).step_by(2) {
+            canvas.set_pixel(x, y, axis);
+        }
+    }
+    if vertical && bounds.x_scale == AxisScale::Linear && bounds.x_min <= 0.0 && bounds.x_max >= 0.0 {
+        let x = (bounds.x_fraction(0.0) * width.saturating_sub(1) as f64).round() as usize;
+        for y in (0..height).step_by(2) {
+            canvas.set_pixel(x, y, axis);
+        }
+    }
+}
+
@@ -0,0 +1,86 @@
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{base64, png, Bitmap, PlotData, DisplayRenderer, PlotSettings};
+
+/// Shows the plot as an inline PNG with the iTerm2 image protocol (iTerm2, WezTerm,
+/// mintty), placed at the cursor
+#[derive(Clone, Debug)]
+pub struct ItermRenderer {
+    /// Text area, in (columns, rows), the terminal should scale the image to fit
+    /// while keeping its aspect ratio. `None` shows the image pixel for pixel.
+    pub fit: Option<(usize, usize)>,
+}
+
+impl ItermRenderer {
+    pub fn new(fit: Option<(usize, usize)>) -> Self {
+        Self { fit }
+    }
+}
+
+impl DisplayRenderer for ItermRenderer {
+    fn render(
+        &self,
+        result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        x_range: &ExpressionRange1dResult,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.0.is_empty() {
+            return "No data to plot".to_string();
+        }
+
+        let bitmap = Bitmap::render(PlotData::Curve { x: x_range, y: result }, settings, width, height);
+
+        self.bitmap_to_iterm(&bitmap)
+    }
+
+    fn render_parametric(
+        &self,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.is_empty() {
+            return "No parametric data to plot".to_string();
+        }
+
+        let bitmap = Bitmap::render(PlotData::Parametric(result), settings, width, height);
+
+        self.bitmap_to_iterm(&bitmap)
+    }
+
+    fn render_surface3d(
+        &self,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.is_empty() {
+            return "No 3D surface data to plot".to_string();
+        }
+
+        let bitmap = Bitmap::render(PlotData::Surface(result), settings, width, height);
+
+        self.bitmap_to_iterm(&bitmap)
+    }
+}
+
+impl ItermRenderer {
+    /// `OSC 1337 ; File=inline=1;... : <base64 PNG> BEL`. Sizes given in cells are
+    /// a box the terminal fits the image into, keeping its aspect ratio.
+    fn bitmap_to_iterm(&self, bitmap: &Bitmap) -> String {
+        let image = png::encode_rgb(bitmap.width, bitmap.height, &bitmap.to_rgb());
+        let mut arguments = format!("inline=1;size={}", image.len());
+        match self.fit {
+            Some((columns, rows)) => arguments.push_str(&format!(";width={columns};height={rows};preserveAspectRatio=1")),
+            None => arguments.push_str(&format!(";width={}px;height={}px", bitmap.width, bitmap.height)),
+        }
+        format!("\x1b]1337;File={arguments}:{}\x07", base64::encode(&image))
+    }
+}
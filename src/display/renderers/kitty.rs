@@ -0,0 +1,218 @@
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{base64, Bitmap, PlotData, DisplayRenderer, PlotSettings};
+
+/// Base64 bytes per escape sequence; the protocol allows at most 4096
+const CHUNK_SIZE: usize = 4096;
+/// Character cells are about twice as tall as they are wide
+const CELL_ASPECT: f64 = 2.0;
+
+/// Shows the plot with the kitty graphics protocol (kitty, WezTerm, Konsole, ...):
+/// the bitmap is sent as raw RGB in base64 chunks and placed at the cursor
+#[derive(Clone, Debug)]
+pub struct KittyRenderer {
+    /// Text area, in (columns, rows), the terminal should scale the image to fit
+    /// while keeping its aspect ratio, when the cell size is not known
+    pub fit: Option<(usize, usize)>,
+    /// Size of a character cell in pixels. When known, the image is placed in the
+    /// cells it covers, so it takes exactly the rows the cursor moves past.
+    pub cell_pixels: Option<(f64, f64)>,
+}
+
+impl KittyRenderer {
+    pub fn new(fit: Option<(usize, usize)>, cell_pixels: Option<(f64, f64)>) -> Self {
+        Self { fit, cell_pixels }
+    }
+}
+
+impl DisplayRenderer for KittyRenderer {
+    fn render(
+        &self,
+        result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        x_range: &ExpressionRange1dResult,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.0.is_empty() {
+            return "No data to plot".to_string();
+        }
+
+        let bitmap = Bitmap::render(PlotData::Curve { x: x_range, y: result }, settings, width, height);
+
+        self.bitmap_to_kitty(&bitmap)
+    }
+
+    fn render_parametric(
+        &self,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.is_empty() {
+            return "No parametric data to plot".to_string();
+        }
+
+        let bitmap = Bitmap::render(PlotData::Parametric(result), settings, width, height);
+
+        self.bitmap_to_kitty(&bitmap)
+    }
+
+    fn render_surface3d(
+        &self,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> String {
+        if result.is_empty() {
+            return "No 3D surface data to plot".to_string();
+        }
+
+        let bitmap = Bitmap::render(PlotData::Surface(result), settings, width, height);
+
+        self.bitmap_to_kitty(&bitmap)
+    }
+}
+
+impl KittyRenderer {
+    /// Transmit and display the bitmap at the cursor in one go (`a=T`), as 24-bit RGB
+    /// (`f=24`) split over as many escape sequences as needed (`m=1` on all but the
+    /// last). `q=2` keeps the terminal from answering into the input stream.
+    fn bitmap_to_kitty(&self, bitmap: &Bitmap) -> String {
+        let mut control = format!("a=T,f=24,s={},v={},q=2", bitmap.width, bitmap.height);
+        let size = (bitmap.width, bitmap.height);
+        let cells = match self.cell_pixels {
+            Some(cell) => Some(cell_box(size, cell)),
+            None => self.fit.map(|area| fit_cells(size, area)),
+        };
+        if let Some((columns, rows)) = cells {
+            control.push_str(&format!(",c={columns},r={rows}"));
+        }
+
+        let payload = base64::encode(&bitmap.to_rgb());
+        let mut chunks = payload.as_bytes().chunks(CHUNK_SIZE).peekable();
+        let mut output = String::with_capacity(payload.len() + payload.len() / CHUNK_SIZE * 16 + control.len());
+        let mut first = true;
+        while let Some(chunk) = chunks.next() {
+            output.push_str("\x1b_G");
+            if first {
+                output.push_str(&control);
+                output.push(',');
+                first = false;
+            }
+            output.push_str(if chunks.peek().is_some() { "m=1;" } else { "m=0;" });
+            output.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+            output.push_str("\x1b\\");
+        }
+        output
+    }
+}
+
+/// The (columns, rows) an image covers at cells of the given pixel size
+fn cell_box((width, height): (usize, usize), (cell_width, cell_height): (f64, f64)) -> (usize, usize) {
+    let columns = (width as f64 / cell_width).round().max(1.0) as usize;
+    let rows = (height as f64 / cell_height).round().max(1.0) as usize;
+    (columns, rows)
+}
+
+/// Largest (columns, rows) inside `area` with the image's aspect ratio
+fn fit_cells((width, height): (usize, usize), (columns, rows): (usize, usize)) -> (usize, usize) {
+    let rows_at_full_width = (height as f64 * columns as f64 / (CELL_ASPECT * width as f64)).ceil() as usize;
+    if rows_at_full_width <= rows {
+        (columns, rows_at_full_width.max(1))
+    } else {
+        let columns_at_full_height = (CELL_ASPECT * width as f64 * rows as f64 / height as f64).round() as usize;
+        (columns_at_full_height.clamp(1, columns), rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::{Rgba, Theme};
+
+    fn decode_base64(text: &str) -> Vec<u8> {
+        let value = |byte: u8| match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => panic!("not base64: {byte}"),
+        };
+        let mut bytes = Vec::new();
+        for group in text.as_bytes().chunks(4) {
+            let digits: Vec<u8> = group.iter().copied().filter(|&byte| byte != b'=').map(value).collect();
+            let bits = digits.iter().enumerate().fold(0u32, |acc, (i, &digit)| acc | (digit as u32) << (18 - 6 * i));
+            bytes.extend((0..digits.len() - 1).map(|i| (bits >> (16 - 8 * i)) as u8));
+        }
+        bytes
+    }
+
+    /// The control data and payload of each `ESC _ G ... ESC \` sequence
+    fn split_commands(stream: &str) -> Vec<(&str, &str)> {
+        let mut commands = Vec::new();
+        let mut rest = stream;
+        while !rest.is_empty() {
+            let body = rest.strip_prefix("\x1b_G").expect("command start");
+            let end = body.find("\x1b\\").expect("command end");
+            commands.push(body[..end].split_once(';').expect("payload separator"));
+            rest = &body[end + 2..];
+        }
+        commands
+    }
+
+    fn test_bitmap() -> Bitmap {
+        let mut bitmap = Bitmap::new(64, 48, Theme::default());
+        for i in 0..48 {
+            bitmap.set_pixel(i, i, Rgba::new(255, 0, 0));
+            bitmap.set_pixel(63 - i, i, Rgba::new(0, 128, 255));
+        }
+        bitmap
+    }
+
+    #[test]
+    fn chunked_stream_decodes_to_the_bitmap() {
+        let bitmap = test_bitmap();
+        let stream = KittyRenderer::new(None, None).bitmap_to_kitty(&bitmap);
+        let commands = split_commands(&stream);
+        assert!(commands.len() > 1, "a 64x48 RGB image needs several chunks");
+
+        let (first_control, _) = commands[0];
+        assert!(first_control.starts_with("a=T,f=24,s=64,v=48,q=2,"));
+        assert!(!first_control.contains("c="));
+        let mut payload = String::new();
+        for (i, (control, chunk)) in commands.iter().enumerate() {
+            let last = i + 1 == commands.len();
+            assert!(control.ends_with(if last { "m=0" } else { "m=1" }));
+            assert!(chunk.len() <= CHUNK_SIZE);
+            if i > 0 {
+                assert_eq!(*control, if last { "m=0" } else { "m=1" });
+            }
+            payload.push_str(chunk);
+        }
+
+        let pixels = decode_base64(&payload);
+        assert_eq!(pixels.len(), 64 * 48 * 3);
+        assert_eq!(pixels, bitmap.to_rgb());
+        assert_eq!(&pixels[..3], &[255, 0, 0]);
+    }
+
+    #[test]
+    fn known_cell_size_places_the_image_in_its_cells() {
+        let stream = KittyRenderer::new(Some((200, 50)), Some((8.0, 16.0))).bitmap_to_kitty(&test_bitmap());
+        let (control, _) = split_commands(&stream)[0];
+        assert!(control.contains(",c=8,r=3,"));
+    }
+
+    #[test]
+    fn fit_keeps_the_aspect_ratio() {
+        assert_eq!(fit_cells((800, 400), (100, 50)), (100, 25));
+        assert_eq!(fit_cells((400, 800), (100, 50)), (50, 50));
+    }
+}
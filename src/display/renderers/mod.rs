@@ -1,9 +1,17 @@
 pub mod ascii;
 pub mod ansi;
+pub mod blocks;
+pub mod braille;
+pub mod iterm;
+pub mod kitty;
 pub mod regis;
 pub mod sixel;
 
 pub use ascii::AsciiRenderer;
 pub use ansi::AnsiRenderer;
+pub use blocks::BlocksRenderer;
+pub use braille::BrailleRenderer;
+pub use iterm::ItermRenderer;
+pub use kitty::KittyRenderer;
 pub use regis::RegisRenderer;
 pub use sixel::SixelRenderer;
\ No newline at end of file
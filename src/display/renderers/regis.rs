@@ -3,7 +3,7 @@ use crate::{
     parametric2d::Parametric2DResult,
 };
 
-use super::super::{DisplayRenderer, Point3D, SurfaceBounds};
+use super::super::{Autoscale, AxisTicks, DisplayRenderer, PlotBounds, Point3D, PlotSettings, Segments, SurfaceBounds};
 
 #[derive(Clone, Debug)]
 pub struct RegisRenderer;
@@ -15,15 +15,21 @@ impl DisplayRenderer for RegisRenderer {
         width: usize,
         height: usize,
         x_range: &ExpressionRange1dResult,
+        settings: &PlotSettings,
     ) -> String {
         if result.0.is_empty() {
             return "No data to plot".to_string();
         }
+        let Some(bounds) = PlotBounds::for_plot(&x_range.0, &result.0, settings) else {
+            return "No finite values to plot".to_string();
+        };
 
         let mut output = String::new();
         output.push_str(&regis_init(width, height));
-        output.push_str(&regis_draw_grid_and_axes(width, height, result, x_range));
-        output.push_str(&regis_plot_data(result, width, height));
+        output.push_str(&regis_draw_grid_and_axes(width, height, result, &bounds));
+        output.push_str(&regis_plot_data(result, x_range, width, height, &bounds, settings.asymptotes));
+        output.push_str(&regis_title(width, settings));
+        output.push_str(&regis_axis_labels(width, height, settings));
         output.push_str(&regis_finish());
         output
     }
@@ -33,15 +39,21 @@ impl DisplayRenderer for RegisRenderer {
         result: &Parametric2DResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> String {
         if result.is_empty() {
             return "No parametric data to plot".to_string();
         }
+        let Some(bounds) = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings) else {
+            return "No finite parametric values to plot".to_string();
+        };
 
         let mut output = String::new();
         output.push_str(&regis_init(width, height));
-        output.push_str(&regis_parametric_grid_and_axes(result, width, height));
-        output.push_str(&regis_parametric_plot(result, width, height));
+        output.push_str(&regis_parametric_grid_and_axes(result, width, height, &bounds));
+        output.push_str(&regis_parametric_plot(result, width, height, &bounds));
+        output.push_str(&regis_title(width, settings));
+        output.push_str(&regis_axis_labels(width, height, settings));
         output.push_str(&regis_finish());
         output
     }
@@ -51,15 +63,20 @@ impl DisplayRenderer for RegisRenderer {
         result: &Expression3dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> String {
         if result.is_empty() {
             return "No 3D surface data to plot".to_string();
         }
+        let Some(bounds) = SurfaceBounds::for_surface(result, settings) else {
+            return "No finite 3D surface values to plot".to_string();
+        };
 
         let mut output = String::new();
         output.push_str(&regis_init(width, height));
-        output.push_str(&regis_surface3d_grid_and_axes(result, width, height));
-        output.push_str(&regis_surface3d_plot(result, width, height));
+        output.push_str(&regis_surface3d_grid_and_axes(result, width, height, &bounds, settings));
+        output.push_str(&regis_surface3d_plot(result, width, height, &bounds));
+        output.push_str(&regis_title(width, settings));
         output.push_str(&regis_finish());
         output
     }
@@ -75,60 +92,76 @@ fn regis_finish() -> String {
     "\x1b\\".to_string()
 }
 
+/// Quote text for a ReGIS `T` command, where a literal apostrophe is written twice
+fn regis_text(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Title centered along the top edge
+fn regis_title(width: usize, settings: &PlotSettings) -> String {
+    match &settings.title {
+        Some(title) => {
+            let text_x = (width / 2).saturating_sub(title.chars().count() * 9);
+            format!("W(P0)\nS(C1)\nP[{text_x},2]\nT(S2){}\n", regis_text(title))
+        }
+        None => String::new(),
+    }
+}
+
+/// X label centered along the bottom edge, Y label rotated along the left edge
+fn regis_axis_labels(width: usize, height: usize, settings: &PlotSettings) -> String {
+    let mut output = String::from("W(P0)\nS(C1)\n");
+
+    if let Some(xlabel) = &settings.xlabel {
+        let text_x = (width / 2).saturating_sub(xlabel.chars().count() * 4);
+        output.push_str(&format!("P[{text_x},{}]\nT(S1){}\n", height.saturating_sub(20), regis_text(xlabel)));
+    }
+    if let Some(ylabel) = &settings.ylabel {
+        let text_y = (height / 2 + ylabel.chars().count() * 4).min(height.saturating_sub(5));
+        output.push_str(&format!("P[2,{text_y}]\nT(D90,S1){}\nT(D0)\n", regis_text(ylabel)));
+    }
+
+    output
+}
+
+/// Axis name for info lines, e.g. `X (time)` when the axis carries a label
+fn regis_axis_caption(axis: &str, label: &Option<String>) -> String {
+    match label {
+        Some(label) => format!("{axis} ({})", label.replace('\'', "''")),
+        None => axis.to_string(),
+    }
+}
+
 fn regis_draw_grid_and_axes(
     width: usize,
     height: usize,
     result: &ExpressionRange1dResult,
-    x_range: &ExpressionRange1dResult,
+    bounds: &PlotBounds,
 ) -> String {
     let mut output = String::new();
 
-    let y_min = result.min();
-    let y_max = result.max();
-    let y_range = y_max - y_min;
-    let x_min = x_range.min();
-    let x_max = x_range.max();
-    let x_range_val = x_max - x_min;
-
-    let y_padding = if y_range > 0.0 { y_range * 0.1 } else { 1.0 };
-    let plot_y_min = y_min - y_padding;
-    let plot_y_max = y_max + y_padding;
-    let plot_y_range = plot_y_max - plot_y_min;
-
-    let data_to_screen_x = |x_data: f64| -> usize {
-        if x_range_val > 0.0 {
-            ((x_data - x_min) / x_range_val * width as f64) as usize
-        } else {
-            width / 2
-        }
-    };
+    let (y_min, y_max) = Autoscale::Full
+        .extent(result.0.iter().copied())
+        .unwrap_or((bounds.y_min, bounds.y_max));
+    let x_min = bounds.x_min;
+    let x_max = bounds.x_max;
+    let plot_y_min = bounds.y_min;
+    let plot_y_max = bounds.y_max;
 
-    let data_to_screen_y = |y_data: f64| -> usize {
-        if plot_y_range > 0.0 {
-            let normalized = (y_data - plot_y_min) / plot_y_range;
-            ((1.0 - normalized) * height as f64) as usize
-        } else {
-            height / 2
-        }
-    };
+    let data_to_screen_x = |x_data: f64| -> usize { (bounds.x_fraction(x_data) * width as f64) as usize };
+    let data_to_screen_y = |y_data: f64| -> usize { ((1.0 - bounds.y_fraction(y_data)) * height as f64) as usize };
 
     // Draw grid lines
-    output.push_str("W(P3)\nS(C2)\n");
-    for i in 1..10 {
-        let x = (i * width) / 10;
-        output.push_str(&format!("P[{x},0]\nV[{x},{height}]\n"));
-    }
-    for i in 1..8 {
-        let y = (i * height) / 8;
-        output.push_str(&format!("P[0,{y}]\nV[{width},{y}]\n"));
-    }
+    let x_axis = bounds.x_axis(6);
+    let y_axis = bounds.y_axis(6);
+    output.push_str(&regis_grid(width, height, &x_axis, &y_axis));
 
     // Draw axes
     output.push_str("W(P0)\nS(C1)\n");
     let x_axis_y = if plot_y_min <= 0.0 && plot_y_max >= 0.0 {
         data_to_screen_y(0.0)
     } else {
-        height - 1
+        height.saturating_sub(1)
     };
     output.push_str(&format!("P[0,{x_axis_y}]\nV[{width},{x_axis_y}]\n"));
     let y_axis_x = if x_min <= 0.0 && x_max >= 0.0 {
@@ -141,49 +174,15 @@ fn regis_draw_grid_and_axes(
     // Add axis labels
     output.push_str("W(P2)\nS(C1)\n");
 
-    // X-axis labels
-    for i in 0..=5 {
-        let x_screen = (i * width) / 5;
-        let x_data = x_min + (i as f64 / 5.0) * x_range_val;
-        let label_y = (x_axis_y + 20).min(height - 10);
-
-        let label = if x_data.abs() < 0.01 {
-            "0".to_string()
-        } else if x_data.abs() >= 1000.0 || x_data.fract() == 0.0 {
-            format!("{x_data:.0}")
-        } else {
-            format!("{x_data:.1}")
-        };
-
-        let text_x = x_screen.saturating_sub(label.len() * 3);
-        output.push_str(&format!("P[{text_x},{label_y}]\nT(S1)'{label}'\n"));
-    }
-
-    // Y-axis labels
-    for i in 0..=5 {
-        let y_screen = (i * height) / 5;
-        let y_data = plot_y_max - (i as f64 / 5.0) * plot_y_range;
-        let label_x = y_axis_x.saturating_sub(30).max(5);
-
-        let label = if y_data.abs() < 0.01 {
-            "0".to_string()
-        } else if y_data.abs() >= 1000.0 {
-            format!("{y_data:.0}")
-        } else {
-            format!("{y_data:.2}")
-        };
-
-        let text_y = y_screen.saturating_sub(5);
-        output.push_str(&format!("P[{label_x},{text_y}]\nT(S1)'{label}'\n"));
-    }
+    output.push_str(&regis_tick_labels(width, height, (&x_axis, &y_axis), (x_axis_y, y_axis_x)));
 
     // Add Info
     output.push_str(&format!("P[5,15]\nT(S2)'Max: {y_max:.2}'\n"));
-    output.push_str(&format!("P[5,{}]\nT(S2)'Min: {:.2}'\n", height - 30, y_min));
+    output.push_str(&format!("P[5,{}]\nT(S2)'Min: {:.2}'\n", height.saturating_sub(30), y_min));
     output.push_str(&format!(
         "P[{},{}]\nT(S2)'X: {:.1} to {:.1}'\n",
-        width - 100,
-        height - 15,
+        width.saturating_sub(100),
+        height.saturating_sub(15),
         x_min,
         x_max
     ));
@@ -191,76 +190,87 @@ fn regis_draw_grid_and_axes(
     output
 }
 
-fn regis_plot_data(result: &ExpressionRange1dResult, width: usize, height: usize) -> String {
-    if result.0.is_empty() {
-        return String::new();
+/// Grid lines at the major ticks of both axes
+fn regis_grid(width: usize, height: usize, x_axis: &AxisTicks, y_axis: &AxisTicks) -> String {
+    let mut output = String::from("W(P3)\nS(C2)\n");
+    for fraction in x_axis.interior() {
+        let x = (fraction * width as f64) as usize;
+        output.push_str(&format!("P[{x},0]\nV[{x},{height}]\n"));
+    }
+    for fraction in y_axis.interior() {
+        let y = ((1.0 - fraction) * height as f64) as usize;
+        output.push_str(&format!("P[0,{y}]\nV[{width},{y}]\n"));
     }
+    output
+}
 
-    let mut output = String::new();
+/// Minor tick marks on the axes and the major tick labels beside them
+fn regis_tick_labels(
+    width: usize,
+    height: usize,
+    (x_axis, y_axis): (&AxisTicks, &AxisTicks),
+    (x_axis_y, y_axis_x): (usize, usize),
+) -> String {
+    let mut output = String::from("W(P0)\nS(C1)\n");
+    for &fraction in &x_axis.minor {
+        let x = (fraction * width as f64) as usize;
+        output.push_str(&format!("P[{x},{}]\nV[{x},{}]\n", x_axis_y.saturating_sub(4), x_axis_y + 4));
+    }
+    for &fraction in &y_axis.minor {
+        let y = ((1.0 - fraction) * height as f64) as usize;
+        output.push_str(&format!("P[{},{y}]\nV[{},{y}]\n", y_axis_x.saturating_sub(4), y_axis_x + 4));
+    }
 
-    let y_min = result.min();
-    let y_max = result.max();
-    let y_range = y_max - y_min;
+    output.push_str("W(P2)\nS(C1)\n");
+    let label_y = (x_axis_y + 20).min(height.saturating_sub(10));
+    for tick in &x_axis.major {
+        let x_screen = (tick.fraction * width as f64) as usize;
+        let text_x = x_screen.saturating_sub(tick.label.len() * 3);
+        output.push_str(&format!("P[{text_x},{label_y}]\nT(S1)'{}'\n", tick.label));
+    }
+    for tick in &y_axis.major {
+        let y_screen = ((1.0 - tick.fraction) * height as f64) as usize;
+        let label_x = y_axis_x.saturating_sub(tick.label.len() * 6 + 6).max(5);
+        output.push_str(&format!("P[{label_x},{}]\nT(S1)'{}'\n", y_screen.saturating_sub(5), tick.label));
+    }
+    output
+}
 
-    let y_padding = if y_range > 0.0 { y_range * 0.1 } else { 1.0 };
-    let plot_y_min = y_min - y_padding;
-    let plot_y_max = y_max + y_padding;
-    let plot_y_range = plot_y_max - plot_y_min;
+fn regis_plot_data(
+    result: &ExpressionRange1dResult,
+    x_range: &ExpressionRange1dResult,
+    width: usize,
+    height: usize,
+    bounds: &PlotBounds,
+    asymptotes: bool,
+) -> String {
+    if result.0.is_empty() {
+        return String::new();
+    }
 
-    let data_to_screen_x = |index: usize| -> usize {
-        if result.0.len() > 1 {
-            (index * width) / (result.0.len() - 1)
-        } else {
-            width / 2
-        }
-    };
+    let mut output = String::new();
+    let points: Vec<(f64, f64)> = x_range.0.iter().copied().zip(result.0.iter().copied()).collect();
 
-    let data_to_screen_y = |y_data: f64| -> usize {
-        if plot_y_range > 0.0 {
-            let normalized = (y_data - plot_y_min) / plot_y_range;
-            let screen_y = ((1.0 - normalized) * height as f64) as usize;
-            screen_y.min(height - 1)
-        } else {
-            height / 2
-        }
-    };
+    let segments = Segments::split(&points, bounds);
+    if asymptotes {
+        output.push_str(&regis_asymptotes(&segments.asymptotes, width, height, bounds));
+    }
 
     output.push_str("W(P1)\nS(C3)\n");
-
-    if result.0.len() > 1 {
-        let x0 = data_to_screen_x(0);
-        let y0 = data_to_screen_y(result.0[0]);
-        output.push_str(&format!("P[{x0},{y0}]\n"));
-
-        for (i, &y_val) in result.0.iter().enumerate().skip(1) {
-            let x = data_to_screen_x(i);
-            let y = data_to_screen_y(y_val);
-            output.push_str(&format!("V[{x},{y}]\n"));
-        }
-
-        let x0 = data_to_screen_x(0);
-        let y0 = data_to_screen_y(result.0[0])
-            .saturating_add(1)
-            .min(height - 1);
-        output.push_str(&format!("P[{x0},{y0}]\n"));
-
-        for (i, &y_val) in result.0.iter().enumerate().skip(1) {
-            let x = data_to_screen_x(i);
-            let y = data_to_screen_y(y_val).saturating_add(1).min(height - 1);
-            output.push_str(&format!("V[{x},{y}]\n"));
-        }
+    for piece in &segments.pieces {
+        output.push_str(&regis_polyline(piece, width, height, bounds, 0));
+        output.push_str(&regis_polyline(piece, width, height, bounds, 1));
     }
 
     output.push_str("S(C4)\n");
-    for (i, &y_val) in result.0.iter().enumerate() {
-        let x = data_to_screen_x(i);
-        let y = data_to_screen_y(y_val);
+    for &(x_val, y_val) in points.iter().filter(|&&(x, y)| bounds.contains(x, y)) {
+        let (x, y) = regis_screen_point((x_val, y_val), width, height, bounds);
 
         let size = 1;
         for dx in 0..=size {
             for dy in 0..=size {
-                let px = x.saturating_add(dx).min(width - 1);
-                let py = y.saturating_add(dy).min(height - 1);
+                let px = x.saturating_add(dx).min(width.saturating_sub(1));
+                let py = y.saturating_add(dy).min(height.saturating_sub(1));
                 output.push_str(&format!("P[{px},{py}]\nV[{px},{py}]\n"));
             }
         }
@@ -270,70 +280,81 @@ fn regis_plot_data(result: &ExpressionRange1dResult, width: usize, height: usize
     output.push_str("S(C1)\n");
     output.push_str(&format!(
         "P[{},25]\nT(S1)'Data: {} points'\n",
-        width - 150,
+        width.saturating_sub(150),
         result.0.len()
     ));
     output.push_str(&format!(
         "P[{},45]\nT(S1)'Y: {:.2} to {:.2}'\n",
-        width - 150,
-        y_min,
-        y_max
+        width.saturating_sub(150),
+        bounds.y_min,
+        bounds.y_max
     ));
 
     output
 }
 
+/// Screen position of a data point inside the window
+fn regis_screen_point(point: (f64, f64), width: usize, height: usize, bounds: &PlotBounds) -> (usize, usize) {
+    let x = (bounds.x_fraction(point.0) * width as f64) as usize;
+    let y = ((1.0 - bounds.y_fraction(point.1)) * height as f64) as usize;
+    (x.min(width.saturating_sub(1)), y.min(height.saturating_sub(1)))
+}
+
+/// Dashed vertical lines across the window at the given X positions
+fn regis_asymptotes(positions: &[f64], width: usize, height: usize, bounds: &PlotBounds) -> String {
+    let mut output = String::from("W(P2)\nS(C2)\n");
+    for &x_val in positions {
+        let x = ((bounds.x_fraction(x_val) * width as f64) as usize).min(width.saturating_sub(1));
+        output.push_str(&format!("P[{x},0]\nV[{x},{}]\n", height.saturating_sub(1)));
+    }
+    output
+}
+
+/// Polyline through consecutive points, clipped to the window and broken wherever
+/// it leaves it. `offset` shifts it down a few pixels to thicken the line.
+fn regis_polyline(points: &[(f64, f64)], width: usize, height: usize, bounds: &PlotBounds, offset: usize) -> String {
+    let mut output = String::new();
+    let mut pen: Option<(usize, usize)> = None;
+
+    for pair in points.windows(2) {
+        let Some((start, end)) = bounds.clip_segment(pair[0], pair[1]) else {
+            pen = None;
+            continue;
+        };
+        let (x0, y0) = regis_screen_point(start, width, height, bounds);
+        let (x1, y1) = regis_screen_point(end, width, height, bounds);
+        let (y0, y1) = ((y0 + offset).min(height.saturating_sub(1)), (y1 + offset).min(height.saturating_sub(1)));
+
+        if pen != Some((x0, y0)) {
+            output.push_str(&format!("P[{x0},{y0}]\n"));
+        }
+        output.push_str(&format!("V[{x1},{y1}]\n"));
+        pen = Some((x1, y1));
+    }
+
+    output
+}
+
 fn regis_parametric_grid_and_axes(
     result: &Parametric2DResult,
     width: usize,
     height: usize,
+    bounds: &PlotBounds,
 ) -> String {
     let mut output = String::new();
 
-    let x_min = result.x_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-    let x_max = result.x_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-    let y_min = result.y_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-    let y_max = result.y_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-
-    let x_range = x_max - x_min;
-    let y_range = y_max - y_min;
-
-    let x_padding = if x_range > 0.0 { x_range * 0.1 } else { 1.0 };
-    let y_padding = if y_range > 0.0 { y_range * 0.1 } else { 1.0 };
-    let plot_x_min = x_min - x_padding;
-    let plot_x_max = x_max + x_padding;
-    let plot_y_min = y_min - y_padding;
-    let plot_y_max = y_max + y_padding;
-    let plot_x_range = plot_x_max - plot_x_min;
-    let plot_y_range = plot_y_max - plot_y_min;
-
-    let data_to_screen_x = |x_data: f64| -> usize {
-        if plot_x_range > 0.0 {
-            ((x_data - plot_x_min) / plot_x_range * width as f64) as usize
-        } else {
-            width / 2
-        }
-    };
+    let plot_x_min = bounds.x_min;
+    let plot_x_max = bounds.x_max;
+    let plot_y_min = bounds.y_min;
+    let plot_y_max = bounds.y_max;
 
-    let data_to_screen_y = |y_data: f64| -> usize {
-        if plot_y_range > 0.0 {
-            let normalized = (y_data - plot_y_min) / plot_y_range;
-            ((1.0 - normalized) * height as f64) as usize
-        } else {
-            height / 2
-        }
-    };
+    let data_to_screen_x = |x_data: f64| -> usize { (bounds.x_fraction(x_data) * width as f64) as usize };
+    let data_to_screen_y = |y_data: f64| -> usize { ((1.0 - bounds.y_fraction(y_data)) * height as f64) as usize };
 
     // Draw grid lines
-    output.push_str("W(P3)\nS(C2)\n");
-    for i in 1..10 {
-        let x = (i * width) / 10;
-        output.push_str(&format!("P[{x},0]\nV[{x},{height}]\n"));
-    }
-    for i in 1..8 {
-        let y = (i * height) / 8;
-        output.push_str(&format!("P[0,{y}]\nV[{width},{y}]\n"));
-    }
+    let x_axis = bounds.x_axis(6);
+    let y_axis = bounds.y_axis(6);
+    output.push_str(&regis_grid(width, height, &x_axis, &y_axis));
 
     // Draw axes
     output.push_str("W(P0)\nS(C1)\n");
@@ -342,7 +363,7 @@ fn regis_parametric_grid_and_axes(
     let x_axis_y = if plot_y_min <= 0.0 && plot_y_max >= 0.0 {
         data_to_screen_y(0.0)
     } else {
-        height - 1
+        height.saturating_sub(1)
     };
     output.push_str(&format!("P[0,{x_axis_y}]\nV[{width},{x_axis_y}]\n"));
     
@@ -357,107 +378,32 @@ fn regis_parametric_grid_and_axes(
     // Add axis labels
     output.push_str("W(P2)\nS(C1)\n");
 
-    // X-axis labels
-    for i in 0..=5 {
-        let x_screen = (i * width) / 5;
-        let x_data = plot_x_min + (i as f64 / 5.0) * plot_x_range;
-        let label_y = (x_axis_y + 20).min(height - 10);
-
-        let label = if x_data.abs() < 0.01 {
-            "0".to_string()
-        } else if x_data.abs() >= 1000.0 || x_data.fract() == 0.0 {
-            format!("{x_data:.0}")
-        } else {
-            format!("{x_data:.1}")
-        };
-
-        let text_x = x_screen.saturating_sub(label.len() * 3);
-        output.push_str(&format!("P[{text_x},{label_y}]\nT(S1)'{label}'\n"));
-    }
-
-    // Y-axis labels
-    for i in 0..=5 {
-        let y_screen = (i * height) / 5;
-        let y_data = plot_y_max - (i as f64 / 5.0) * plot_y_range;
-        let label_x = y_axis_x.saturating_sub(30).max(5);
-
-        let label = if y_data.abs() < 0.01 {
-            "0".to_string()
-        } else if y_data.abs() >= 1000.0 {
-            format!("{y_data:.0}")
-        } else {
-            format!("{y_data:.2}")
-        };
-
-        let text_y = y_screen.saturating_sub(5);
-        output.push_str(&format!("P[{label_x},{text_y}]\nT(S1)'{label}'\n"));
-    }
+    output.push_str(&regis_tick_labels(width, height, (&x_axis, &y_axis), (x_axis_y, y_axis_x)));
 
     // Add plot info
     output.push_str(&format!("P[5,15]\nT(S2)'Parametric Plot: {} points'\n", result.len()));
-    output.push_str(&format!("P[5,30]\nT(S2)'X: {x_min:.2} to {x_max:.2}'\n"));
-    output.push_str(&format!("P[5,45]\nT(S2)'Y: {y_min:.2} to {y_max:.2}'\n"));
+    output.push_str(&format!("P[5,30]\nT(S2)'X: {plot_x_min:.2} to {plot_x_max:.2}'\n"));
+    output.push_str(&format!("P[5,45]\nT(S2)'Y: {plot_y_min:.2} to {plot_y_max:.2}'\n"));
 
     output
 }
 
-fn regis_parametric_plot(result: &Parametric2DResult, width: usize, height: usize) -> String {
+fn regis_parametric_plot(result: &Parametric2DResult, width: usize, height: usize, bounds: &PlotBounds) -> String {
     if result.is_empty() {
         return String::new();
     }
 
+    let points: Vec<(f64, f64)> = result
+        .x_values
+        .iter()
+        .copied()
+        .zip(result.y_values.iter().copied())
+        .collect();
+
     let mut output = String::new();
     output.push_str("W(P1)\nS(C3)\n");
-
-    let x_min = result.x_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-    let x_max = result.x_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-    let y_min = result.y_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-    let y_max = result.y_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-
-    let x_range = x_max - x_min;
-    let y_range = y_max - y_min;
-
-    if x_range.abs() < f64::EPSILON || y_range.abs() < f64::EPSILON {
-        return output;
-    }
-
-    // Add padding to match the axes coordinate system
-    let x_padding = x_range * 0.1;
-    let y_padding = y_range * 0.1;
-    let plot_x_min = x_min - x_padding;
-    let plot_x_max = x_max + x_padding;
-    let plot_y_min = y_min - y_padding;
-    let plot_y_max = y_max + y_padding;
-    let plot_x_range = plot_x_max - plot_x_min;
-    let plot_y_range = plot_y_max - plot_y_min;
-
-    let data_to_screen_x = |x_val: f64| -> usize {
-        if plot_x_range > 0.0 {
-            ((x_val - plot_x_min) / plot_x_range * width as f64) as usize
-        } else {
-            width / 2
-        }
-    };
-
-    let data_to_screen_y = |y_val: f64| -> usize {
-        if plot_y_range > 0.0 {
-            let normalized = (y_val - plot_y_min) / plot_y_range;
-            ((1.0 - normalized) * height as f64) as usize
-        } else {
-            height / 2
-        }
-    };
-
-    if let (Some(&first_x), Some(&first_y)) = (result.x_values.first(), result.y_values.first()) {
-        let x0 = data_to_screen_x(first_x);
-        let y0 = data_to_screen_y(first_y);
-        output.push_str(&format!("P[{x0},{y0}]\n"));
-
-        for (&x_val, &y_val) in result.x_values.iter().zip(result.y_values.iter()).skip(1) {
-            let x = data_to_screen_x(x_val);
-            let y = data_to_screen_y(y_val);
-            output.push_str(&format!("V[{x},{y}]\n"));
-        }
+    for piece in Segments::split_curve(&points, bounds).pieces {
+        output.push_str(&regis_polyline(&piece, width, height, bounds, 0));
     }
 
     output
@@ -467,15 +413,12 @@ fn regis_surface3d_grid_and_axes(
     result: &Expression3dResult,
     width: usize,
     height: usize,
+    bounds: &SurfaceBounds,
+    settings: &PlotSettings,
 ) -> String {
     let mut output = String::new();
 
-    let x_min = result.x_min();
-    let x_max = result.x_max();
-    let y_min = result.y_min();
-    let y_max = result.y_max();
-    let z_min = result.z_min();
-    let z_max = result.z_max();
+    let SurfaceBounds { x_min, x_max, y_min, y_max, z_min, z_max, .. } = *bounds;
 
     // Draw grid lines
     output.push_str("W(P3)\nS(C2)\n");
@@ -496,14 +439,17 @@ fn regis_surface3d_grid_and_axes(
     // Add axis labels
     output.push_str("W(P2)\nS(C1)\n");
     output.push_str(&format!("P[5,15]\nT(S2)'3D Surface: {}x{} points'\n", result.x_len(), result.y_len()));
-    output.push_str(&format!("P[5,30]\nT(S2)'X: {x_min:.2} to {x_max:.2}'\n"));
-    output.push_str(&format!("P[5,45]\nT(S2)'Y: {y_min:.2} to {y_max:.2}'\n"));
-    output.push_str(&format!("P[5,60]\nT(S2)'Z: {z_min:.2} to {z_max:.2}'\n"));
+    let x_caption = regis_axis_caption("X", &settings.xlabel);
+    let y_caption = regis_axis_caption("Y", &settings.ylabel);
+    let z_caption = regis_axis_caption("Z", &settings.zlabel);
+    output.push_str(&format!("P[5,30]\nT(S2)'{x_caption}: {x_min:.2} to {x_max:.2}'\n"));
+    output.push_str(&format!("P[5,45]\nT(S2)'{y_caption}: {y_min:.2} to {y_max:.2}'\n"));
+    output.push_str(&format!("P[5,60]\nT(S2)'{z_caption}: {z_min:.2} to {z_max:.2}'\n"));
 
     output
 }
 
-fn regis_surface3d_plot(result: &Expression3dResult, width: usize, height: usize) -> String {
+fn regis_surface3d_plot(result: &Expression3dResult, width: usize, height: usize, bounds: &SurfaceBounds) -> String {
     if result.is_empty() {
         return String::new();
     }
@@ -511,17 +457,14 @@ fn regis_surface3d_plot(result: &Expression3dResult, width: usize, height: usize
     let mut output = String::new();
     output.push_str("W(P1)\nS(C3)\n");
 
-    let z_min = result.z_min();
-    let z_max = result.z_max();
-    let z_range = z_max - z_min;
+    let z_range = bounds.z_max - bounds.z_min;
 
     if z_range.abs() < f64::EPSILON {
         return output;
     }
 
     // Create isometric wireframe representation
-    let bounds = SurfaceBounds::from_surface(result);
-    
+
     for (y_idx, z_row) in result.data.iter().enumerate() {
         for (x_idx, &z_val) in z_row.iter().enumerate() {
             // Get the actual 3D coordinates
@@ -530,7 +473,10 @@ fn regis_surface3d_plot(result: &Expression3dResult, width: usize, height: usize
             
             // Create 3D point and project to isometric coordinates
             let point_3d = Point3D::new(x_val, y_val, z_val);
-            let (iso_x, iso_y) = point_3d.to_isometric(width, height, &bounds);
+            if !bounds.contains(&point_3d) {
+                continue;
+            }
+            let (iso_x, iso_y) = point_3d.to_isometric(width, height, bounds);
             
             // Draw a small point for the surface
             output.push_str(&format!("P[{},{}]\nV[{},{}]\n", iso_x, iso_y, iso_x + 1, iso_y + 1));
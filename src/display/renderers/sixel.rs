@@ -3,10 +3,29 @@ use crate::{
     parametric2d::Parametric2DResult,
 };
 
-use super::super::{DisplayRenderer, Bitmap};
+use super::super::{color::quantize, DisplayRenderer, Bitmap, PlotData, PlotSettings};
+
+/// Color registers assumed when the terminal does not say how many it has
+pub const DEFAULT_REGISTERS: usize = 256;
 
 #[derive(Clone, Debug)]
-pub struct SixelRenderer;
+pub struct SixelRenderer {
+    /// Color registers the image may use; when it has more colors, similar ones
+    /// are merged to fit
+    pub registers: usize,
+}
+
+impl SixelRenderer {
+    pub fn new(registers: usize) -> Self {
+        Self { registers }
+    }
+}
+
+impl Default for SixelRenderer {
+    fn default() -> Self {
+        Self::new(DEFAULT_REGISTERS)
+    }
+}
 
 impl DisplayRenderer for SixelRenderer {
     fn render(
@@ -15,19 +34,15 @@ impl DisplayRenderer for SixelRenderer {
         width: usize,
         height: usize,
         x_range: &ExpressionRange1dResult,
+        settings: &PlotSettings,
     ) -> String {
         if result.0.is_empty() {
             return "No data to plot".to_string();
         }
 
-        let margin = 50;
-        let total_width = width + 2 * margin;
-        let total_height = height + 2 * margin;
-
-        let mut bitmap = Bitmap::new(total_width, total_height, 0);
-        bitmap.create_plot(result, x_range, margin);
+        let bitmap = Bitmap::render(PlotData::Curve { x: x_range, y: result }, settings, width, height);
 
-        bitmap_to_sixel(&bitmap, result)
+        bitmap_to_sixel(&bitmap, self.registers, result)
     }
 
     fn render_parametric(
@@ -35,19 +50,15 @@ impl DisplayRenderer for SixelRenderer {
         result: &Parametric2DResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> String {
         if result.is_empty() {
             return "No parametric data to plot".to_string();
         }
 
-        let margin = 50;
-        let total_width = width + 2 * margin;
-        let total_height = height + 2 * margin;
+        let bitmap = Bitmap::render(PlotData::Parametric(result), settings, width, height);
 
-        let mut bitmap = Bitmap::new(total_width, total_height, 0);
-        bitmap.create_parametric_plot(result, margin);
-
-        bitmap_to_sixel(&bitmap, &ExpressionRange1dResult::from(result.y_values.clone()))
+        bitmap_to_sixel(&bitmap, self.registers, &ExpressionRange1dResult::from(result.y_values.clone()))
     }
 
     fn render_surface3d(
@@ -55,23 +66,19 @@ impl DisplayRenderer for SixelRenderer {
         result: &Expression3dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> String {
         if result.is_empty() {
             return "No 3D surface data to plot".to_string();
         }
 
-        let margin = 50;
-        let total_width = width + 2 * margin;
-        let total_height = height + 2 * margin;
-
-        let mut bitmap = Bitmap::new(total_width, total_height, 0);
-        bitmap.create_surface3d_plot(result, margin);
+        let bitmap = Bitmap::render(PlotData::Surface(result), settings, width, height);
 
-        bitmap_to_sixel(&bitmap, &ExpressionRange1dResult::from(vec![result.z_min(), result.z_max()]))
+        bitmap_to_sixel(&bitmap, self.registers, &ExpressionRange1dResult::from(vec![result.z_min(), result.z_max()]))
     }
 }
 
-fn bitmap_to_sixel(bitmap: &Bitmap, result: &ExpressionRange1dResult) -> String {
+fn bitmap_to_sixel(bitmap: &Bitmap, registers: usize, result: &ExpressionRange1dResult) -> String {
     let mut output = String::new();
 
     output.push_str("\x1bPq");
@@ -79,15 +86,23 @@ fn bitmap_to_sixel(bitmap: &Bitmap, result: &ExpressionRange1dResult) -> String
     output.push_str(&format!("{};{}", bitmap.width, bitmap.height));
     output.push('\n');
 
-    //Colormap
-    output.push_str("#0;2;0;0;0");
-    output.push_str("#1;2;0;100;0");
-    output.push_str("#2;2;25;25;25");
-    output.push_str("#3;2;100;100;0");
-    output.push_str("#4;2;75;75;75");
+    // Colormap, one register per palette entry with channels in percent
+    let (palette, indices) = quantize(&bitmap.data.concat(), registers);
+    let percent = |channel: u8| (channel as u32 * 100 + 127) / 255;
+    for (register, color) in palette.iter().enumerate() {
+        output.push_str(&format!("#{register};2;{};{};{}", percent(color.r), percent(color.g), percent(color.b)));
+    }
 
     for row_chunk in (0..bitmap.height).step_by(6) {
-        for color in 1..=4 {
+        let rows = row_chunk..(row_chunk + 6).min(bitmap.height);
+        let mut used = vec![false; palette.len()];
+        for y in rows.clone() {
+            for &register in &indices[y * bitmap.width..(y + 1) * bitmap.width] {
+                used[register] = true;
+            }
+        }
+
+        for color in (0..palette.len()).filter(|&color| used[color]) {
             output.push_str(&format!("#{color}"));
 
             let mut repeat_count = 0;
@@ -96,9 +111,8 @@ fn bitmap_to_sixel(bitmap: &Bitmap, result: &ExpressionRange1dResult) -> String
             for x in 0..bitmap.width {
                 let mut sixel_value = 0u8;
 
-                for bit in 0..6 {
-                    let y = row_chunk + bit;
-                    if y < bitmap.height && bitmap.get_pixel(x, y) == color {
+                for (bit, y) in rows.clone().enumerate() {
+                    if indices[y * bitmap.width + x] == color {
                         sixel_value |= 1 << bit;
                     }
                 }
@@ -109,13 +123,7 @@ fn bitmap_to_sixel(bitmap: &Bitmap, result: &ExpressionRange1dResult) -> String
                     repeat_count += 1;
                 } else {
                     if let Some(prev_char) = last_char {
-                        if repeat_count > 3 {
-                            output.push_str(&format!("!{repeat_count}{prev_char}"));
-                        } else {
-                            for _ in 0..repeat_count {
-                                output.push(prev_char);
-                            }
-                        }
+                        push_run(&mut output, prev_char, repeat_count);
                     }
                     last_char = Some(sixel_char);
                     repeat_count = 1;
@@ -123,13 +131,7 @@ fn bitmap_to_sixel(bitmap: &Bitmap, result: &ExpressionRange1dResult) -> String
             }
 
             if let Some(prev_char) = last_char {
-                if repeat_count > 3 {
-                    output.push_str(&format!("!{repeat_count}{prev_char}"));
-                } else {
-                    for _ in 0..repeat_count {
-                        output.push(prev_char);
-                    }
-                }
+                push_run(&mut output, prev_char, repeat_count);
             }
 
             output.push('$');
@@ -144,4 +146,14 @@ fn bitmap_to_sixel(bitmap: &Bitmap, result: &ExpressionRange1dResult) -> String
     output.push_str("   🟢 Data line  🟡 Axes & ticks  ⬜ Grid lines  ⬛ Plot area\n");
 
     output
-}
\ No newline at end of file
+}
+/// A sixel repeated `count` times, with the `!` repeat introducer when it is shorter
+fn push_run(output: &mut String, sixel: char, count: usize) {
+    if count > 3 {
+        output.push_str(&format!("!{count}{sixel}"));
+    } else {
+        for _ in 0..count {
+            output.push(sixel);
+        }
+    }
+}
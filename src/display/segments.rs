@@ -0,0 +1,136 @@
+use super::{AxisScale, PlotBounds};
+
+/// A jump is only a discontinuity when it covers at least this fraction of the
+/// window, so that small wiggles never break a line
+const MIN_JUMP: f64 = 0.02;
+/// ... and is this many times steeper than the samples on either side of it
+const STEEPNESS: f64 = 10.0;
+/// A jump against the trend on both sides, between values of opposite sign that grow
+/// towards it, is a pass through infinity when it covers at least this fraction of
+/// the window
+const MIN_POLE_JUMP: f64 = 0.25;
+
+/// A series cut into the runs of samples that may be joined by lines, plus the X
+/// positions of the vertical asymptotes found between them
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Segments {
+    pub pieces: Vec<Vec<(f64, f64)>>,
+    pub asymptotes: Vec<f64>,
+}
+
+impl Segments {
+    /// Split the samples of a function of x, in drawing order, wherever it is
+    /// discontinuous: at samples the window cannot show (non-finite, or non-positive
+    /// on a log axis), at steps much steeper than the local slope (as with
+    /// `floor(x)`), and where the curve leaves through infinity and comes back from
+    /// the other side (as with `tan(x)`). Slopes use the real x values, so uneven or
+    /// unsorted samples are only split where Y actually jumps. Jumps are measured as
+    /// drawn, i.e. in log space on a log axis.
+    pub fn split(points: &[(f64, f64)], bounds: &PlotBounds) -> Self {
+        let y_span = span(bounds.y_scale, bounds.y_min, bounds.y_max);
+        Self::split_with(points, bounds, |step, before, after| {
+            let slope = |(dx, dy): (f64, f64)| if dx == 0.0 { f64::INFINITY } else { (dy / dx).abs() };
+            let local = before.map(slope).unwrap_or(0.0).max(after.map(slope).unwrap_or(0.0));
+            (before.is_some() || after.is_some())
+                && step.1.abs() > MIN_JUMP * y_span
+                && slope(step) > STEEPNESS * local
+        })
+    }
+
+    /// Split a parametric curve, where both coordinates depend on the parameter:
+    /// either coordinate jumping much further than its neighbouring steps breaks it
+    pub fn split_curve(points: &[(f64, f64)], bounds: &PlotBounds) -> Self {
+        let x_span = span(bounds.x_scale, bounds.x_min, bounds.x_max);
+        let y_span = span(bounds.y_scale, bounds.y_min, bounds.y_max);
+        Self::split_with(points, bounds, |step, before, after| {
+            is_jump(step.0, before.map(|d| d.0), after.map(|d| d.0), x_span)
+                || is_jump(step.1, before.map(|d| d.1), after.map(|d| d.1), y_span)
+        })
+    }
+
+    /// Shared walk over the samples: `jump` gets the drawn step into each sample and
+    /// the steps before and after it, and decides whether the line breaks there
+    fn split_with(
+        points: &[(f64, f64)],
+        bounds: &PlotBounds,
+        jump: impl Fn((f64, f64), Option<(f64, f64)>, Option<(f64, f64)>) -> bool,
+    ) -> Self {
+        let drawn: Vec<Option<(f64, f64)>> = points
+            .iter()
+            .map(|&(x, y)| {
+                (bounds.x_scale.accepts(x) && bounds.y_scale.accepts(y))
+                    .then(|| (bounds.x_scale.forward(x), bounds.y_scale.forward(y)))
+            })
+            .collect();
+        let y_span = span(bounds.y_scale, bounds.y_min, bounds.y_max);
+
+        let mut segments = Segments::default();
+        let mut piece = Vec::new();
+        for (i, &point) in points.iter().enumerate() {
+            let Some(current) = drawn[i] else {
+                if point.1.is_infinite() {
+                    segments.add_asymptote(point.0, bounds);
+                }
+                segments.close(&mut piece);
+                continue;
+            };
+
+            if let Some(previous) = i.checked_sub(1).and_then(|j| drawn[j]) {
+                let before = i.checked_sub(2).and_then(|j| drawn[j]).map(|p| (previous.0 - p.0, previous.1 - p.1));
+                let after = drawn.get(i + 1).copied().flatten().map(|n| (n.0 - current.0, n.1 - current.1));
+                let step = (current.0 - previous.0, current.1 - previous.1);
+
+                let y_pole = is_pole(step.1, before.map(|d| d.1), after.map(|d| d.1), y_span)
+                    && points[i - 1].1.signum() != point.1.signum()
+                    && points[i - 1].1.abs() > points[i - 2].1.abs()
+                    && point.1.abs() > points[i + 1].1.abs();
+
+                if y_pole {
+                    segments.add_asymptote((points[i - 1].0 + point.0) / 2.0, bounds);
+                }
+                if y_pole || jump(step, before, after) {
+                    segments.close(&mut piece);
+                }
+            }
+            piece.push(point);
+        }
+        segments.close(&mut piece);
+        segments
+    }
+
+    fn close(&mut self, piece: &mut Vec<(f64, f64)>) {
+        if !piece.is_empty() {
+            self.pieces.push(std::mem::take(piece));
+        }
+    }
+
+    fn add_asymptote(&mut self, x: f64, bounds: &PlotBounds) {
+        if x.is_finite() && x > bounds.x_min && x < bounds.x_max {
+            self.asymptotes.push(x);
+        }
+    }
+}
+
+/// Drawn length of an axis window
+fn span(scale: AxisScale, min: f64, max: f64) -> f64 {
+    (scale.forward(max) - scale.forward(min)).abs()
+}
+
+/// A step far longer than its neighbours on both sides
+fn is_jump(step: f64, before: Option<f64>, after: Option<f64>, span: f64) -> bool {
+    if before.is_none() && after.is_none() {
+        return false;
+    }
+    let local = before.unwrap_or(0.0).abs().max(after.unwrap_or(0.0).abs());
+    step.abs() > MIN_JUMP * span && step.abs() > STEEPNESS * local
+}
+
+/// A large step against the direction the curve moves on both sides of it
+fn is_pole(step: f64, before: Option<f64>, after: Option<f64>, span: f64) -> bool {
+    match (before, after) {
+        (Some(before), Some(after)) => {
+            step.abs() > MIN_POLE_JUMP * span && step * before < 0.0 && step * after < 0.0
+        }
+        _ => false,
+    }
+}
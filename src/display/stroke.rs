@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use super::{Bitmap, Rgba};
+
+/// Width of plotted curves when `linewidth=` is not given, in pixels
+pub const DEFAULT_LINE_WIDTH: f64 = 2.0;
+/// Shortest dash or gap, in pixels. Shorter ones would not show, and the dash
+/// pattern would take forever to walk along the curve.
+pub const MIN_DASH_LENGTH: f64 = 0.5;
+
+/// How a curve is drawn, with the meaning of SVG's `stroke-width` and
+/// `stroke-dasharray`: lengths are in pixels, a width of 0 draws nothing, and an
+/// odd dash list is repeated to make it even. Lines get round joins and caps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stroke {
+    pub width: f64,
+    /// Alternating dash and gap lengths, empty for a solid line
+    pub dash: Vec<f64>,
+}
+
+impl Stroke {
+    /// Stroke from user given values. As in SVG, a negative width is ignored, and so
+    /// is a dash list with a negative length or one adding up to zero. Lengths
+    /// below `MIN_DASH_LENGTH` are raised to it.
+    pub fn new(width: Option<f64>, dash: &[f64]) -> Self {
+        let width = width.filter(|width| *width >= 0.0).unwrap_or(DEFAULT_LINE_WIDTH);
+        let valid = dash.iter().all(|&length| length >= 0.0) && dash.iter().sum::<f64>() > 0.0;
+        let dash: Vec<f64> = dash.iter().map(|length| length.max(MIN_DASH_LENGTH)).collect();
+        let dash = match dash.len() % 2 {
+            _ if !valid => Vec::new(),
+            1 => dash.repeat(2),
+            _ => dash,
+        };
+        Self { width, dash }
+    }
+
+    pub fn solid(width: f64) -> Self {
+        Self { width, dash: Vec::new() }
+    }
+
+    /// The parts of a polyline covered by dashes, each as a polyline of its own. The
+    /// pattern starts over with every polyline.
+    pub fn dashes(&self, points: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+        if self.dash.is_empty() || points.is_empty() {
+            return vec![points.to_vec()];
+        }
+
+        let mut dashes = Vec::new();
+        let mut current = vec![points[0]];
+        let (mut index, mut remaining, mut on) = (0, self.dash[0], true);
+        for pair in points.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let length = (to.0 - from.0).hypot(to.1 - from.1);
+            let mut travelled = 0.0;
+            while length - travelled > remaining {
+                travelled += remaining;
+                let t = travelled / length;
+                let split = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+                if on {
+                    current.push(split);
+                    dashes.push(std::mem::take(&mut current));
+                } else {
+                    current = vec![split];
+                }
+                on = !on;
+                index = (index + 1) % self.dash.len();
+                remaining = self.dash[index];
+            }
+            remaining -= length - travelled;
+            if on {
+                current.push(to);
+            }
+        }
+        if on {
+            dashes.push(current);
+        }
+        dashes
+    }
+}
+
+impl Bitmap {
+    /// Draw a polyline given in pixel coordinates, where pixel centers are at whole
+    /// numbers. Lines up to a pixel wide are Xiaolin Wu lines, fainter when thinner;
+    /// wider ones are filled with round joins and caps.
+    pub fn stroke_polyline(&mut self, points: &[(f64, f64)], stroke: &Stroke, color: Rgba) {
+        if stroke.width <= 0.0 {
+            return;
+        }
+        let mut coverage = Coverage::new(self.width, self.height);
+        for dash in stroke.dashes(points) {
+            match dash.as_slice() {
+                [] => {}
+                &[point] => coverage.capsule(point, point, stroke.width / 2.0),
+                _ if stroke.width <= 1.0 && self.antialias => {
+                    for pair in dash.windows(2) {
+                        coverage.wu_line(pair[0], pair[1], stroke.width);
+                    }
+                }
+                _ => {
+                    for pair in dash.windows(2) {
+                        coverage.capsule(pair[0], pair[1], stroke.width.max(1.0) / 2.0);
+                    }
+                }
+            }
+        }
+        self.paint(coverage, color);
+    }
+
+    /// A filled round dot of `diameter` pixels
+    pub fn fill_dot(&mut self, center: (f64, f64), diameter: f64, color: Rgba) {
+        let mut coverage = Coverage::new(self.width, self.height);
+        coverage.capsule(center, center, diameter / 2.0);
+        self.paint(coverage, color);
+    }
+
+    /// Blend `color` into every covered pixel, as translucent as the pixel is
+    /// uncovered. Without antialiasing, pixels are either painted or left alone.
+    fn paint(&mut self, coverage: Coverage, color: Rgba) {
+        for ((x, y), amount) in coverage.pixels {
+            let amount = match self.antialias {
+                true => amount,
+                false if amount >= 0.5 => 1.0,
+                false => continue,
+            };
+            self.set_pixel(x, y, color.with_alpha((color.a as f64 * amount).round() as u8));
+        }
+    }
+}
+
+/// How much of each pixel a stroke covers, from 0 to 1, collected before painting
+/// so pixels where pieces of the stroke overlap are only blended once
+struct Coverage {
+    pixels: HashMap<(usize, usize), f64>,
+    width: usize,
+    height: usize,
+}
+
+impl Coverage {
+    fn new(width: usize, height: usize) -> Self {
+        Self { pixels: HashMap::new(), width, height }
+    }
+
+    fn pixel(&mut self, x: f64, y: f64) -> Option<&mut f64> {
+        let inside = x >= 0.0 && y >= 0.0 && (x as usize) < self.width && (y as usize) < self.height;
+        inside.then(|| self.pixels.entry((x as usize, y as usize)).or_default())
+    }
+
+    /// Xiaolin Wu's line: the two pixels straddling the line in every column (or
+    /// row, for steep lines) share its intensity by how close they are to it. The
+    /// half covered end pixels of lines meeting end to end add up to a full one.
+    fn wu_line(&mut self, from: (f64, f64), to: (f64, f64), intensity: f64) {
+        let ((mut x0, mut y0), (mut x1, mut y1)) = (from, to);
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        if steep {
+            (x0, y0, x1, y1) = (y0, x0, y1, x1);
+        }
+        if x0 > x1 {
+            (x0, y0, x1, y1) = (x1, y1, x0, y0);
+        }
+        let gradient = if x1 == x0 { 1.0 } else { (y1 - y0) / (x1 - x0) };
+
+        let mut plot = |x: f64, y: f64, amount: f64| {
+            let (x, y) = if steep { (y, x) } else { (x, y) };
+            if let Some(pixel) = self.pixel(x, y) {
+                *pixel = (*pixel + amount * intensity).min(1.0);
+            }
+        };
+
+        let start = x0.round();
+        let y_start = y0 + gradient * (start - x0);
+        let gap = 1.0 - fraction(x0 + 0.5);
+        plot(start, y_start.floor(), (1.0 - fraction(y_start)) * gap);
+        plot(start, y_start.floor() + 1.0, fraction(y_start) * gap);
+
+        let end = x1.round();
+        let y_end = y1 + gradient * (end - x1);
+        let gap = fraction(x1 + 0.5);
+        plot(end, y_end.floor(), (1.0 - fraction(y_end)) * gap);
+        plot(end, y_end.floor() + 1.0, fraction(y_end) * gap);
+
+        let mut y = y_start + gradient;
+        let mut x = start + 1.0;
+        while x < end {
+            plot(x, y.floor(), 1.0 - fraction(y));
+            plot(x, y.floor() + 1.0, fraction(y));
+            y += gradient;
+            x += 1.0;
+        }
+    }
+
+    /// Every pixel within `radius` of the segment, with a one pixel soft edge.
+    /// Capsules of a polyline overlap at the joins, which makes them round.
+    fn capsule(&mut self, from: (f64, f64), to: (f64, f64), radius: f64) {
+        let reach = radius + 1.0;
+        let (left, right) = ((from.0.min(to.0) - reach).max(0.0), from.0.max(to.0) + reach);
+        let (top, bottom) = ((from.1.min(to.1) - reach).max(0.0), from.1.max(to.1) + reach);
+        let right = right.min(self.width as f64 - 1.0);
+        let bottom = bottom.min(self.height as f64 - 1.0);
+
+        let mut y = top.ceil();
+        while y <= bottom {
+            let mut x = left.ceil();
+            while x <= right {
+                let amount = (radius + 0.5 - distance_to_segment((x, y), from, to)).clamp(0.0, 1.0);
+                if amount > 0.0 {
+                    if let Some(pixel) = self.pixel(x, y) {
+                        *pixel = pixel.max(amount);
+                    }
+                }
+                x += 1.0;
+            }
+            y += 1.0;
+        }
+    }
+}
+
+/// Fractional part, also for negative values
+fn fraction(value: f64) -> f64 {
+    value - value.floor()
+}
+
+fn distance_to_segment(point: (f64, f64), from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((point.0 - from.0) * dx + (point.1 - from.1) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    (point.0 - from.0 - t * dx).hypot(point.1 - from.1 - t * dy)
+}
@@ -0,0 +1,295 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{IsTerminal, Read, Write},
+    process::{Command, Stdio},
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+/// Character cells reserved around a text plot: the title line, the bottom border,
+/// the X tick line, the blank line after the plot and the next prompt
+const TEXT_ROWS_RESERVED: usize = 5;
+/// Character cells taken by the left and right borders of a text plot
+const TEXT_COLUMNS_RESERVED: usize = 2;
+/// Text rows kept free below a graphics plot so the prompt does not scroll it away
+const GRAPHICS_ROWS_RESERVED: usize = 2;
+/// Smallest grid the text renderers lay out their axes and tick labels in
+pub const MIN_TEXT_PLOT_SIZE: (usize, usize) = (20, 5);
+/// Smallest image, in pixels, that leaves room for the labels around a plot
+pub const MIN_GRAPHICS_SIZE: (usize, usize) = (100, 100);
+
+/// Size of the terminal window in character cells and, when known, in pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub columns: usize,
+    pub rows: usize,
+    pub pixels: Option<(usize, usize)>,
+}
+
+impl TerminalSize {
+    /// Size of the terminal stdout is attached to, falling back to the `COLUMNS`
+    /// and `LINES` environment variables when stdout is not a terminal
+    pub fn detect() -> Option<Self> {
+        window_size().or_else(|| {
+            let columns = env_size("COLUMNS")?;
+            let rows = env_size("LINES")?;
+            Some(Self { columns, rows, pixels: None })
+        })
+    }
+
+    /// Like `detect`, but asks the terminal for its pixel size with `CSI 14 t`
+    /// when the kernel does not know it
+    pub fn detect_with_pixels() -> Option<Self> {
+        let mut size = Self::detect();
+        if size.is_none_or(|size| size.pixels.is_none()) {
+            if let Some(pixels) = query_pixel_size(size.map(|size| (size.columns, size.rows))) {
+                let detected = size.get_or_insert(Self { columns: 0, rows: 0, pixels: None });
+                detected.pixels = Some(pixels);
+            }
+        }
+        size
+    }
+
+    /// Grid size for the ASCII and ANSI renderers filling the terminal
+    pub fn text_plot_size(&self) -> Option<(usize, usize)> {
+        let width = self.columns.checked_sub(TEXT_COLUMNS_RESERVED)?;
+        let height = self.rows.checked_sub(TEXT_ROWS_RESERVED)?;
+        (width >= MIN_TEXT_PLOT_SIZE.0 && height >= MIN_TEXT_PLOT_SIZE.1).then_some((width, height))
+    }
+
+    /// Pixel area available to graphics, leaving a few text rows for the prompt
+    pub fn graphics_size(&self) -> Option<(usize, usize)> {
+        let (width, height) = self.pixels?;
+        let reserved = match self.rows {
+            0 => 0,
+            rows => height / rows * GRAPHICS_ROWS_RESERVED,
+        };
+        let height = height.checked_sub(reserved)?;
+        (width >= MIN_GRAPHICS_SIZE.0 && height >= MIN_GRAPHICS_SIZE.1).then_some((width, height))
+    }
+
+    /// Text area an inline image may be scaled to, in cells, leaving the same rows
+    /// for the prompt as `graphics_size`
+    pub fn image_cells(&self) -> Option<(usize, usize)> {
+        let rows = self.rows.checked_sub(GRAPHICS_ROWS_RESERVED)?;
+        (self.columns > 0 && rows > 0).then_some((self.columns, rows))
+    }
+
+    /// Size of one character cell in pixels, when the pixel size is known
+    pub fn cell_pixels(&self) -> Option<(f64, f64)> {
+        let (width, height) = self.pixels?;
+        (self.columns > 0 && self.rows > 0).then(|| (width as f64 / self.columns as f64, height as f64 / self.rows as f64))
+    }
+}
+
+/// How many colors the terminal shows, which decides the SGR codes used for RGB colors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit `38;2;r;g;b`
+    TrueColor,
+    /// The xterm 256-color palette, `38;5;n`
+    Ansi256,
+    /// The 16 basic colors, `30`-`37` and `90`-`97`
+    Ansi16,
+}
+
+/// Channel levels of the 6x6x6 color cube of the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+/// The 16 basic colors as xterm shows them by default
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+    (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+    (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+
+impl ColorDepth {
+    /// Depth announced by the environment: `COLORTERM=truecolor` (or `24bit`, or a
+    /// `TERM` ending in `-direct`) for 24-bit color, a `TERM` naming 256 colors for the
+    /// palette, and the basic colors otherwise. `None` when the user opted out of
+    /// colors with a non-empty `NO_COLOR` (https://no-color.org).
+    pub fn detect() -> Option<Self> {
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return None;
+        }
+        Some(Self::from_env(std::env::var("COLORTERM").ok().as_deref(), std::env::var("TERM").ok().as_deref()))
+    }
+
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let colorterm = colorterm.unwrap_or_default().to_ascii_lowercase();
+        let term = term.unwrap_or_default().to_ascii_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// SGR parameters selecting the closest color this depth can show, as the
+    /// foreground or the background
+    pub fn sgr(&self, (r, g, b): (u8, u8, u8), background: bool) -> String {
+        match self {
+            ColorDepth::TrueColor => format!("{};2;{r};{g};{b}", if background { 48 } else { 38 }),
+            ColorDepth::Ansi256 => format!("{};5;{}", if background { 48 } else { 38 }, palette_index((r, g, b))),
+            ColorDepth::Ansi16 => {
+                let index = nearest(&BASIC_COLORS, (r, g, b));
+                let code = if index < 8 { 30 + index } else { 90 + index - 8 };
+                (code + if background { 10 } else { 0 }).to_string()
+            }
+        }
+    }
+}
+
+/// Closest entry of the 256-color palette: a color cube entry or a gray ramp step
+fn palette_index((r, g, b): (u8, u8, u8)) -> usize {
+    let level = |channel: u8| (0..CUBE_LEVELS.len()).min_by_key(|&i| CUBE_LEVELS[i].abs_diff(channel)).unwrap_or(0);
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+
+    let average = (r as usize + g as usize + b as usize) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23);
+    let gray_level = (8 + 10 * step) as u8;
+
+    if distance((r, g, b), (gray_level, gray_level, gray_level)) < distance((r, g, b), cube) {
+        232 + step
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
+fn nearest(colors: &[(u8, u8, u8)], color: (u8, u8, u8)) -> usize {
+    (0..colors.len()).min_by_key(|&i| distance(colors[i], color)).unwrap_or(0)
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |x: u8, y: u8| (x as i32 - y as i32).unsigned_abs().pow(2);
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+fn env_size(name: &str) -> Option<usize> {
+    std::env::var(name).ok()?.trim().parse().ok().filter(|&value| value > 0)
+}
+
+#[cfg(unix)]
+fn window_size() -> Option<TerminalSize> {
+    use std::ffi::{c_int, c_ulong};
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct WinSize {
+        ws_row: u16,
+        ws_col: u16,
+        ws_xpixel: u16,
+        ws_ypixel: u16,
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    const TIOCGWINSZ: c_ulong = 0x4008_7468;
+
+    unsafe extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let mut size = WinSize::default();
+    // SAFETY: TIOCGWINSZ writes exactly one `struct winsize` through the pointer
+    if unsafe { ioctl(1, TIOCGWINSZ, &mut size as *mut WinSize) } != 0 || size.ws_col == 0 || size.ws_row == 0 {
+        return None;
+    }
+    let pixels = (size.ws_xpixel > 0 && size.ws_ypixel > 0)
+        .then_some((size.ws_xpixel as usize, size.ws_ypixel as usize));
+    Some(TerminalSize {
+        columns: size.ws_col as usize,
+        rows: size.ws_row as usize,
+        pixels,
+    })
+}
+
+#[cfg(not(unix))]
+fn window_size() -> Option<TerminalSize> {
+    None
+}
+
+/// Ask the terminal for its text area size in pixels (`CSI 14 t`, answered with
+/// `CSI 4 ; height ; width t`). The answer is kept and only asked for again when
+/// the window size in `cells` changes, so a terminal that does not answer costs
+/// the wait once.
+fn query_pixel_size(cells: Option<(usize, usize)>) -> Option<(usize, usize)> {
+    /// Window size in cells when asked, and the answer
+    type Answer = (Option<(usize, usize)>, Option<(usize, usize)>);
+    static ANSWER: Mutex<Option<Answer>> = Mutex::new(None);
+    let mut answer = ANSWER.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((asked, pixels)) = *answer {
+        if asked == cells {
+            return pixels;
+        }
+    }
+
+    let pixels = query(b"\x1b[14t", b't').and_then(|reply| {
+        let fields = reply.strip_prefix("\x1b[4;")?.strip_suffix('t')?;
+        let (height, width) = fields.split_once(';')?;
+        let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+        (width > 0 && height > 0).then_some((width, height))
+    });
+    *answer = Some((cells, pixels));
+    pixels
+}
+
+/// Number of sixel color registers the terminal has, asked with XTSMGRAPHICS
+/// (`CSI ? 1 ; 1 ; 0 S`, answered with `CSI ? 1 ; 0 ; count S`) the first time only
+pub fn sixel_registers() -> Option<usize> {
+    static ANSWER: OnceLock<Option<usize>> = OnceLock::new();
+    *ANSWER.get_or_init(|| {
+        let reply = query(b"\x1b[?1;1;0S", b'S')?;
+        let count = reply.strip_prefix("\x1b[?1;0;")?.strip_suffix('S')?;
+        count.parse().ok().filter(|&count| count >= 2)
+    })
+}
+
+/// Send a request to the terminal and read its reply, up to and including
+/// `terminator`. The terminal is put in raw mode with a short read timeout so
+/// terminals that do not answer only cost half a second.
+fn query(request: &[u8], terminator: u8) -> Option<String> {
+    if !cfg!(unix) || !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    let saved = stty(&["-g"])?;
+    let saved = saved.trim();
+    stty(&["raw", "-echo", "min", "0", "time", "5"])?;
+
+    let mut reply = Vec::new();
+    if tty.write_all(request).and_then(|_| tty.flush()).is_ok() {
+        let mut byte = [0u8; 1];
+        while reply.len() < 32 {
+            match tty.read(&mut byte) {
+                Ok(1) => {
+                    reply.push(byte[0]);
+                    if byte[0] == terminator {
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+    stty(&[saved]);
+
+    String::from_utf8(reply).ok()
+}
+
+/// Run `stty` on the controlling terminal, returning its output on success
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(File::open("/dev/tty").ok()?)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -3,7 +3,7 @@ use crate::{
     parametric2d::Parametric2DResult,
 };
 
-use super::{LabelConfig, Label3DConfig};
+use super::{Autoscale, AxisScale, AxisTicks, PlotBounds, PlotSettings, PlotStyle, Rgba, Segments, Stroke, Theme};
 
 #[derive(Clone, Debug)]
 pub struct Point3D {
@@ -18,11 +18,21 @@ impl Point3D {
     }
 
     pub fn to_isometric(&self, width: usize, height: usize, bounds: &SurfaceBounds) -> (usize, usize) {
+        let (screen_x, screen_y) = self.isometric(width as f64, height as f64, bounds);
+        (
+            screen_x.max(0.0).min(width as f64 - 1.0) as usize,
+            screen_y.max(0.0).min(height as f64 - 1.0) as usize,
+        )
+    }
+
+    /// Isometric position in a `width` by `height` plot area, before rounding to
+    /// pixels, for outputs that keep fractional coordinates
+    pub fn isometric(&self, width: f64, height: f64, bounds: &SurfaceBounds) -> (f64, f64) {
         // Normalize coordinates to [0,1] range
-        let norm_x = (self.x - bounds.x_min) / (bounds.x_max - bounds.x_min);
-        let norm_y = (self.y - bounds.y_min) / (bounds.y_max - bounds.y_min);
+        let norm_x = bounds.x_scale.fraction(self.x, bounds.x_min, bounds.x_max);
+        let norm_y = bounds.y_scale.fraction(self.y, bounds.y_min, bounds.y_max);
         let norm_z = if bounds.z_max != bounds.z_min {
-            (self.z - bounds.z_min) / (bounds.z_max - bounds.z_min)
+            bounds.z_fraction(self.z)
         } else {
             0.5
         };
@@ -38,13 +48,9 @@ impl Point3D {
 
         // Map to screen coordinates with proper scaling
         let scale = 0.4; // Use consistent scale for all dimensions
-        let screen_x = (width as f64 * 0.5) + (iso_x * width as f64 * scale);
-        let screen_y = (height as f64 * 0.7) - (iso_y * height as f64 * scale);
-
-        (
-            screen_x.max(0.0).min(width as f64 - 1.0) as usize,
-            screen_y.max(0.0).min(height as f64 - 1.0) as usize,
-        )
+        let screen_x = (width * 0.5) + (iso_x * width * scale);
+        let screen_y = (height * 0.7) - (iso_y * height * scale);
+        (screen_x, screen_y)
     }
 }
 
@@ -56,49 +62,158 @@ pub struct SurfaceBounds {
     pub y_max: f64,
     pub z_min: f64,
     pub z_max: f64,
+    pub x_scale: AxisScale,
+    pub y_scale: AxisScale,
+    pub z_scale: AxisScale,
 }
 
 impl SurfaceBounds {
-    pub fn from_surface(result: &Expression3dResult) -> Self {
-        Self {
-            x_min: result.x_min(),
-            x_max: result.x_max(),
-            y_min: result.y_min(),
-            y_max: result.y_max(),
-            z_min: result.z_min(),
-            z_max: result.z_max(),
+    /// Box around the surface: X and Y follow the grid (or `xrange=`/`yrange=`), Z covers
+    /// the finite heights inside that part of the grid. Returns `None` when no height is finite.
+    pub fn for_surface(result: &Expression3dResult, settings: &PlotSettings) -> Option<Self> {
+        let (x_min, x_max) =
+            settings.x_scale.window(settings.x_view, result.x_values.iter().copied(), Autoscale::Full, 0.0)?;
+        let (y_min, y_max) =
+            settings.y_scale.window(settings.y_view, result.y_values.iter().copied(), Autoscale::Full, 0.0)?;
+
+        let mut heights = Vec::new();
+        for (y_idx, &y_val) in result.y_values.iter().enumerate() {
+            for (x_idx, &x_val) in result.x_values.iter().enumerate() {
+                if x_val >= x_min && x_val <= x_max && y_val >= y_min && y_val <= y_max {
+                    if let Some(z_val) = result.get_z(x_idx, y_idx) {
+                        heights.push(z_val);
+                    }
+                }
+            }
         }
+        let (z_min, z_max) = settings.z_scale.window(None, heights.into_iter(), settings.autoscale, 0.0)?;
+
+        Some(Self {
+            x_min, x_max, y_min, y_max, z_min, z_max,
+            x_scale: settings.x_scale,
+            y_scale: settings.y_scale,
+            z_scale: settings.z_scale,
+        })
+    }
+
+    /// Ticks along X, about `target` of them labelled
+    pub fn x_axis(&self, target: usize) -> AxisTicks {
+        AxisTicks::new(self.x_scale, self.x_min, self.x_max, target)
+    }
+
+    /// Ticks along Y, about `target` of them labelled
+    pub fn y_axis(&self, target: usize) -> AxisTicks {
+        AxisTicks::new(self.y_scale, self.y_min, self.y_max, target)
+    }
+
+    /// Ticks along Y for a text display, no two labels on the same line
+    pub fn y_axis_lines(&self, target: usize, line: impl Fn(f64) -> usize) -> AxisTicks {
+        AxisTicks::for_lines(self.y_scale, self.y_min, self.y_max, target, line)
+    }
+
+    /// Ticks up Z, about `target` of them labelled
+    pub fn z_axis(&self, target: usize) -> AxisTicks {
+        AxisTicks::new(self.z_scale, self.z_min, self.z_max, target)
+    }
+
+    /// Position of a height between the bottom (0.0) and top (1.0) of the box
+    pub fn z_fraction(&self, z: f64) -> f64 {
+        self.z_scale.fraction(z, self.z_min, self.z_max)
+    }
+
+    /// Whether a grid point lies inside the box. Non-finite heights never do, nor do
+    /// non-positive values on a log axis.
+    pub fn contains(&self, point: &Point3D) -> bool {
+        self.x_scale.accepts(point.x) && self.y_scale.accepts(point.y) && self.z_scale.accepts(point.z)
+            && point.x >= self.x_min && point.x <= self.x_max
+            && point.y >= self.y_min && point.y <= self.y_max
+            && point.z >= self.z_min && point.z <= self.z_max
     }
 }
 
+/// RGB canvas the image outputs draw on, filled with the theme's background.
+/// Pixels are blended with what is already there, so translucent colors show
+/// the lines below them.
 #[derive(Clone, Debug)]
 pub struct Bitmap {
-    pub data: Vec<Vec<u8>>,
+    pub data: Vec<Vec<Rgba>>,
     pub width: usize,
     pub height: usize,
+    pub theme: Theme,
+    /// Blend the edges of lines into the pixels around them. Canvases read back as
+    /// dots that are either set or not turn this off.
+    pub antialias: bool,
+}
+
+/// Margin the bitmap outputs leave on every side of the plot area for the labels
+pub const BITMAP_MARGIN: usize = 50;
+
+/// What a bitmap output draws: a curve, a parametric curve or a surface
+#[derive(Clone, Copy, Debug)]
+pub enum PlotData<'a> {
+    Curve { x: &'a ExpressionRange1dResult, y: &'a ExpressionRange1dResult },
+    Parametric(&'a Parametric2DResult),
+    Surface(&'a Expression3dResult),
 }
 
 impl Bitmap {
-    pub fn new(width: usize, height: usize, background: u8) -> Self {
+    pub fn new(width: usize, height: usize, theme: Theme) -> Self {
         Self {
-            data: vec![vec![background; width]; height],
+            data: vec![vec![theme.background; width]; height],
             width,
             height,
+            theme,
+            antialias: true,
         }
     }
 
-    pub fn set_pixel(&mut self, x: usize, y: usize, color: u8) {
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Rgba) {
         if x < self.width && y < self.height {
-            self.data[y][x] = color;
+            self.data[y][x] = color.over(self.data[y][x]);
         }
     }
 
-    pub fn get_pixel(&self, x: usize, y: usize) -> u8 {
+    pub fn get_pixel(&self, x: usize, y: usize) -> Rgba {
         if x < self.width && y < self.height {
             self.data[y][x]
         } else {
-            0
+            self.theme.background
+        }
+    }
+
+    /// Whether nothing has been drawn at a pixel yet
+    pub fn is_background(&self, x: usize, y: usize) -> bool {
+        self.get_pixel(x, y) == self.theme.background
+    }
+
+    /// The whole bitmap as packed 8-bit RGB, row by row from the top
+    pub fn to_rgb(&self) -> Vec<u8> {
+        self.data
+            .iter()
+            .flatten()
+            .flat_map(|color| [color.r, color.g, color.b])
+            .collect()
+    }
+
+    /// A `width` by `height` image, margins included, plotted and labelled as
+    /// every bitmap output shows it
+    pub fn render(data: PlotData, settings: &PlotSettings, width: usize, height: usize) -> Self {
+        let mut bitmap = Self::new(width, height, settings.theme);
+        bitmap.plot(data, settings);
+        bitmap
+    }
+
+    /// Plot and label the data inside the margin. As in the vector outputs, the
+    /// margin never takes more than half of either side, so tiny images still get
+    /// a plot area.
+    pub fn plot(&mut self, data: PlotData, settings: &PlotSettings) {
+        let margin = BITMAP_MARGIN.min(self.width.min(self.height) / 4);
+        match data {
+            PlotData::Curve { x, y } => self.create_plot(y, x, margin, settings),
+            PlotData::Parametric(result) => self.create_parametric_plot(result, margin, settings),
+            PlotData::Surface(result) => self.create_surface3d_plot(result, margin, settings),
         }
+        self.draw_labels(margin, settings, matches!(data, PlotData::Surface(_)));
     }
 
     pub fn create_plot(
@@ -106,6 +221,7 @@ impl Bitmap {
         y_result: &ExpressionRange1dResult,
         x_range: &ExpressionRange1dResult,
         margin: usize,
+        settings: &PlotSettings,
     ) {
         if y_result.0.is_empty() {
             return;
@@ -118,70 +234,24 @@ impl Bitmap {
             return;
         }
 
-        let min_val = y_result.min();
-        let max_val = y_result.max();
-        let x_min = x_range.min();
-        let x_max = x_range.max();
-        let y_range = max_val - min_val;
-
-        if y_range.abs() < f64::EPSILON {
-            // Constant value - horizontal line
-            let y_pos = margin + plot_height / 2;
-            for x in margin..margin + plot_width {
-                self.set_pixel(x, y_pos, 1);
-            }
-        } else {
-            // Plot points and lines
-            let mut points = Vec::new();
-            for (i, &y_val) in y_result.0.iter().enumerate() {
-                let x_pos = if y_result.0.len() > 1 {
-                    margin + (i * plot_width) / (y_result.0.len() - 1)
-                } else {
-                    margin + plot_width / 2
-                };
-
-                let normalized_y = (y_val - min_val) / y_range;
-                let y_pos = margin + ((1.0 - normalized_y) * plot_height as f64) as usize;
-                let y_pos = y_pos.min(margin + plot_height - 1);
+        let Some(bounds) = PlotBounds::for_plot(&x_range.0, &y_result.0, settings) else {
+            return;
+        };
 
-                points.push((x_pos, y_pos));
-            }
+        let samples: Vec<(f64, f64)> = x_range.0.iter().copied().zip(y_result.0.iter().copied()).collect();
+        let segments = Segments::split(&samples, &bounds);
+        self.plot_samples(&segments, &bounds, (margin, plot_width, plot_height), settings.asymptotes, settings.style, &settings.stroke());
 
-            for i in 1..points.len() {
-                self.draw_line(
-                    points[i - 1].0,
-                    points[i - 1].1,
-                    points[i].0,
-                    points[i].1,
-                    1,
-                );
-            }
+        self.draw_axes_and_grid((margin, plot_width, plot_height), &bounds);
 
-            // Draw points
-            for &(x, y) in &points {
-                self.set_pixel(x, y, 1);
-                for dx in 0..=1 {
-                    for dy in 0..=1 {
-                        self.set_pixel(x.saturating_add(dx), y.saturating_add(dy), 1);
-                        self.set_pixel(x.saturating_sub(dx), y.saturating_sub(dy), 1);
-                    }
-                }
-            }
-        }
-
-        self.draw_axes_and_grid(margin, plot_width, plot_height);
-
-        self.add_value_labels(
-            (margin, plot_width, plot_height),
-            (min_val, max_val),
-            (x_min, x_max),
-        );
+        self.add_value_labels((margin, plot_width, plot_height), &bounds);
     }
 
     pub fn create_parametric_plot(
         &mut self,
         parametric_result: &Parametric2DResult,
         margin: usize,
+        settings: &PlotSettings,
     ) {
         if parametric_result.is_empty() {
             return;
@@ -194,60 +264,101 @@ impl Bitmap {
             return;
         }
 
-        let x_min = parametric_result.x_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let x_max = parametric_result.x_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        let y_min = parametric_result.y_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let y_max = parametric_result.y_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        let Some(bounds) = PlotBounds::for_parametric(
+            &parametric_result.x_values,
+            &parametric_result.y_values,
+            settings,
+        ) else {
+            return;
+        };
 
-        let x_range = x_max - x_min;
-        let y_range = y_max - y_min;
+        let samples: Vec<(f64, f64)> = parametric_result
+            .x_values
+            .iter()
+            .copied()
+            .zip(parametric_result.y_values.iter().copied())
+            .collect();
+        let segments = Segments::split_curve(&samples, &bounds);
+        self.plot_samples(&segments, &bounds, (margin, plot_width, plot_height), false, settings.style, &settings.stroke());
 
-        if x_range.abs() < f64::EPSILON || y_range.abs() < f64::EPSILON {
-            return;
-        }
+        self.draw_axes_and_grid((margin, plot_width, plot_height), &bounds);
+
+        self.add_value_labels((margin, plot_width, plot_height), &bounds);
+    }
 
-        let mut points = Vec::new();
-        for (&x_val, &y_val) in parametric_result.x_values.iter().zip(parametric_result.y_values.iter()) {
-            let x_pos = margin + ((x_val - x_min) / x_range * plot_width as f64) as usize;
-            let y_pos = margin + ((y_max - y_val) / y_range * plot_height as f64) as usize;
-            let x_pos = x_pos.min(margin + plot_width - 1);
-            let y_pos = y_pos.min(margin + plot_height - 1);
-            points.push((x_pos, y_pos));
+    /// Connect consecutive samples of each piece with `stroke` lines clipped to the
+    /// window, mark every visible sample with a dot (as `style` asks) and optionally
+    /// dash the asymptotes. A piece of a single sample always gets its dot.
+    pub fn plot_samples(
+        &mut self,
+        segments: &Segments,
+        bounds: &PlotBounds,
+        layout: (usize, usize, usize), // (margin, plot_width, plot_height)
+        asymptotes: bool,
+        style: PlotStyle,
+        stroke: &Stroke,
+    ) {
+        let (margin, plot_width, plot_height) = layout;
+        if plot_width == 0 || plot_height == 0 {
+            return;
         }
 
-        // Draw lines connecting parametric points
-        for i in 1..points.len() {
-            self.draw_line(
-                points[i - 1].0,
-                points[i - 1].1,
-                points[i].0,
-                points[i].1,
-                1,
-            );
+        let to_screen = |(x, y): (f64, f64)| -> (f64, f64) {
+            (
+                margin as f64 + bounds.x_fraction(x) * (plot_width - 1) as f64,
+                margin as f64 + (1.0 - bounds.y_fraction(y)) * (plot_height - 1) as f64,
+            )
+        };
+        let color = self.theme.series[0];
+
+        if asymptotes {
+            let dashed = Stroke::new(Some(1.0), &[4.0, 4.0]);
+            let (top, bottom) = (margin as f64, (margin + plot_height - 1) as f64);
+            for &x_val in &segments.asymptotes {
+                let x = (margin as f64 + bounds.x_fraction(x_val) * (plot_width - 1) as f64).round();
+                self.stroke_polyline(&[(x, top), (x, bottom)], &dashed, self.theme.text.with_alpha(160));
+            }
         }
 
-        // Draw points
-        for &(x, y) in &points {
-            self.set_pixel(x, y, 1);
-            for dx in 0..=1 {
-                for dy in 0..=1 {
-                    self.set_pixel(x.saturating_add(dx), y.saturating_add(dy), 1);
-                    self.set_pixel(x.saturating_sub(dx), y.saturating_sub(dy), 1);
+        if style != PlotStyle::Points {
+            // Each run of visible segments is one polyline, so it gets round joins
+            // and one dash pattern
+            for piece in &segments.pieces {
+                let mut run: Vec<(f64, f64)> = Vec::new();
+                for pair in piece.windows(2) {
+                    let Some((start, end)) = bounds.clip_segment(pair[0], pair[1]) else {
+                        self.stroke_polyline(&run, stroke, color);
+                        run.clear();
+                        continue;
+                    };
+                    let (start, end) = (to_screen(start), to_screen(end));
+                    if run.last() != Some(&start) {
+                        self.stroke_polyline(&run, stroke, color);
+                        run = vec![start];
+                    }
+                    run.push(end);
                 }
+                self.stroke_polyline(&run, stroke, color);
             }
         }
 
-        self.draw_axes_and_grid(margin, plot_width, plot_height);
-
-        self.add_parametric_value_labels(
-            LabelConfig::new(margin, plot_width, plot_height, x_min, x_max, y_min, y_max),
-        );
+        let dotted = segments
+            .pieces
+            .iter()
+            .filter(|piece| style != PlotStyle::Lines || piece.len() == 1)
+            .flatten();
+        for &(x_val, y_val) in dotted {
+            if bounds.contains(x_val, y_val) {
+                self.fill_dot(to_screen((x_val, y_val)), stroke.width + 2.0, color);
+            }
+        }
     }
 
     pub fn create_surface3d_plot(
         &mut self,
         surface_result: &Expression3dResult,
         margin: usize,
+        settings: &PlotSettings,
     ) {
         if surface_result.is_empty() {
             return;
@@ -260,24 +371,24 @@ impl Bitmap {
             return;
         }
 
-        let x_min = surface_result.x_min();
-        let x_max = surface_result.x_max();
-        let y_min = surface_result.y_min();
-        let y_max = surface_result.y_max();
-        let z_min = surface_result.z_min();
-        let z_max = surface_result.z_max();
+        // Create isometric 3D visualization
+        let Some(bounds) = SurfaceBounds::for_surface(surface_result, settings) else {
+            return;
+        };
+        self.plot_surface3d(surface_result, &bounds, (margin, plot_width, plot_height));
 
-        let x_range = x_max - x_min;
-        let y_range = y_max - y_min;
-        let z_range = z_max - z_min;
+        self.add_surface3d_value_labels((margin, plot_width, plot_height), &bounds);
+    }
 
-        if x_range.abs() < f64::EPSILON || y_range.abs() < f64::EPSILON {
-            return;
-        }
+    /// Draw the surface points, colored by height, and the 3D axes without any labels
+    pub fn plot_surface3d(
+        &mut self,
+        surface_result: &Expression3dResult,
+        bounds: &SurfaceBounds,
+        layout: (usize, usize, usize), // (margin, plot_width, plot_height)
+    ) {
+        let (margin, plot_width, plot_height) = layout;
 
-        // Create isometric 3D visualization
-        let bounds = SurfaceBounds::from_surface(surface_result);
-        
         for (y_idx, z_row) in surface_result.data.iter().enumerate() {
             for (x_idx, &z_val) in z_row.iter().enumerate() {
                 // Get the actual 3D coordinates
@@ -286,189 +397,201 @@ impl Bitmap {
                 
                 // Create 3D point and project to isometric coordinates
                 let point_3d = Point3D::new(x_val, y_val, z_val);
-                let (iso_x, iso_y) = point_3d.to_isometric(plot_width, plot_height, &bounds);
+                if !bounds.contains(&point_3d) {
+                    continue;
+                }
+                let (iso_x, iso_y) = point_3d.to_isometric(plot_width, plot_height, bounds);
                 
                 // Apply margin offset
                 let screen_x = margin + iso_x;
                 let screen_y = margin + iso_y;
                 
-                // Use Z value to determine color/intensity
-                let color = if z_range.abs() > f64::EPSILON {
-                    let normalized_z = (z_val - z_min) / z_range;
-                    if normalized_z < 0.2 { 1 } 
-                    else if normalized_z < 0.4 { 2 }
-                    else if normalized_z < 0.6 { 3 }
-                    else if normalized_z < 0.8 { 4 }
-                    else { 1 }
+                // Color by height along the theme's colormap
+                let color = if bounds.z_max - bounds.z_min > f64::EPSILON {
+                    self.theme.heat(bounds.z_fraction(z_val))
                 } else {
-                    1
+                    self.theme.series[0]
                 };
                 
                 // Draw a small point for each surface point
                 for dx in 0..2 {
                     for dy in 0..2 {
-                        let px = screen_x.saturating_add(dx).min(self.width - 1);
-                        let py = screen_y.saturating_add(dy).min(self.height - 1);
+                        let px = screen_x.saturating_add(dx).min(self.width.saturating_sub(1));
+                        let py = screen_y.saturating_add(dy).min(self.height.saturating_sub(1));
                         self.set_pixel(px, py, color);
                     }
                 }
             }
         }
 
-        self.draw_3d_axes(margin, plot_width, plot_height, &bounds);
-
-        self.add_surface3d_value_labels(
-            Label3DConfig::new((margin, plot_width, plot_height), (x_min, x_max), (y_min, y_max), (z_min, z_max)),
-        );
+        self.draw_3d_axes(margin, plot_width, plot_height, bounds);
     }
 
     fn add_surface3d_value_labels(
         &mut self,
-        config: Label3DConfig,
+        layout: (usize, usize, usize), // (margin, plot_width, plot_height)
+        bounds: &SurfaceBounds,
     ) {
+        let (margin, plot_width, plot_height) = layout;
+
         // X-axis labels
-        for i in 0..=5 {
-            let x = config.margin + (i * config.plot_width) / 5;
-            let value = config.x_min + (i as f64 / 5.0) * (config.x_max - config.x_min);
-            let text = format!("{value:.1}");
-            let text_x = x.saturating_sub(text.len() * 3);
-            self.render_text(&text, text_x, config.margin + config.plot_height + 5, 4);
+        for tick in bounds.x_axis(6).major {
+            let x = margin + (tick.fraction * plot_width as f64).round() as usize;
+            let text_x = x.saturating_sub(tick.label.len() * 3);
+            self.render_text(&tick.label, text_x, margin + plot_height + 5, self.theme.text);
         }
 
         // Y-axis labels
-        for i in 0..=5 {
-            let y = config.margin + (i * config.plot_height) / 5;
-            let value = config.y_max - (i as f64 / 5.0) * (config.y_max - config.y_min);
-            let text = format!("{value:.1}");
-            self.render_text(&text, config.margin.saturating_sub(40), y.saturating_sub(3), 4);
+        for tick in bounds.y_axis(6).major {
+            let y = margin + ((1.0 - tick.fraction) * plot_height as f64).round() as usize;
+            let text_x = margin.saturating_sub(tick.label.len() * 6 + 4);
+            self.render_text(&tick.label, text_x, y.saturating_sub(3), self.theme.text);
         }
 
         // Z-range info
-        let z_text = format!("Z:[{:.1}, {:.1}]", config.z_min, config.z_max);
-        self.render_text(&z_text, config.margin + 5, config.margin - 15, 4);
+        let z_axis = bounds.z_axis(6);
+        let z_text = match (z_axis.major.first(), z_axis.major.last()) {
+            (Some(first), Some(last)) => format!("Z:[{}, {}]", first.label, last.label),
+            _ => format!("Z:[{:.1}, {:.1}]", bounds.z_min, bounds.z_max),
+        };
+        self.render_text(&z_text, margin + 5, margin.saturating_sub(15), self.theme.text);
     }
 
-    fn add_parametric_value_labels(
+    fn draw_axes_and_grid(
         &mut self,
-        config: LabelConfig,
+        layout: (usize, usize, usize), // (margin, plot_width, plot_height)
+        bounds: &PlotBounds,
     ) {
-        for i in 0..=5 {
-            let y = config.margin + (i * config.plot_height) / 5;
-            let value = config.y_max - (i as f64 / 5.0) * (config.y_max - config.y_min);
-            let text = format!("{value:.1}");
-            self.render_text(&text, config.margin.saturating_sub(40), y.saturating_sub(3), 4);
-        }
-
-        for i in 0..=5 {
-            let x = config.margin + (i * config.plot_width) / 5;
-            let value = config.x_min + (i as f64 / 5.0) * (config.x_max - config.x_min);
-            let text = format!("{value:.1}");
-            let text_x = x.saturating_sub(text.len() * 3);
-            self.render_text(&text, text_x, config.margin + config.plot_height + 5, 4);
-        }
-    }
-
-    fn draw_line(&mut self, x0: usize, y0: usize, x1: usize, y1: usize, color: u8) {
-        let dx = x1.abs_diff(x0);
-        let dy = y1.abs_diff(y0);
-        let x_step = if x0 < x1 { 1i32 } else { -1i32 };
-        let y_step = if y0 < y1 { 1i32 } else { -1i32 };
-
-        let mut error = dx as i32 - dy as i32;
-        let mut x = x0 as i32;
-        let mut y = y0 as i32;
-
-        loop {
-            self.set_pixel(x as usize, y as usize, color);
-
-            if x == x1 as i32 && y == y1 as i32 {
-                break;
-            }
-
-            let e2 = 2 * error;
-            if e2 > -(dy as i32) {
-                error -= dy as i32;
-                x += x_step;
-            }
-            if e2 < dx as i32 {
-                error += dx as i32;
-                y += y_step;
-            }
-        }
-    }
-
-    fn draw_axes_and_grid(&mut self, margin: usize, plot_width: usize, plot_height: usize) {
+        let (margin, plot_width, plot_height) = layout;
         let x_axis_y = margin + plot_height - 1;
+        let (axis, grid) = (self.theme.axis, self.theme.grid);
         for x in margin..margin + plot_width {
-            self.set_pixel(x, x_axis_y, 3);
+            self.set_pixel(x, x_axis_y, axis);
         }
 
         // Y-axis
         for y in margin..margin + plot_height {
-            self.set_pixel(margin, y, 3);
+            self.set_pixel(margin, y, axis);
         }
 
-        // Grid lines
-        for i in 1..10 {
-            let x = margin + (i * plot_width) / 10;
+        // Grid lines at the major ticks, short marks at the minor ones
+        let x_axis = bounds.x_axis(6);
+        let y_axis = bounds.y_axis(6);
+        for fraction in x_axis.interior() {
+            let x = margin + (fraction * plot_width as f64).round() as usize;
             for y in margin..margin + plot_height {
-                if self.get_pixel(x, y) == 0 {
-                    self.set_pixel(x, y, 2);
+                if self.is_background(x, y) {
+                    self.set_pixel(x, y, grid);
                 }
             }
         }
 
-        for i in 1..8 {
-            let y = margin + (i * plot_height) / 8;
+        for fraction in y_axis.interior() {
+            let y = margin + ((1.0 - fraction) * plot_height as f64).round() as usize;
             for x in margin..margin + plot_width {
-                if self.get_pixel(x, y) == 0 {
-                    self.set_pixel(x, y, 2);
+                if self.is_background(x, y) {
+                    self.set_pixel(x, y, grid);
                 }
             }
         }
+
+        for &fraction in &x_axis.minor {
+            let x = margin + (fraction * plot_width as f64).round() as usize;
+            for y in x_axis_y.saturating_sub(3)..x_axis_y {
+                self.set_pixel(x, y, axis);
+            }
+        }
+
+        for &fraction in &y_axis.minor {
+            let y = margin + ((1.0 - fraction) * plot_height as f64).round() as usize;
+            for x in margin + 1..margin + 4 {
+                self.set_pixel(x, y, axis);
+            }
+        }
     }
 
     fn add_value_labels(
         &mut self,
         layout: (usize, usize, usize), // (margin, plot_width, plot_height)
-        y_range: (f64, f64), // (min_val, max_val)
-        x_range: (f64, f64), // (x_min, x_max)
+        bounds: &PlotBounds,
     ) {
         let (margin, plot_width, plot_height) = layout;
-        let (min_val, max_val) = y_range;
-        let (x_min, x_max) = x_range;
-        for i in 0..=5 {
-            let y = margin + (i * plot_height) / 5;
-            let value = max_val - (i as f64 / 5.0) * (max_val - min_val);
-            let text = format!("{value:.1}");
-            self.render_text(&text, margin.saturating_sub(40), y.saturating_sub(3), 4);
+        for tick in bounds.y_axis(6).major {
+            let y = margin + ((1.0 - tick.fraction) * plot_height as f64).round() as usize;
+            let text_x = margin.saturating_sub(tick.label.len() * 6 + 4);
+            self.render_text(&tick.label, text_x, y.saturating_sub(3), self.theme.text);
         }
 
-        for i in 0..=5 {
-            let x = margin + (i * plot_width) / 5;
-            let value = x_min + (i as f64 / 5.0) * (x_max - x_min);
-            let text = format!("{value:.1}");
-            let text_x = x.saturating_sub(text.len() * 3);
-            self.render_text(&text, text_x, margin + plot_height + 5, 4);
+        for tick in bounds.x_axis(6).major {
+            let x = margin + (tick.fraction * plot_width as f64).round() as usize;
+            let text_x = x.saturating_sub(tick.label.len() * 3);
+            self.render_text(&tick.label, text_x, margin + plot_height + 5, self.theme.text);
         }
     }
 
-    fn render_text(&mut self, text: &str, start_x: usize, start_y: usize, color: u8) {
+    pub fn render_text(&mut self, text: &str, start_x: usize, start_y: usize, color: Rgba) {
+        self.render_text_scaled(text, start_x, start_y, color, 1);
+    }
+
+    /// Render text with every font pixel drawn as a `scale` x `scale` block
+    pub fn render_text_scaled(&mut self, text: &str, start_x: usize, start_y: usize, color: Rgba, scale: usize) {
         let mut x_offset = 0;
         for ch in text.chars() {
             let bitmap = get_char_bitmap(ch);
             for (row, bitmap_row) in bitmap.iter().enumerate() {
                 for (col, &pixel) in bitmap_row.iter().enumerate() {
                     if pixel {
-                        let y = start_y + row;
-                        let x = start_x + x_offset + col;
-                        if y < self.height && x < self.width {
-                            self.set_pixel(x, y, color);
+                        for dy in 0..scale {
+                            for dx in 0..scale {
+                                let y = start_y + row * scale + dy;
+                                let x = start_x + x_offset + col * scale + dx;
+                                if y < self.height && x < self.width {
+                                    self.set_pixel(x, y, color);
+                                }
+                            }
                         }
                     }
                 }
             }
-            x_offset += 6; // char width + spacing
+            x_offset += 6 * scale; // char width + spacing
+        }
+    }
+
+    /// Draw the title above the plot area, the X label below the tick labels and the
+    /// Y label stacked vertically along the left edge. Surfaces also get their Z label
+    /// in the top-right corner.
+    pub fn draw_labels(&mut self, margin: usize, settings: &PlotSettings, include_z: bool) {
+        let plot_width = self.width.saturating_sub(2 * margin);
+        let plot_height = self.height.saturating_sub(2 * margin);
+        let center_x = margin + plot_width / 2;
+        let text = self.theme.text;
+
+        if let Some(title) = &settings.title {
+            let scale = if margin >= 30 { 2 } else { 1 };
+            let text_width = title.chars().count() * 6 * scale;
+            let text_y = margin.saturating_sub(7 * scale) / 3;
+            self.render_text_scaled(title, center_x.saturating_sub(text_width / 2), text_y, text, scale);
+        }
+
+        if let Some(xlabel) = &settings.xlabel {
+            let text_width = xlabel.chars().count() * 6;
+            self.render_text(xlabel, center_x.saturating_sub(text_width / 2), margin + plot_height + 20, text);
+        }
+
+        if let Some(ylabel) = &settings.ylabel {
+            let text_height = ylabel.chars().count() * 9;
+            let start_y = (margin + plot_height / 2).saturating_sub(text_height / 2);
+            for (i, ch) in ylabel.chars().enumerate() {
+                self.render_text(&ch.to_string(), 2, start_y + i * 9, text);
+            }
+        }
+
+        if include_z {
+            if let Some(zlabel) = &settings.zlabel {
+                let label = format!("Z: {zlabel}");
+                let text_x = (margin + plot_width).saturating_sub(label.chars().count() * 6);
+                self.render_text(&label, text_x, margin.saturating_sub(15), text);
+            }
         }
     }
 
@@ -481,167 +604,134 @@ impl Bitmap {
         // Draw thick, obvious axes lines
         // X-axis - horizontal line in red
         let x_end = Point3D::new(bounds.x_max, bounds.y_min, bounds.z_min);
-        self.draw_thick_3d_line(&origin, &x_end, bounds, (margin, plot_width, plot_height), 3);
+        self.draw_thick_3d_line(&origin, &x_end, bounds, (margin, plot_width, plot_height), self.theme.axis);
         
         // Y-axis - depth line in green  
         let y_end = Point3D::new(bounds.x_min, bounds.y_max, bounds.z_min);
-        self.draw_thick_3d_line(&origin, &y_end, bounds, (margin, plot_width, plot_height), 4);
+        self.draw_thick_3d_line(&origin, &y_end, bounds, (margin, plot_width, plot_height), self.theme.text);
         
         // Z-axis - vertical line in blue
         let z_end = Point3D::new(bounds.x_min, bounds.y_min, bounds.z_max);
-        self.draw_thick_3d_line(&origin, &z_end, bounds, (margin, plot_width, plot_height), 2);
+        self.draw_thick_3d_line(&origin, &z_end, bounds, (margin, plot_width, plot_height), self.theme.grid);
     }
     
     fn draw_thick_3d_line(&mut self, start: &Point3D, end: &Point3D, bounds: &SurfaceBounds, 
-                         layout: (usize, usize, usize), color: u8) {
+                         layout: (usize, usize, usize), color: Rgba) {
         let (margin, plot_width, plot_height) = layout;
-        let (start_x, start_y) = start.to_isometric(plot_width, plot_height, bounds);
-        let (end_x, end_y) = end.to_isometric(plot_width, plot_height, bounds);
-        
-        // Draw a thick line by drawing multiple parallel lines
-        for offset_x in -1..=1 {
-            for offset_y in -1..=1 {
-                let adj_start_x = (margin as i32 + start_x as i32 + offset_x).max(0) as usize;
-                let adj_start_y = (margin as i32 + start_y as i32 + offset_y).max(0) as usize;
-                let adj_end_x = (margin as i32 + end_x as i32 + offset_x).max(0) as usize;
-                let adj_end_y = (margin as i32 + end_y as i32 + offset_y).max(0) as usize;
-                
-                if adj_start_x < self.width && adj_start_y < self.height &&
-                   adj_end_x < self.width && adj_end_y < self.height {
-                    self.draw_line(adj_start_x, adj_start_y, adj_end_x, adj_end_y, color);
-                }
-            }
-        }
+        let to_pixel = |point: &Point3D| {
+            let (x, y) = point.to_isometric(plot_width, plot_height, bounds);
+            ((margin + x) as f64, (margin + y) as f64)
+        };
+        self.stroke_polyline(&[to_pixel(start), to_pixel(end)], &Stroke::solid(3.0), color);
     }
 }
 
 fn get_char_bitmap(ch: char) -> Vec<Vec<bool>> {
+    glyph_rows(ch)
+        .iter()
+        .map(|row| (0..5).map(|col| row & (0b10000 >> col) != 0).collect())
+        .collect()
+}
+
+/// 5x7 glyphs for printable ASCII, one byte per row with the leftmost pixel in bit 4.
+/// Anything outside the table renders as an empty box.
+fn glyph_rows(ch: char) -> [u8; 7] {
     match ch {
-        '0' => vec![
-            vec![false, true, true, true, false],
-            vec![true, false, false, false, true],
-            vec![true, false, false, true, true],
-            vec![true, false, true, false, true],
-            vec![true, true, false, false, true],
-            vec![true, false, false, false, true],
-            vec![false, true, true, true, false],
-        ],
-        '1' => vec![
-            vec![false, false, true, false, false],
-            vec![false, true, true, false, false],
-            vec![false, false, true, false, false],
-            vec![false, false, true, false, false],
-            vec![false, false, true, false, false],
-            vec![false, false, true, false, false],
-            vec![false, true, true, true, false],
-        ],
-        '2' => vec![
-            vec![false, true, true, true, false],
-            vec![true, false, false, false, true],
-            vec![false, false, false, false, true],
-            vec![false, false, false, true, false],
-            vec![false, false, true, false, false],
-            vec![false, true, false, false, false],
-            vec![true, true, true, true, true],
-        ],
-        '3' => vec![
-            vec![false, true, true, true, false],
-            vec![true, false, false, false, true],
-            vec![false, false, false, false, true],
-            vec![false, false, true, true, false],
-            vec![false, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![false, true, true, true, false],
-        ],
-        '4' => vec![
-            vec![false, false, false, true, false],
-            vec![false, false, true, true, false],
-            vec![false, true, false, true, false],
-            vec![true, false, false, true, false],
-            vec![true, true, true, true, true],
-            vec![false, false, false, true, false],
-            vec![false, false, false, true, false],
-        ],
-        '5' => vec![
-            vec![true, true, true, true, true],
-            vec![true, false, false, false, false],
-            vec![true, true, true, true, false],
-            vec![false, false, false, false, true],
-            vec![false, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![false, true, true, true, false],
-        ],
-        '6' => vec![
-            vec![false, false, true, true, false],
-            vec![false, true, false, false, false],
-            vec![true, false, false, false, false],
-            vec![true, true, true, true, false],
-            vec![true, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![false, true, true, true, false],
-        ],
-        '7' => vec![
-            vec![true, true, true, true, true],
-            vec![false, false, false, false, true],
-            vec![false, false, false, true, false],
-            vec![false, false, true, false, false],
-            vec![false, true, false, false, false],
-            vec![false, true, false, false, false],
-            vec![false, true, false, false, false],
-        ],
-        '8' => vec![
-            vec![false, true, true, true, false],
-            vec![true, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![false, true, true, true, false],
-            vec![true, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![false, true, true, true, false],
-        ],
-        '9' => vec![
-            vec![false, true, true, true, false],
-            vec![true, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![false, true, true, true, true],
-            vec![false, false, false, false, true],
-            vec![false, false, false, true, false],
-            vec![false, true, true, false, false],
-        ],
-        '.' => vec![
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, true, true, false, false],
-            vec![false, true, true, false, false],
-        ],
-        '-' => vec![
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![true, true, true, true, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-        ],
-        ' ' => vec![
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-        ],
-        _ => vec![
-            vec![true, true, true, true, true],
-            vec![true, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![true, false, false, false, true],
-            vec![true, true, true, true, true],
-        ],
+        ' ' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100],
+        '"' => [0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000],
+        '#' => [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010],
+        '$' => [0b00100, 0b01111, 0b10100, 0b01110, 0b00101, 0b11110, 0b00100],
+        '%' => [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011],
+        '&' => [0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101],
+        '\'' => [0b01100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000],
+        '(' => [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010],
+        ')' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000],
+        '*' => [0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000],
+        '+' => [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000],
+        ',' => [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11110, 0b00000, 0b00000, 0b00000],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100],
+        '/' => [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000],
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b01110, 0b10001, 0b00001, 0b00110, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        ':' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],
+        ';' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b00100, 0b01000],
+        '<' => [0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010],
+        '=' => [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000],
+        '>' => [0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000],
+        '?' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100],
+        '@' => [0b01110, 0b10001, 0b00001, 0b01101, 0b10101, 0b10101, 0b01110],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '[' => [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110],
+        '\\' => [0b00000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00000],
+        ']' => [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110],
+        '^' => [0b00100, 0b01010, 0b10001, 0b00000, 0b00000, 0b00000, 0b00000],
+        '_' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111],
+        '`' => [0b01000, 0b00100, 0b00010, 0b00000, 0b00000, 0b00000, 0b00000],
+        'a' => [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111],
+        'b' => [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110],
+        'c' => [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110],
+        'd' => [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111],
+        'e' => [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110],
+        'f' => [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000],
+        'g' => [0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110],
+        'h' => [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001],
+        'i' => [0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'j' => [0b00010, 0b00000, 0b00110, 0b00010, 0b00010, 0b10010, 0b01100],
+        'k' => [0b10000, 0b10000, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010],
+        'l' => [0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'm' => [0b00000, 0b00000, 0b11010, 0b10101, 0b10101, 0b10001, 0b10001],
+        'n' => [0b00000, 0b00000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001],
+        'o' => [0b00000, 0b00000, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110],
+        'p' => [0b00000, 0b00000, 0b11110, 0b10001, 0b11110, 0b10000, 0b10000],
+        'q' => [0b00000, 0b00000, 0b01101, 0b10011, 0b01111, 0b00001, 0b00001],
+        'r' => [0b00000, 0b00000, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000],
+        's' => [0b00000, 0b00000, 0b01110, 0b10000, 0b01110, 0b00001, 0b11110],
+        't' => [0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b01001, 0b00110],
+        'u' => [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b10011, 0b01101],
+        'v' => [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'w' => [0b00000, 0b00000, 0b10001, 0b10001, 0b10101, 0b10101, 0b01010],
+        'x' => [0b00000, 0b00000, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001],
+        'y' => [0b00000, 0b00000, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110],
+        'z' => [0b00000, 0b00000, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111],
+        '{' => [0b00010, 0b00100, 0b00100, 0b01000, 0b00100, 0b00100, 0b00010],
+        '|' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        '}' => [0b01000, 0b00100, 0b00100, 0b00010, 0b00100, 0b00100, 0b01000],
+        '~' => [0b00000, 0b00000, 0b01000, 0b10101, 0b00010, 0b00000, 0b00000],
+        _ => [0b11111, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11111],
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,91 @@
+use std::error::Error;
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{OutputWriter, Bitmap, PlotData, PlotSettings};
+
+/// Size of the file header and the BITMAPINFOHEADER before the pixels
+const HEADER_SIZE: usize = 14 + 40;
+/// 72 DPI, in pixels per meter
+const PIXELS_PER_METER: u32 = 2835;
+
+/// Writes the bitmap plot as an uncompressed 24-bit Windows BMP
+#[derive(Clone, Debug)]
+pub struct BmpWriter;
+
+impl BmpWriter {
+    /// Rows go bottom to top in BGR order, each padded to a multiple of four bytes
+    fn save(bitmap: &Bitmap, filename: &str) -> Result<(), Box<dyn Error>> {
+        let stride = (bitmap.width * 3).div_ceil(4) * 4;
+        let image_size = stride * bitmap.height;
+        let mut bmp = Vec::with_capacity(HEADER_SIZE + image_size);
+
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&((HEADER_SIZE + image_size) as u32).to_le_bytes());
+        bmp.extend_from_slice(&[0; 4]); // reserved
+        bmp.extend_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+
+        bmp.extend_from_slice(&40u32.to_le_bytes());
+        bmp.extend_from_slice(&(bitmap.width as i32).to_le_bytes());
+        bmp.extend_from_slice(&(bitmap.height as i32).to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes()); // planes
+        bmp.extend_from_slice(&24u16.to_le_bytes()); // bits per pixel
+        bmp.extend_from_slice(&0u32.to_le_bytes()); // BI_RGB, no compression
+        bmp.extend_from_slice(&(image_size as u32).to_le_bytes());
+        bmp.extend_from_slice(&PIXELS_PER_METER.to_le_bytes());
+        bmp.extend_from_slice(&PIXELS_PER_METER.to_le_bytes());
+        bmp.extend_from_slice(&[0; 8]); // no palette, all colors important
+
+        for row in bitmap.data.iter().rev() {
+            let start = bmp.len();
+            for color in row {
+                bmp.extend_from_slice(&[color.b, color.g, color.r]);
+            }
+            bmp.resize(start + stride, 0);
+        }
+        std::fs::write(filename, bmp)?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for BmpWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Curve { x: x_result, y: y_result }, settings, width, height);
+        Self::save(&bitmap, filename)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Parametric(result), settings, width, height);
+        Self::save(&bitmap, filename)
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Surface(result), settings, width, height);
+        Self::save(&bitmap, filename)
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     parametric2d::Parametric2DResult,
 };
 
-use super::super::OutputWriter;
+use super::super::{OutputWriter, PlotSettings};
 
 #[derive(Clone, Debug)]
 pub struct CsvWriter;
@@ -18,6 +18,7 @@ impl OutputWriter for CsvWriter {
         y_result: &ExpressionRange1dResult,
         _width: usize,
         _height: usize,
+        _settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
         let mut file = std::fs::File::create(filename)?;
         writeln!(file, "x,y")?;
@@ -33,6 +34,7 @@ impl OutputWriter for CsvWriter {
         result: &Parametric2DResult,
         _width: usize,
         _height: usize,
+        _settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
         let mut file = std::fs::File::create(filename)?;
         writeln!(file, "x,y")?;
@@ -48,6 +50,7 @@ impl OutputWriter for CsvWriter {
         result: &Expression3dResult,
         _width: usize,
         _height: usize,
+        _settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
         let mut file = std::fs::File::create(filename)?;
         writeln!(file, "x,y,z")?;
@@ -0,0 +1,169 @@
+use std::{error::Error, fmt::Write};
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{
+    figure::{coordinate, ps_color, ps_string, Element, Figure},
+    OutputWriter, PlotSettings,
+};
+
+/// Procedures the drawing uses. `reencode` gives the standard fonts the Latin-1
+/// encoding; `text` shows a string with its anchor at the current point, turned
+/// by an angle, as in `(label) 0.5 90 /Helvetica-Latin1 12 text`.
+const PROLOG: &str = r"/M { moveto } bind def
+/L { lineto } bind def
+/reencode {
+  findfont dup length dict begin
+    { 1 index /FID ne { def } { pop pop } ifelse } forall
+    /Encoding ISOLatin1Encoding def
+  currentdict end definefont pop
+} bind def
+/Helvetica-Latin1 /Helvetica reencode
+/Courier-Latin1 /Courier reencode
+/text {
+  gsave
+    selectfont rotate
+    exch dup stringwidth pop 3 -1 roll mul neg 0 rmoveto
+    show
+  grestore
+} bind def
+";
+
+/// Writes plots as Encapsulated PostScript with the layout of the SVG output, one
+/// point per pixel, for toolchains that only take EPS figures
+#[derive(Clone, Debug)]
+pub struct EpsWriter;
+
+impl EpsWriter {
+    fn save(&self, figure: &Figure, filename: &str) -> Result<(), Box<dyn Error>> {
+        std::fs::write(filename, eps_document(figure)?)?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for EpsWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(&Figure::plot(x_result, y_result, width, height, settings), filename)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(&Figure::parametric(result, width, height, settings), filename)
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(&Figure::surface(result, width, height, settings), filename)
+    }
+}
+
+/// The DSC header, prolog and drawing. PostScript puts the origin at the bottom
+/// left, so every Y of the figure is turned upside down.
+fn eps_document(figure: &Figure) -> Result<String, Box<dyn Error>> {
+    let (width, height) = (figure.width, figure.height);
+    let point = |(x, y): (f64, f64)| format!("{} {}", coordinate(x), coordinate(height as f64 - y));
+
+    let mut eps = String::new();
+    writeln!(eps, "%!PS-Adobe-3.0 EPSF-3.0")?;
+    writeln!(eps, "%%BoundingBox: 0 0 {width} {height}")?;
+    writeln!(eps, "%%HiResBoundingBox: 0 0 {width} {height}")?;
+    writeln!(eps, "%%Title: {}", comment_text(&figure.title))?;
+    writeln!(eps, "%%Creator: termplot")?;
+    writeln!(eps, "%%LanguageLevel: 2")?;
+    writeln!(eps, "%%DocumentNeededResources: font Helvetica Courier")?;
+    writeln!(eps, "%%EndComments")?;
+    writeln!(eps, "%%BeginProlog")?;
+    eps.push_str(PROLOG);
+    writeln!(eps, "%%EndProlog")?;
+    writeln!(eps, "%%BeginSetup")?;
+    writeln!(eps, "%%IncludeResource: font Helvetica")?;
+    writeln!(eps, "%%IncludeResource: font Courier")?;
+    writeln!(eps, "%%EndSetup")?;
+
+    writeln!(eps, "% {}", comment_text(&figure.description))?;
+    writeln!(eps, "gsave")?;
+    writeln!(eps, "{} setrgbcolor 0 0 {width} {height} rectfill", ps_color(figure.theme.background))?;
+    for element in &figure.elements {
+        match element {
+            Element::Path(kind, polylines) => {
+                let style = figure.style(*kind);
+                let dash: Vec<String> = style.dash.iter().map(|&length| coordinate(length)).collect();
+                let round = style.round as u8;
+                writeln!(
+                    eps,
+                    "gsave {} setrgbcolor {} setlinewidth {round} setlinecap {round} setlinejoin [{}] 0 setdash newpath",
+                    ps_color(style.color),
+                    coordinate(style.width),
+                    dash.join(" ")
+                )?;
+                for polyline in polylines {
+                    let Some((&first, rest)) = polyline.split_first() else { continue };
+                    write!(eps, "{} M", point(first))?;
+                    // A round capped line of no length is a dot
+                    for &next in if rest.is_empty() { polyline.as_slice() } else { rest } {
+                        write!(eps, " {} L", point(next))?;
+                    }
+                    eps.push('\n');
+                }
+                writeln!(eps, "stroke grestore")?;
+            }
+            Element::Surface(polygons) => {
+                writeln!(eps, "gsave 0.5 setlinewidth 1 setlinejoin")?;
+                for (points, color) in polygons {
+                    let Some((&first, rest)) = points.split_first() else { continue };
+                    write!(eps, "{} setrgbcolor newpath {} M", ps_color(*color), point(first))?;
+                    for &next in rest {
+                        write!(eps, " {} L", point(next))?;
+                    }
+                    writeln!(eps, " closepath gsave fill grestore stroke")?;
+                }
+                writeln!(eps, "grestore")?;
+            }
+            Element::Text { kind, position, anchor, vertical, text } => {
+                let font = if kind.monospace() { "/Courier-Latin1" } else { "/Helvetica-Latin1" };
+                writeln!(
+                    eps,
+                    "{} setrgbcolor {} moveto {} {} {} {font} {} text",
+                    ps_color(figure.theme.text),
+                    point(*position),
+                    ps_string(text),
+                    anchor.fraction(),
+                    if *vertical { 90 } else { 0 },
+                    coordinate(kind.size())
+                )?;
+            }
+        }
+    }
+    writeln!(eps, "grestore")?;
+    writeln!(eps, "showpage")?;
+    writeln!(eps, "%%EOF")?;
+    Ok(eps)
+}
+
+/// Text for a DSC comment, which ends at the first line break and must stay ASCII
+fn comment_text(text: &str) -> String {
+    text.chars().map(|ch| if (' '..='~').contains(&ch) { ch } else { '?' }).collect()
+}
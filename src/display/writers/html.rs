@@ -0,0 +1,250 @@
+use std::{error::Error, fmt::Write};
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::{
+    super::{
+        figure::{coordinate, Element, Figure, PathKind},
+        OutputWriter, PlotSettings,
+    },
+    json::{json_number, json_string},
+    svg::{svg_element, svg_escape},
+};
+
+/// Hover readout, zoom and pan of the `viewBox`, and the layer toggles. It reads
+/// the samples from the `plot-data` JSON, each `[image x, image y, x, y, z?]`.
+const SCRIPT: &str = r##"(function () {
+  const svg = document.querySelector("#plot svg");
+  const data = JSON.parse(document.getElementById("plot-data").textContent);
+  const readout = document.getElementById("readout");
+  const samplesToggle = document.getElementById("samples-toggle");
+  const box = svg.viewBox.baseVal;
+  const home = [box.x, box.y, box.width, box.height];
+  let view = home.slice();
+  let drag = null;
+
+  const setView = (next) => {
+    view = next;
+    svg.setAttribute("viewBox", view.join(" "));
+  };
+  const toImage = (event) => {
+    const point = svg.createSVGPoint();
+    point.x = event.clientX;
+    point.y = event.clientY;
+    return point.matrixTransform(svg.getScreenCTM().inverse());
+  };
+  const format = (value) => (value === null ? "undefined" : String(Number(value.toPrecision(6))));
+
+  const marker = document.createElementNS("http://www.w3.org/2000/svg", "circle");
+  marker.setAttribute("class", "hover");
+  marker.style.display = "none";
+  svg.appendChild(marker);
+  const hide = () => {
+    marker.style.display = "none";
+    readout.textContent = "";
+  };
+
+  const hover = (event) => {
+    if (samplesToggle && !samplesToggle.checked) return hide();
+    const point = toImage(event);
+    const scale = svg.getScreenCTM().a;
+    let nearest = null;
+    let best = Math.pow(24 / scale, 2);
+    for (const sample of data.samples) {
+      const distance = Math.pow(sample[0] - point.x, 2) + Math.pow(sample[1] - point.y, 2);
+      if (distance < best) {
+        best = distance;
+        nearest = sample;
+      }
+    }
+    if (nearest === null) return hide();
+    marker.setAttribute("cx", nearest[0]);
+    marker.setAttribute("cy", nearest[1]);
+    marker.setAttribute("r", 5 / scale);
+    marker.style.display = "";
+    readout.textContent = data.axes.map((name, index) => name + " = " + format(nearest[index + 2])).join(", ");
+  };
+
+  svg.addEventListener("wheel", (event) => {
+    event.preventDefault();
+    const point = toImage(event);
+    const factor = Math.exp(event.deltaY * 0.002);
+    const [x, y, width, height] = view;
+    setView([point.x - (point.x - x) * factor, point.y - (point.y - y) * factor, width * factor, height * factor]);
+    hover(event);
+  }, { passive: false });
+
+  svg.addEventListener("pointerdown", (event) => {
+    drag = { x: event.clientX, y: event.clientY, view: view.slice(), scale: svg.getScreenCTM().a };
+    svg.setPointerCapture(event.pointerId);
+    svg.classList.add("panning");
+  });
+  svg.addEventListener("pointermove", (event) => {
+    if (drag === null) return hover(event);
+    const [x, y, width, height] = drag.view;
+    const dx = (event.clientX - drag.x) / drag.scale;
+    const dy = (event.clientY - drag.y) / drag.scale;
+    setView([x - dx, y - dy, width, height]);
+  });
+  const endDrag = () => {
+    drag = null;
+    svg.classList.remove("panning");
+  };
+  svg.addEventListener("pointerup", endDrag);
+  svg.addEventListener("pointercancel", endDrag);
+  svg.addEventListener("pointerleave", hide);
+  svg.addEventListener("dblclick", () => setView(home.slice()));
+  document.getElementById("reset").addEventListener("click", () => setView(home.slice()));
+
+  for (const toggle of document.querySelectorAll("#controls input[data-classes]")) {
+    toggle.addEventListener("change", () => {
+      for (const name of toggle.dataset.classes.split(" ")) {
+        for (const element of svg.querySelectorAll("." + name)) {
+          element.style.display = toggle.checked ? "" : "none";
+        }
+      }
+      if (!toggle.checked && toggle === samplesToggle) hide();
+    });
+  }
+})();
+"##;
+
+/// Writes plots as a single HTML page that needs nothing but a browser: the SVG
+/// output inlined, with a readout of the sample nearest the pointer, zoom with the
+/// wheel, panning by dragging and checkboxes to hide the layers. The samples are
+/// embedded as JSON; nothing is loaded from the network.
+#[derive(Clone, Debug)]
+pub struct HtmlWriter;
+
+impl HtmlWriter {
+    fn save(&self, figure: &Figure, settings: &PlotSettings, filename: &str) -> Result<(), Box<dyn Error>> {
+        std::fs::write(filename, html_document(figure, settings)?)?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for HtmlWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(&Figure::plot(x_result, y_result, width, height, settings), settings, filename)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(&Figure::parametric(result, width, height, settings), settings, filename)
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(&Figure::surface(result, width, height, settings), settings, filename)
+    }
+}
+
+fn html_document(figure: &Figure, settings: &PlotSettings) -> Result<String, Box<dyn Error>> {
+    let surface = figure.elements.iter().any(|element| matches!(element, Element::Surface(_)));
+    let has_path = |kind: PathKind| figure.elements.iter().any(|element| matches!(element, Element::Path(k, _) if *k == kind));
+
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>")?;
+    writeln!(html, "<html lang=\"en\">")?;
+    writeln!(html, "<head>")?;
+    writeln!(html, "<meta charset=\"utf-8\">")?;
+    writeln!(html, "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">")?;
+    writeln!(html, "<meta name=\"generator\" content=\"termplot\">")?;
+    writeln!(html, "<meta name=\"description\" content=\"{}\">", svg_escape(&figure.description))?;
+    writeln!(html, "<title>{}</title>", svg_escape(&figure.title))?;
+    writeln!(html, "<style>")?;
+    writeln!(
+        html,
+        "  body {{ margin: 0; padding: 16px; background: {}; color: {}; font-family: sans-serif }}",
+        figure.theme.background.css(),
+        figure.theme.text.css()
+    )?;
+    writeln!(html, "  #controls {{ display: flex; flex-wrap: wrap; gap: 16px; align-items: center; margin-bottom: 8px; font-size: 14px }}")?;
+    writeln!(html, "  #readout {{ font-family: monospace }}")?;
+    writeln!(html, "  #plot svg {{ max-width: 100%; height: auto; cursor: crosshair; touch-action: none }}")?;
+    writeln!(html, "  #plot svg.panning {{ cursor: grabbing }}")?;
+    writeln!(html, "  #plot .hover {{ fill: none; stroke: {}; stroke-width: 1.5; vector-effect: non-scaling-stroke }}", figure.theme.text.css())?;
+    writeln!(html, "</style>")?;
+    writeln!(html, "</head>")?;
+    writeln!(html, "<body>")?;
+
+    // One checkbox per layer the plot has; the one holding the samples also turns off the readout
+    writeln!(html, "<div id=\"controls\">")?;
+    let mut toggles = Vec::new();
+    if surface {
+        toggles.push(("surface", "Surface", true));
+    }
+    if has_path(PathKind::Series) || has_path(PathKind::Markers) {
+        toggles.push(("series markers", "Curve", true));
+    }
+    if has_path(PathKind::Asymptote) {
+        toggles.push(("asymptote", "Asymptotes", false));
+    }
+    if has_path(PathKind::Grid) {
+        toggles.push(("grid", "Grid", false));
+    }
+    for (classes, label, holds_samples) in toggles {
+        let id = if holds_samples { " id=\"samples-toggle\"" } else { "" };
+        writeln!(html, "  <label><input type=\"checkbox\"{id} data-classes=\"{classes}\" checked> {label}</label>")?;
+    }
+    writeln!(html, "  <button type=\"button\" id=\"reset\">Reset view</button>")?;
+    writeln!(html, "  <span>Scroll to zoom, drag to pan, double-click to reset</span>")?;
+    writeln!(html, "  <span id=\"readout\" aria-live=\"polite\"></span>")?;
+    writeln!(html, "</div>")?;
+
+    writeln!(html, "<div id=\"plot\">")?;
+    html.push_str(&svg_element(figure)?);
+    writeln!(html, "</div>")?;
+
+    let mut axes = vec![axis_name("x", &settings.xlabel), axis_name("y", &settings.ylabel)];
+    if surface {
+        axes.push(axis_name("z", &settings.zlabel));
+    }
+    let samples: Vec<String> = figure
+        .samples
+        .iter()
+        .map(|sample| {
+            let mut fields = vec![coordinate(sample.position.0), coordinate(sample.position.1)];
+            fields.extend(sample.values.iter().map(|&value| json_number(value)));
+            format!("[{}]", fields.join(","))
+        })
+        .collect();
+    writeln!(html, "<script type=\"application/json\" id=\"plot-data\">")?;
+    writeln!(html, "{{\"axes\": [{}], \"samples\": [{}]}}", axes.join(", "), samples.join(","))?;
+    writeln!(html, "</script>")?;
+    writeln!(html, "<script>")?;
+    html.push_str(SCRIPT);
+    writeln!(html, "</script>")?;
+    writeln!(html, "</body>")?;
+    writeln!(html, "</html>")?;
+    Ok(html)
+}
+
+/// Name of an axis in the readout as a JSON string, its label when it has one. `<`
+/// is escaped so a label cannot end the script element.
+fn axis_name(axis: &str, label: &Option<String>) -> String {
+    json_string(label.as_deref().unwrap_or(axis)).replace('<', "\\u003c")
+}
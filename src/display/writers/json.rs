@@ -0,0 +1,222 @@
+use std::{error::Error, fmt::Write};
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+    range::Range,
+};
+
+use super::super::{AxisScale, OutputWriter, PlotBounds, PlotSettings, SurfaceBounds};
+
+/// Writes the plot result as a JSON document: the kind of plot, the expression and
+/// ranges it came from, the labels, the view window and the samples themselves.
+/// Values that are not finite are `null`, as JSON has no NaN or infinity.
+#[derive(Clone, Debug)]
+pub struct JsonWriter;
+
+impl JsonWriter {
+    /// Every member but the data, which goes last as it is by far the longest
+    fn write_metadata(
+        json: &mut String,
+        kind: &str,
+        settings: &PlotSettings,
+        samples: &[usize],
+        bounds: &[(&str, f64, f64, AxisScale)],
+    ) -> Result<(), Box<dyn Error>> {
+        let optional = |text: &Option<String>| text.as_deref().map_or_else(|| "null".to_string(), json_string);
+
+        writeln!(json, "{{")?;
+        writeln!(json, "  \"kind\": {},", json_string(kind))?;
+        writeln!(json, "  \"expression\": {},", json_string(&settings.expression))?;
+        writeln!(json, "  \"title\": {},", optional(&settings.title))?;
+        let mut labels = vec![("x", &settings.xlabel), ("y", &settings.ylabel)];
+        if bounds.len() > 2 {
+            labels.push(("z", &settings.zlabel));
+        }
+        let labels: Vec<String> = labels.into_iter().map(|(axis, label)| format!("\"{axis}\": {}", optional(label))).collect();
+        writeln!(json, "  \"labels\": {{{}}},", labels.join(", "))?;
+
+        let ranges: Vec<String> = settings
+            .ranges
+            .iter()
+            .zip(samples.iter().chain(std::iter::repeat(&0)))
+            .map(|(range, &count)| format!("    {}", range_object(range, count)))
+            .collect();
+        writeln!(json, "  \"ranges\": [\n{}\n  ],", ranges.join(",\n"))?;
+        writeln!(json, "  \"samples\": {},", samples.iter().product::<usize>())?;
+
+        // No window when nothing is finite
+        if bounds.iter().all(|&(_, min, max, _)| min.is_finite() && max.is_finite()) {
+            let axes: Vec<String> = bounds
+                .iter()
+                .map(|&(axis, min, max, scale)| {
+                    let scale = match scale {
+                        AxisScale::Linear => "linear",
+                        AxisScale::Log => "log",
+                    };
+                    format!(
+                        "    \"{axis}\": {{\"min\": {}, \"max\": {}, \"scale\": \"{scale}\"}}",
+                        json_number(min),
+                        json_number(max)
+                    )
+                })
+                .collect();
+            writeln!(json, "  \"bounds\": {{\n{}\n  }},", axes.join(",\n"))?;
+        } else {
+            writeln!(json, "  \"bounds\": null,")?;
+        }
+        Ok(())
+    }
+
+    fn write_curve(
+        filename: &str,
+        kind: &str,
+        (x_values, y_values): (&[f64], &[f64]),
+        samples: usize,
+        bounds: Option<PlotBounds>,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bounds = match bounds {
+            Some(bounds) => [("x", bounds.x_min, bounds.x_max, bounds.x_scale), ("y", bounds.y_min, bounds.y_max, bounds.y_scale)],
+            None => [("x", f64::NAN, f64::NAN, settings.x_scale), ("y", f64::NAN, f64::NAN, settings.y_scale)],
+        };
+        let mut json = String::new();
+        Self::write_metadata(&mut json, kind, settings, &[samples], &bounds)?;
+        writeln!(json, "  \"data\": {{")?;
+        writeln!(json, "    \"x\": {},", json_array(x_values.iter().take(samples)))?;
+        writeln!(json, "    \"y\": {}", json_array(y_values.iter().take(samples)))?;
+        writeln!(json, "  }}")?;
+        writeln!(json, "}}")?;
+        std::fs::write(filename, json)?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for JsonWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let samples = x_result.0.len().min(y_result.0.len());
+        let bounds = PlotBounds::for_plot(&x_result.0, &y_result.0, settings);
+        Self::write_curve(filename, "plot2d", (&x_result.0, &y_result.0), samples, bounds, settings)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bounds = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings);
+        Self::write_curve(filename, "parametric2d", (&result.x_values, &result.y_values), result.len(), bounds, settings)
+    }
+
+    /// `z` holds one row per Y value, `z[j][i]` being the height at `x[i]`, `y[j]`
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bounds = match SurfaceBounds::for_surface(result, settings) {
+            Some(bounds) => [
+                ("x", bounds.x_min, bounds.x_max, bounds.x_scale),
+                ("y", bounds.y_min, bounds.y_max, bounds.y_scale),
+                ("z", bounds.z_min, bounds.z_max, bounds.z_scale),
+            ],
+            None => [
+                ("x", f64::NAN, f64::NAN, settings.x_scale),
+                ("y", f64::NAN, f64::NAN, settings.y_scale),
+                ("z", f64::NAN, f64::NAN, settings.z_scale),
+            ],
+        };
+        let mut json = String::new();
+        Self::write_metadata(&mut json, "surface3d", settings, &[result.x_len(), result.y_len()], &bounds)?;
+        writeln!(json, "  \"data\": {{")?;
+        writeln!(json, "    \"x\": {},", json_array(&result.x_values))?;
+        writeln!(json, "    \"y\": {},", json_array(&result.y_values))?;
+        let rows: Vec<String> = (0..result.y_len())
+            .map(|y_idx| {
+                let row: Vec<f64> = (0..result.x_len()).map(|x_idx| result.get_z(x_idx, y_idx).unwrap_or(f64::NAN)).collect();
+                format!("      {}", json_array(&row))
+            })
+            .collect();
+        writeln!(json, "    \"z\": [\n{}\n    ]", rows.join(",\n"))?;
+        writeln!(json, "  }}")?;
+        writeln!(json, "}}")?;
+        std::fs::write(filename, json)?;
+        Ok(())
+    }
+}
+
+/// A range as an object: the variable with either the numeric interval or the file
+/// and column it was read from, and how many samples it gave
+fn range_object(range: &Range, samples: usize) -> String {
+    let (variable, source) = match range {
+        Range::Numeric(node) => {
+            let (variable, start, end) = &node.value;
+            (variable, format!("\"start\": {}, \"end\": {}", json_number(*start), json_number(*end)))
+        }
+        Range::NumericStep(node) => {
+            let (variable, start, end, steps) = &node.value;
+            (
+                variable,
+                format!("\"start\": {}, \"end\": {}, \"steps\": {}", json_number(*start), json_number(*end), json_number(*steps)),
+            )
+        }
+        Range::FileBare(node) => {
+            let (variable, file) = &node.value;
+            (variable, format!("\"file\": {}", json_string(file)))
+        }
+        Range::FileCol(node) => {
+            let (variable, file, column) = &node.value;
+            (variable, format!("\"file\": {}, \"column\": {column}", json_string(file)))
+        }
+    };
+    format!("{{\"variable\": {}, {source}, \"samples\": {samples}}}", json_string(variable))
+}
+
+/// A JSON number, or `null` when the value is not finite. Very large and very
+/// small values get an exponent instead of dozens of digits.
+pub fn json_number(value: f64) -> String {
+    match value.abs() {
+        magnitude if !magnitude.is_finite() => "null".to_string(),
+        magnitude if magnitude == 0.0 || (1e-4..1e15).contains(&magnitude) => format!("{value}"),
+        _ => format!("{value:e}"),
+    }
+}
+
+/// A JSON array of numbers on one line
+pub fn json_array<'a>(values: impl IntoIterator<Item = &'a f64>) -> String {
+    let values: Vec<String> = values.into_iter().map(|&value| json_number(value)).collect();
+    format!("[{}]", values.join(", "))
+}
+
+/// A JSON string literal. Quotes, backslashes and control characters are escaped;
+/// everything else is written as is, the file being UTF-8.
+pub fn json_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{0}'..='\u{1f}' => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -1,14 +1,56 @@
-use std::{error::Error, io::Write};
+use std::{error::Error, fmt::Write, path::Path};
 
 use crate::{
     values::{ExpressionRange1dResult, Expression3dResult},
     parametric2d::Parametric2DResult,
 };
 
-use super::super::OutputWriter;
+use super::super::{AxisScale, OutputWriter, PlotBounds, PlotSettings, Segments, SurfaceBounds};
 
+/// Tables with more rows than this go to a `.dat` file next to the `.tex` one, as
+/// TeX gets slow and can run out of memory reading long inline data
+const INLINE_ROWS: usize = 2000;
+
+/// What a LaTeX file holds around the `tikzpicture`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LatexMode {
+    /// A `standalone` document cropped to the figure, to compile into a PDF of its own
+    Standalone,
+    /// Only the `tikzpicture`, to `\input` into a document that loads pgfplots
+    Snippet,
+    /// A complete `article`
+    #[default]
+    Document,
+}
+
+/// Writes plots as pgfplots figures. The data goes into `table`s, inline or in a
+/// `.dat` file beside the output when it is long.
 #[derive(Clone, Debug)]
-pub struct LatexWriter;
+pub struct LatexWriter {
+    pub mode: LatexMode,
+}
+
+impl LatexWriter {
+    pub fn new(mode: LatexMode) -> Self {
+        Self { mode }
+    }
+
+    /// Wrap the picture as the mode asks and write it out
+    fn save(&self, filename: &str, picture: &str) -> Result<(), Box<dyn Error>> {
+        let preamble = "\\usepackage{pgfplots}\n\\pgfplotsset{compat=1.18}\n";
+        let text = match self.mode {
+            LatexMode::Standalone => format!(
+                "\\documentclass[tikz]{{standalone}}\n{preamble}\\begin{{document}}\n{picture}\\end{{document}}\n"
+            ),
+            LatexMode::Snippet => format!("% Needs in the preamble:\n% {}\n{picture}", preamble.trim_end().replace('\n', "\n% ")),
+            LatexMode::Document => {
+                format!("\\documentclass{{article}}\n{preamble}\\begin{{document}}\n{picture}\\end{{document}}\n")
+            }
+        };
+        std::fs::write(filename, text)?;
+        Ok(())
+    }
+}
 
 impl OutputWriter for LatexWriter {
     fn write(
@@ -18,158 +60,266 @@ impl OutputWriter for LatexWriter {
         y_result: &ExpressionRange1dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
-        let mut file = std::fs::File::create(filename)?;
-
-        writeln!(file, r"\documentclass{{article}}")?;
-        writeln!(file, r"\usepackage{{pgfplots}}")?;
-        writeln!(file, r"\pgfplotsset{{compat=1.18}}")?;
-        writeln!(file, r"\begin{{document}}")?;
-        writeln!(file, r"\begin{{tikzpicture}}")?;
-        writeln!(file, r"\begin{{axis}}[")?;
-        writeln!(
-            file,
-            r"    width={}cm, height={}cm,",
-            width as f64 / 100.0,
-            height as f64 / 100.0
-        )?;
-        writeln!(file, r"    xlabel={{X}}, ylabel={{Y}},")?;
-        writeln!(
-            file,
-            r"    xmin={:.3}, xmax={:.3},",
-            x_result.min(),
-            x_result.max()
-        )?;
-        writeln!(
-            file,
-            r"    ymin={:.3}, ymax={:.3},",
-            y_result.min(),
-            y_result.max()
-        )?;
-        writeln!(file, r"    grid=major")?;
-        writeln!(file, r"]")?;
+        let mut tex = String::new();
+        writeln!(tex, r"\begin{{tikzpicture}}")?;
+        writeln!(tex, r"\begin{{{}}}[", axis_environment(settings))?;
+        writeln!(tex, r"    width={}cm, height={}cm,", width as f64 / 100.0, height as f64 / 100.0)?;
+        write_axis_labels(&mut tex, settings, false)?;
+        let bounds = PlotBounds::for_plot(&x_result.0, &y_result.0, settings);
+        if let Some(bounds) = &bounds {
+            write_axis_window(&mut tex, bounds)?;
+        }
+        writeln!(tex, r"    unbounded coords=jump,")?;
+        writeln!(tex, r"    grid=major")?;
+        writeln!(tex, r"]")?;
 
-        writeln!(
-            file,
-            r"\addplot[blue, mark=*, mark size=1pt] coordinates {{"
-        )?;
-        for (&x_val, &y_val) in x_result.0.iter().zip(y_result.0.iter()) {
-            writeln!(file, "    ({x_val:.6}, {y_val:.6})")?;
+        let points: Vec<(f64, f64)> = x_result.0.iter().copied().zip(y_result.0.iter().copied()).collect();
+        let segments = bounds.map(|bounds| Segments::split(&points, &bounds));
+        if let (Some(bounds), Some(segments)) = (&bounds, &segments) {
+            if settings.asymptotes {
+                for &x_val in &segments.asymptotes {
+                    writeln!(
+                        tex,
+                        r"\addplot[gray, dashed, forget plot] coordinates {{({x}, {}) ({x}, {})}};",
+                        axis_limit(bounds.y_min, bounds.y_scale),
+                        axis_limit(bounds.y_max, bounds.y_scale),
+                        x = latex_number(x_val, settings.x_scale)
+                    )?;
+                }
+            }
         }
-        writeln!(file, r"}};")?;
 
-        writeln!(file, r"\end{{axis}}")?;
-        writeln!(file, r"\end{{tikzpicture}}")?;
-        writeln!(file, r"\end{{document}}")?;
-        Ok(())
+        let rows = curve_rows(&points, segments.as_ref(), settings);
+        writeln!(tex, r"\addplot[blue, mark=*, mark size=1pt] {};", data_table(filename, "x y", &rows)?)?;
+
+        writeln!(tex, r"\end{{{}}}", axis_environment(settings))?;
+        writeln!(tex, r"\end{{tikzpicture}}")?;
+        self.save(filename, &tex)
     }
 
+    /// A curve has the same unit on both axes, so circles come out round
     fn write_parametric(
         &self,
         filename: &str,
         result: &Parametric2DResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
-        let mut file = std::fs::File::create(filename)?;
-
-        writeln!(file, r"\documentclass{{article}}")?;
-        writeln!(file, r"\usepackage{{pgfplots}}")?;
-        writeln!(file, r"\pgfplotsset{{compat=1.18}}")?;
-        writeln!(file, r"\begin{{document}}")?;
-        writeln!(file, r"\begin{{tikzpicture}}")?;
-        writeln!(file, r"\begin{{axis}}[")?;
-        writeln!(
-            file,
-            r"    width={}cm, height={}cm,",
-            width as f64 / 100.0,
-            height as f64 / 100.0
-        )?;
-        writeln!(file, r"    xlabel={{X}}, ylabel={{Y}},")?;
-
-        let x_min = result.x_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let x_max = result.x_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        let y_min = result.y_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let y_max = result.y_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-
-        writeln!(file, r"    xmin={x_min:.3}, xmax={x_max:.3},")?;
-        writeln!(file, r"    ymin={y_min:.3}, ymax={y_max:.3},")?;
-        writeln!(file, r"    grid=major")?;
-        writeln!(file, r"]")?;
-
-        writeln!(
-            file,
-            r"\addplot[red, mark=*, mark size=1pt] coordinates {{"
-        )?;
-        for (&x_val, &y_val) in result.x_values.iter().zip(result.y_values.iter()) {
-            writeln!(file, "    ({x_val:.6}, {y_val:.6})")?;
+        let mut tex = String::new();
+        writeln!(tex, r"\begin{{tikzpicture}}")?;
+        writeln!(tex, r"\begin{{{}}}[", axis_environment(settings))?;
+        writeln!(tex, r"    width={}cm, height={}cm,", width as f64 / 100.0, height as f64 / 100.0)?;
+        write_axis_labels(&mut tex, settings, false)?;
+        let bounds = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings);
+        if let Some(bounds) = &bounds {
+            write_axis_window(&mut tex, bounds)?;
+        }
+        // Equal units mean nothing between a linear and a log axis
+        if (settings.x_scale, settings.y_scale) == (AxisScale::Linear, AxisScale::Linear) {
+            writeln!(tex, r"    axis equal image,")?;
         }
-        writeln!(file, r"}};")?;
+        writeln!(tex, r"    unbounded coords=jump,")?;
+        writeln!(tex, r"    grid=major")?;
+        writeln!(tex, r"]")?;
 
-        writeln!(file, r"\end{{axis}}")?;
-        writeln!(file, r"\end{{tikzpicture}}")?;
-        writeln!(file, r"\end{{document}}")?;
-        Ok(())
+        let points: Vec<(f64, f64)> = result.x_values.iter().copied().zip(result.y_values.iter().copied()).collect();
+        let segments = bounds.map(|bounds| Segments::split_curve(&points, &bounds));
+        let rows = curve_rows(&points, segments.as_ref(), settings);
+        writeln!(tex, r"\addplot[red, mark=*, mark size=1pt] {};", data_table(filename, "x y", &rows)?)?;
+
+        writeln!(tex, r"\end{{{}}}", axis_environment(settings))?;
+        writeln!(tex, r"\end{{tikzpicture}}")?;
+        self.save(filename, &tex)
     }
 
+    /// A `surf` plot over the whole grid, one mesh row per Y value. Undefined
+    /// points are kept as `nan` so the rows stay whole and pgfplots leaves holes.
     fn write_surface3d(
         &self,
         filename: &str,
         result: &Expression3dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
-        let mut file = std::fs::File::create(filename)?;
-
-        writeln!(file, r"\documentclass{{article}}")?;
-        writeln!(file, r"\usepackage{{pgfplots}}")?;
-        writeln!(file, r"\pgfplotsset{{compat=1.18}}")?;
-        writeln!(file, r"\begin{{document}}")?;
-        writeln!(file, r"\begin{{tikzpicture}}")?;
-        writeln!(file, r"\begin{{axis}}[")?;
-        writeln!(
-            file,
-            r"    width={}cm, height={}cm,",
-            width as f64 / 100.0,
-            height as f64 / 100.0
-        )?;
-        writeln!(file, r"    xlabel={{X}}, ylabel={{Y}}, zlabel={{Z}},")?;
-        writeln!(
-            file,
-            r"    xmin={:.3}, xmax={:.3},",
-            result.x_min(),
-            result.x_max()
-        )?;
-        writeln!(
-            file,
-            r"    ymin={:.3}, ymax={:.3},",
-            result.y_min(),
-            result.y_max()
-        )?;
-        writeln!(
-            file,
-            r"    zmin={:.3}, zmax={:.3},",
-            result.z_min(),
-            result.z_max()
-        )?;
-        writeln!(file, r"    grid=major,")?;
-        writeln!(file, r"    view={{30}}{{30}}")?;
-        writeln!(file, r"]")?;
+        let mut tex = String::new();
+        writeln!(tex, r"\begin{{tikzpicture}}")?;
+        writeln!(tex, r"\begin{{axis}}[")?;
+        writeln!(tex, r"    width={}cm, height={}cm,", width as f64 / 100.0, height as f64 / 100.0)?;
+        write_axis_labels(&mut tex, settings, true)?;
+        if let Some(bounds) = SurfaceBounds::for_surface(result, settings) {
+            write_axis_window(
+                &mut tex,
+                &PlotBounds {
+                    x_scale: bounds.x_scale,
+                    y_scale: bounds.y_scale,
+                    ..PlotBounds::new(bounds.x_min, bounds.x_max, bounds.y_min, bounds.y_max)
+                },
+            )?;
+            writeln!(
+                tex,
+                r"    zmin={}, zmax={},",
+                axis_limit(bounds.z_min, bounds.z_scale),
+                axis_limit(bounds.z_max, bounds.z_scale)
+            )?;
+        }
+        for (key, scale) in [("xmode", settings.x_scale), ("ymode", settings.y_scale), ("zmode", settings.z_scale)] {
+            if scale == AxisScale::Log {
+                writeln!(tex, r"    {key}=log,")?;
+            }
+        }
+        writeln!(tex, r"    unbounded coords=jump,")?;
+        writeln!(tex, r"    grid=major,")?;
+        writeln!(tex, r"    view={{30}}{{30}}")?;
+        writeln!(tex, r"]")?;
 
-        writeln!(file, r"\addplot3[surf, mesh/rows={}, mesh/cols={}]", result.y_len(), result.x_len())?;
-        writeln!(file, "coordinates {{")?;
+        let mut rows = Vec::with_capacity(result.x_len() * result.y_len());
         for (y_idx, &y_val) in result.y_values.iter().enumerate() {
             for (x_idx, &x_val) in result.x_values.iter().enumerate() {
-                if let Some(z_val) = result.get_z(x_idx, y_idx) {
-                    writeln!(file, "    ({x_val:.6}, {y_val:.6}, {z_val:.6})")?;
-                }
+                let z_val = result.get_z(x_idx, y_idx).unwrap_or(f64::NAN);
+                rows.push(format!(
+                    "{} {} {}",
+                    latex_number(x_val, settings.x_scale),
+                    latex_number(y_val, settings.y_scale),
+                    latex_number(z_val, settings.z_scale)
+                ));
             }
         }
-        writeln!(file, "}};")?;
+        writeln!(
+            tex,
+            r"\addplot3[surf, mesh/rows={}, mesh/cols={}] {};",
+            result.y_len(),
+            result.x_len(),
+            data_table(filename, "x y z", &rows)?
+        )?;
 
-        writeln!(file, r"\end{{axis}}")?;
-        writeln!(file, r"\end{{tikzpicture}}")?;
-        writeln!(file, r"\end{{document}}")?;
-        Ok(())
+        writeln!(tex, r"\end{{axis}}")?;
+        writeln!(tex, r"\end{{tikzpicture}}")?;
+        self.save(filename, &tex)
+    }
+}
+
+/// The `table` argument of `\addplot` for rows of whitespace separated columns
+/// under `header`. Long tables are written to the output's name with a `.dat`
+/// extension and referenced by that path, so LaTeX has to run where termplot ran.
+fn data_table(filename: &str, header: &str, rows: &[String]) -> Result<String, Box<dyn Error>> {
+    if rows.len() <= INLINE_ROWS {
+        return Ok(format!("table {{\n    {header}\n    {}\n}}", rows.join("\n    ")));
+    }
+    let path = Path::new(filename);
+    let data_path = match path.extension() {
+        Some(extension) if extension == "dat" => format!("{filename}.dat"),
+        _ => path.with_extension("dat").to_string_lossy().into_owned(),
+    };
+    std::fs::write(&data_path, format!("{header}\n{}\n", rows.join("\n")))?;
+    Ok(format!("table {{{data_path}}}"))
+}
+
+/// Emit the `title` and axis label keys of an axis environment, falling back to the
+/// bare axis names when no label was given
+fn write_axis_labels(
+    tex: &mut String,
+    settings: &PlotSettings,
+    include_z: bool,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(title) = &settings.title {
+        writeln!(tex, r"    title={{{}}},", latex_escape(title))?;
+    }
+    let xlabel = latex_escape(settings.xlabel.as_deref().unwrap_or("X"));
+    let ylabel = latex_escape(settings.ylabel.as_deref().unwrap_or("Y"));
+    if include_z {
+        let zlabel = latex_escape(settings.zlabel.as_deref().unwrap_or("Z"));
+        writeln!(tex, r"    xlabel={{{xlabel}}}, ylabel={{{ylabel}}}, zlabel={{{zlabel}}},")?;
+    } else {
+        writeln!(tex, r"    xlabel={{{xlabel}}}, ylabel={{{ylabel}}},")?;
+    }
+    Ok(())
+}
+
+/// Table rows of a series. With `segments` the pieces are separated by a `nan` row
+/// so pgfplots does not join across discontinuities.
+fn curve_rows(points: &[(f64, f64)], segments: Option<&Segments>, settings: &PlotSettings) -> Vec<String> {
+    let pieces = match segments {
+        Some(segments) => segments.pieces.iter().map(Vec::as_slice).collect(),
+        None => vec![points],
+    };
+    let mut rows = Vec::with_capacity(points.len());
+    for (index, piece) in pieces.into_iter().enumerate() {
+        if index > 0 {
+            rows.push("nan nan".to_string());
+        }
+        for &(x_val, y_val) in piece {
+            rows.push(format!("{} {}", latex_number(x_val, settings.x_scale), latex_number(y_val, settings.y_scale)));
+        }
+    }
+    rows
+}
+
+/// Emit the `xmin`..`ymax` keys of the plot window
+fn write_axis_window(tex: &mut String, bounds: &PlotBounds) -> Result<(), Box<dyn Error>> {
+    writeln!(
+        tex,
+        r"    xmin={}, xmax={},",
+        axis_limit(bounds.x_min, bounds.x_scale),
+        axis_limit(bounds.x_max, bounds.x_scale)
+    )?;
+    writeln!(
+        tex,
+        r"    ymin={}, ymax={},",
+        axis_limit(bounds.y_min, bounds.y_scale),
+        axis_limit(bounds.y_max, bounds.y_scale)
+    )?;
+    Ok(())
+}
+
+/// Axis limit with three decimals, or in scientific notation on a log axis where
+/// limits can be far below 0.001
+fn axis_limit(value: f64, scale: AxisScale) -> String {
+    match scale {
+        AxisScale::Linear => format!("{value:.3}"),
+        AxisScale::Log => format!("{value:.4e}"),
+    }
+}
+
+/// pgfplots environment for the axis scales of a 2D plot
+fn axis_environment(settings: &PlotSettings) -> &'static str {
+    match (settings.x_scale, settings.y_scale) {
+        (AxisScale::Linear, AxisScale::Linear) => "axis",
+        (AxisScale::Log, AxisScale::Linear) => "semilogxaxis",
+        (AxisScale::Linear, AxisScale::Log) => "semilogyaxis",
+        (AxisScale::Log, AxisScale::Log) => "loglogaxis",
+    }
+}
+
+/// Coordinate value as pgfplots reads it, in full so tiny values keep their
+/// magnitude, with an exponent when very large or small. Samples the axis cannot
+/// show (non-finite, or non-positive on a log axis) become `nan` so that
+/// `unbounded coords=jump` breaks the line there.
+fn latex_number(value: f64, scale: AxisScale) -> String {
+    match value.abs() {
+        _ if !scale.accepts(value) => "nan".to_string(),
+        magnitude if magnitude == 0.0 || (1e-4..1e15).contains(&magnitude) => format!("{value}"),
+        _ => format!("{value:e}"),
+    }
+}
+
+/// Escape the characters TeX treats specially so labels print verbatim
+fn latex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => escaped.push_str(r"\textbackslash{}"),
+            '{' | '}' | '#' | '$' | '%' | '&' | '_' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '^' => escaped.push_str(r"\^{}"),
+            '~' => escaped.push_str(r"\~{}"),
+            _ => escaped.push(ch),
+        }
     }
-}
\ No newline at end of file
+    escaped
+}
@@ -1,13 +1,33 @@
 pub mod csv;
-pub mod ppm;
+pub mod json;
+pub mod vegalite;
+pub mod npy;
+pub mod models;
+pub mod bmp;
+pub mod netpbm;
+pub mod png;
 pub mod svg;
+pub mod pdf;
+pub mod eps;
+pub mod html;
 pub mod latex;
+pub mod scripts;
 pub mod sixel;
 pub mod regis;
 
 pub use csv::CsvWriter;
-pub use ppm::PpmWriter;
+pub use json::JsonWriter;
+pub use vegalite::VegaLiteWriter;
+pub use npy::NpyWriter;
+pub use models::{ObjWriter, PlyWriter, StlWriter};
+pub use bmp::BmpWriter;
+pub use netpbm::{NetpbmFormat, PbmWriter, PgmWriter, PpmWriter};
+pub use png::PngWriter;
 pub use svg::SvgWriter;
-pub use latex::LatexWriter;
+pub use pdf::PdfWriter;
+pub use eps::EpsWriter;
+pub use html::HtmlWriter;
+pub use latex::{LatexMode, LatexWriter};
+pub use scripts::{GnuplotWriter, MatplotlibWriter};
 pub use sixel::SixelWriter;
 pub use regis::RegisWriter;
\ No newline at end of file
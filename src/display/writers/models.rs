@@ -0,0 +1,234 @@
+use std::{error::Error, fmt::Write};
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{mesh::Mesh, OutputWriter, PlotSettings};
+
+const CURVE_ERROR: &str = "only 3D surfaces can be written as meshes";
+
+/// Writes surfaces as Wavefront OBJ meshes with a normal per vertex, smooth shaded
+/// in Blender and most other 3D tools. Curves have no mesh and are refused.
+#[derive(Clone, Debug)]
+pub struct ObjWriter;
+
+impl OutputWriter for ObjWriter {
+    fn write(
+        &self,
+        _filename: &str,
+        _x_result: &ExpressionRange1dResult,
+        _y_result: &ExpressionRange1dResult,
+        _width: usize,
+        _height: usize,
+        _settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        Err(CURVE_ERROR.into())
+    }
+
+    fn write_parametric(
+        &self,
+        _filename: &str,
+        _result: &Parametric2DResult,
+        _width: usize,
+        _height: usize,
+        _settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        Err(CURVE_ERROR.into())
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let mesh = Mesh::surface(result, settings);
+        let mut obj = String::new();
+        writeln!(obj, "# Surface written by termplot")?;
+        if let Some(title) = &settings.title {
+            writeln!(obj, "# {}", single_line(title))?;
+        }
+        writeln!(obj, "o surface")?;
+        for [x, y, z] in &mesh.vertices {
+            writeln!(obj, "v {} {} {}", *x as f32, *y as f32, *z as f32)?;
+        }
+        for [x, y, z] in &mesh.normals {
+            writeln!(obj, "vn {} {} {}", *x as f32, *y as f32, *z as f32)?;
+        }
+        // Indices count from 1, and each vertex has the normal of the same number
+        for triangle in &mesh.triangles {
+            let [a, b, c] = triangle.map(|vertex| vertex + 1);
+            writeln!(obj, "f {a}//{a} {b}//{b} {c}//{c}")?;
+        }
+        std::fs::write(filename, obj)?;
+        Ok(())
+    }
+}
+
+/// Writes surfaces as STL triangle soups for slicers, as text or, more compactly,
+/// as binary. STL has no shared vertices, so every facet carries its own corners
+/// and normal. Curves are refused.
+#[derive(Clone, Debug)]
+pub struct StlWriter {
+    pub binary: bool,
+}
+
+impl StlWriter {
+    pub fn new(binary: bool) -> Self {
+        Self { binary }
+    }
+
+    fn ascii(mesh: &Mesh) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut stl = String::from("solid surface\n");
+        for triangle in &mesh.triangles {
+            let [nx, ny, nz] = mesh.face_normal(triangle);
+            writeln!(stl, "  facet normal {} {} {}", nx as f32, ny as f32, nz as f32)?;
+            writeln!(stl, "    outer loop")?;
+            for &vertex in triangle {
+                let [x, y, z] = mesh.vertices[vertex];
+                writeln!(stl, "      vertex {} {} {}", x as f32, y as f32, z as f32)?;
+            }
+            writeln!(stl, "    endloop")?;
+            writeln!(stl, "  endfacet")?;
+        }
+        writeln!(stl, "endsolid surface")?;
+        Ok(stl.into_bytes())
+    }
+
+    /// An 80 byte header that must not start with `solid`, the triangle count and
+    /// 50 bytes per triangle: normal and corners as little-endian `f32`, then an
+    /// unused attribute
+    fn binary(mesh: &Mesh) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut stl = vec![0u8; 80];
+        let header = b"Surface written by termplot";
+        stl[..header.len()].copy_from_slice(header);
+        stl.extend_from_slice(&u32::try_from(mesh.triangles.len())?.to_le_bytes());
+        for triangle in &mesh.triangles {
+            let corners = triangle.map(|vertex| mesh.vertices[vertex]);
+            for vector in std::iter::once(mesh.face_normal(triangle)).chain(corners) {
+                for component in vector {
+                    stl.extend_from_slice(&(component as f32).to_le_bytes());
+                }
+            }
+            stl.extend_from_slice(&0u16.to_le_bytes());
+        }
+        Ok(stl)
+    }
+}
+
+impl OutputWriter for StlWriter {
+    fn write(
+        &self,
+        _filename: &str,
+        _x_result: &ExpressionRange1dResult,
+        _y_result: &ExpressionRange1dResult,
+        _width: usize,
+        _height: usize,
+        _settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        Err(CURVE_ERROR.into())
+    }
+
+    fn write_parametric(
+        &self,
+        _filename: &str,
+        _result: &Parametric2DResult,
+        _width: usize,
+        _height: usize,
+        _settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        Err(CURVE_ERROR.into())
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let mesh = Mesh::surface(result, settings);
+        let stl = match self.binary {
+            true => Self::binary(&mesh)?,
+            false => Self::ascii(&mesh)?,
+        };
+        std::fs::write(filename, stl)?;
+        Ok(())
+    }
+}
+
+/// Writes surfaces as ASCII PLY meshes with a normal and a color per vertex, the
+/// colors following the height on the theme's color scale. Curves are refused.
+#[derive(Clone, Debug)]
+pub struct PlyWriter;
+
+impl OutputWriter for PlyWriter {
+    fn write(
+        &self,
+        _filename: &str,
+        _x_result: &ExpressionRange1dResult,
+        _y_result: &ExpressionRange1dResult,
+        _width: usize,
+        _height: usize,
+        _settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        Err(CURVE_ERROR.into())
+    }
+
+    fn write_parametric(
+        &self,
+        _filename: &str,
+        _result: &Parametric2DResult,
+        _width: usize,
+        _height: usize,
+        _settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        Err(CURVE_ERROR.into())
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let mesh = Mesh::surface(result, settings);
+        let mut ply = String::from("ply\nformat ascii 1.0\ncomment Surface written by termplot\n");
+        if let Some(title) = &settings.title {
+            writeln!(ply, "comment {}", single_line(title))?;
+        }
+        writeln!(ply, "element vertex {}", mesh.vertices.len())?;
+        for property in ["x", "y", "z", "nx", "ny", "nz"] {
+            writeln!(ply, "property float {property}")?;
+        }
+        for property in ["red", "green", "blue"] {
+            writeln!(ply, "property uchar {property}")?;
+        }
+        writeln!(ply, "element face {}", mesh.triangles.len())?;
+        writeln!(ply, "property list uchar int vertex_indices")?;
+        writeln!(ply, "end_header")?;
+        for ((position, normal), color) in mesh.vertices.iter().zip(&mesh.normals).zip(&mesh.colors) {
+            let [x, y, z] = position.map(|component| component as f32);
+            let [nx, ny, nz] = normal.map(|component| component as f32);
+            let (r, g, b) = color.channels();
+            writeln!(ply, "{x} {y} {z} {nx} {ny} {nz} {r} {g} {b}")?;
+        }
+        for [a, b, c] in &mesh.triangles {
+            writeln!(ply, "3 {a} {b} {c}")?;
+        }
+        std::fs::write(filename, ply)?;
+        Ok(())
+    }
+}
+
+/// Text for a comment line, with line breaks turned into spaces
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
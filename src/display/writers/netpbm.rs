@@ -0,0 +1,275 @@
+use std::error::Error;
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{OutputWriter, Bitmap, PlotData, PlotSettings, SurfaceBounds};
+
+/// How a Netpbm file stores its samples: as decimal text (P1, P2, P3) or as raw
+/// bytes (P4, P5, P6), which is smaller and much faster to read and write
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NetpbmFormat {
+    #[default]
+    Ascii,
+    Binary,
+}
+
+/// Header and samples of a Netpbm image. `magic` is the digit of the ASCII variant;
+/// the binary one is three higher. Samples above 255 take two bytes, big endian.
+fn encode(magic: u8, format: NetpbmFormat, (width, height): (usize, usize), max_value: u16, samples: &[u16]) -> Vec<u8> {
+    let channels = samples.len() / (width * height).max(1);
+    match format {
+        NetpbmFormat::Ascii => {
+            let mut text = format!("P{magic}\n{width} {height}\n{max_value}\n");
+            for row in samples.chunks((width * channels).max(1)) {
+                let values: Vec<String> = row.iter().map(u16::to_string).collect();
+                text.push_str(&values.join(" "));
+                text.push('\n');
+            }
+            text.into_bytes()
+        }
+        NetpbmFormat::Binary => {
+            let mut bytes = format!("P{}\n{width} {height}\n{max_value}\n", magic + 3).into_bytes();
+            for &sample in samples {
+                match max_value {
+                    0..=255 => bytes.push(sample as u8),
+                    _ => bytes.extend_from_slice(&sample.to_be_bytes()),
+                }
+            }
+            bytes
+        }
+    }
+}
+
+/// Color image, P3 or P6
+#[derive(Clone, Debug)]
+pub struct PpmWriter {
+    pub format: NetpbmFormat,
+}
+
+impl PpmWriter {
+    pub fn new(format: NetpbmFormat) -> Self {
+        Self { format }
+    }
+
+    fn save(&self, bitmap: &Bitmap, filename: &str) -> Result<(), Box<dyn Error>> {
+        let samples: Vec<u16> = bitmap.to_rgb().into_iter().map(u16::from).collect();
+        std::fs::write(filename, encode(3, self.format, (bitmap.width, bitmap.height), 255, &samples))?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for PpmWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Curve { x: x_result, y: y_result }, settings, width, height);
+        self.save(&bitmap, filename)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Parametric(result), settings, width, height);
+        self.save(&bitmap, filename)
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Surface(result), settings, width, height);
+        self.save(&bitmap, filename)
+    }
+}
+
+/// Grayscale image, P2 or P5. Curves are drawn as in `PpmWriter` and converted to
+/// luma; surfaces are written as a 16-bit heightmap instead.
+#[derive(Clone, Debug)]
+pub struct PgmWriter {
+    pub format: NetpbmFormat,
+}
+
+impl PgmWriter {
+    pub fn new(format: NetpbmFormat) -> Self {
+        Self { format }
+    }
+
+    fn save(&self, bitmap: &Bitmap, filename: &str) -> Result<(), Box<dyn Error>> {
+        let samples: Vec<u16> = bitmap
+            .data
+            .iter()
+            .flatten()
+            .map(|color| ((299 * color.r as u32 + 587 * color.g as u32 + 114 * color.b as u32 + 500) / 1000) as u16)
+            .collect();
+        std::fs::write(filename, encode(2, self.format, (bitmap.width, bitmap.height), 255, &samples))?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for PgmWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Curve { x: x_result, y: y_result }, settings, width, height);
+        self.save(&bitmap, filename)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Parametric(result), settings, width, height);
+        self.save(&bitmap, filename)
+    }
+
+    /// One pixel per grid point, the highest Y at the top, with the height mapped
+    /// onto 0 to 65535 over the Z view. Undefined points are 0. The geometry is not
+    /// used: the grid decides the size.
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bounds = SurfaceBounds::for_surface(result, settings).ok_or("No finite 3D surface values to write")?;
+
+        let mut rows: Vec<usize> = (0..result.y_len()).collect();
+        rows.sort_by(|&a, &b| result.y_values[b].total_cmp(&result.y_values[a]));
+        let mut columns: Vec<usize> = (0..result.x_len()).collect();
+        columns.sort_by(|&a, &b| result.x_values[a].total_cmp(&result.x_values[b]));
+
+        let mut samples = Vec::with_capacity(rows.len() * columns.len());
+        for &y_idx in &rows {
+            for &x_idx in &columns {
+                let height = match result.get_z(x_idx, y_idx) {
+                    Some(z) if bounds.z_scale.accepts(z) => bounds.z_fraction(z).clamp(0.0, 1.0),
+                    _ => 0.0,
+                };
+                samples.push((height * u16::MAX as f64).round() as u16);
+            }
+        }
+        std::fs::write(filename, encode(2, self.format, (columns.len(), rows.len()), u16::MAX, &samples))?;
+        Ok(())
+    }
+}
+
+/// Black and white image, P1 or P4: everything drawn is black on white
+#[derive(Clone, Debug)]
+pub struct PbmWriter {
+    pub format: NetpbmFormat,
+}
+
+impl PbmWriter {
+    pub fn new(format: NetpbmFormat) -> Self {
+        Self { format }
+    }
+
+    /// As `Bitmap::render`, with hard edges: blended ones would only turn into
+    /// jagged black pixels
+    fn render(data: PlotData, settings: &PlotSettings, width: usize, height: usize) -> Bitmap {
+        let mut bitmap = Bitmap::new(width, height, settings.theme);
+        bitmap.antialias = false;
+        bitmap.plot(data, settings);
+        bitmap
+    }
+
+    fn save(&self, bitmap: &Bitmap, filename: &str) -> Result<(), Box<dyn Error>> {
+        let (width, height) = (bitmap.width, bitmap.height);
+        let ink = |x, y| !bitmap.is_background(x, y);
+        let bytes = match self.format {
+            NetpbmFormat::Ascii => {
+                let mut text = format!("P1\n{width} {height}\n");
+                for y in 0..height {
+                    let row: Vec<&str> = (0..width).map(|x| if ink(x, y) { "1" } else { "0" }).collect();
+                    text.push_str(&row.join(" "));
+                    text.push('\n');
+                }
+                text.into_bytes()
+            }
+            // Rows of eight pixels per byte, the leftmost in the highest bit
+            NetpbmFormat::Binary => {
+                let mut bytes = format!("P4\n{width} {height}\n").into_bytes();
+                for y in 0..height {
+                    for start in (0..width).step_by(8) {
+                        let byte = (start..(start + 8).min(width))
+                            .filter(|&x| ink(x, y))
+                            .fold(0u8, |byte, x| byte | 0x80 >> (x - start));
+                        bytes.push(byte);
+                    }
+                }
+                bytes
+            }
+        };
+        std::fs::write(filename, bytes)?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for PbmWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Self::render(PlotData::Curve { x: x_result, y: y_result }, settings, width, height);
+        self.save(&bitmap, filename)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Self::render(PlotData::Parametric(result), settings, width, height);
+        self.save(&bitmap, filename)
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Self::render(PlotData::Surface(result), settings, width, height);
+        self.save(&bitmap, filename)
+    }
+}
@@ -0,0 +1,91 @@
+use std::error::Error;
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{OutputWriter, PlotSettings};
+
+/// Writes the samples as a NumPy `.npy` array of little-endian float64, to load with
+/// `numpy.load`: curves as N×2 arrays of X and Y, surfaces as the Z grid with one
+/// row per Y value. Undefined values are NaN.
+#[derive(Clone, Debug)]
+pub struct NpyWriter;
+
+impl NpyWriter {
+    fn save(filename: &str, shape: &[usize], values: impl Iterator<Item = f64>) -> Result<(), Box<dyn Error>> {
+        let mut npy = npy_header(shape);
+        for value in values {
+            npy.extend_from_slice(&value.to_le_bytes());
+        }
+        std::fs::write(filename, npy)?;
+        Ok(())
+    }
+
+    fn save_pairs(filename: &str, x_values: &[f64], y_values: &[f64]) -> Result<(), Box<dyn Error>> {
+        let rows = x_values.len().min(y_values.len());
+        let values = x_values.iter().zip(y_values).flat_map(|(&x, &y)| [x, y]);
+        Self::save(filename, &[rows, 2], values)
+    }
+}
+
+impl OutputWriter for NpyWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        _width: usize,
+        _height: usize,
+        _settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        Self::save_pairs(filename, &x_result.0, &y_result.0)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        _width: usize,
+        _height: usize,
+        _settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        Self::save_pairs(filename, &result.x_values, &result.y_values)
+    }
+
+    /// `array[j, i]` is the height at the i-th X and the j-th Y value
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        _width: usize,
+        _height: usize,
+        _settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let (columns, rows) = (result.x_len(), result.y_len());
+        let values = (0..rows).flat_map(|y_idx| (0..columns).map(move |x_idx| result.get_z(x_idx, y_idx).unwrap_or(f64::NAN)));
+        Self::save(filename, &[rows, columns], values)
+    }
+}
+
+/// Magic string, version 1.0 and the header dictionary, padded with spaces so the
+/// data starts on a 64 byte boundary as NumPy expects
+fn npy_header(shape: &[usize]) -> Vec<u8> {
+    let dimensions: Vec<String> = shape.iter().map(usize::to_string).collect();
+    let shape = match dimensions.as_slice() {
+        [single] => format!("({single},)"),
+        _ => format!("({})", dimensions.join(", ")),
+    };
+    let mut dictionary = format!("{{'descr': '<f8', 'fortran_order': False, 'shape': {shape}, }}");
+    // Ten bytes come before the dictionary and a line break ends it
+    while (10 + dictionary.len() + 1) % 64 != 0 {
+        dictionary.push(' ');
+    }
+    dictionary.push('\n');
+
+    let mut header = b"\x93NUMPY\x01\x00".to_vec();
+    header.extend_from_slice(&(dictionary.len() as u16).to_le_bytes());
+    header.extend_from_slice(dictionary.as_bytes());
+    header
+}
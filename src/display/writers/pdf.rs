@@ -0,0 +1,212 @@
+use std::{error::Error, fmt::Write};
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{
+    deflate,
+    figure::{coordinate, ps_color, ps_string, Element, Figure, TextKind},
+    OutputWriter, PlotSettings,
+};
+
+/// Advance widths of the printable ASCII characters in Helvetica, in thousandths
+/// of the font size, from the font's standard metrics
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // space to /
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, // 0 to ?
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, // @ to O
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, // P to _
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, // ` to o
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584, // p to ~
+];
+
+/// Writes plots as single page PDF 1.4 files with the layout of the SVG output, one
+/// point per pixel. Labels use the standard Helvetica and Courier fonts, which every
+/// reader has, so nothing is embedded.
+#[derive(Clone, Debug)]
+pub struct PdfWriter {
+    /// Deflate the page contents, which makes long series several times smaller
+    pub compress: bool,
+}
+
+impl PdfWriter {
+    pub fn new(compress: bool) -> Self {
+        Self { compress }
+    }
+
+    fn save(&self, figure: &Figure, filename: &str) -> Result<(), Box<dyn Error>> {
+        let content = page_content(figure)?;
+        let stream = match self.compress {
+            true => {
+                let data = deflate::zlib(content.as_bytes());
+                [format!("<< /Length {} /Filter /FlateDecode >>\nstream\n", data.len()).into_bytes(), data].concat()
+            }
+            false => format!("<< /Length {} >>\nstream\n{content}", content.len()).into_bytes(),
+        };
+
+        let (width, height) = (figure.width, figure.height);
+        let objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {width} {height}] \
+                 /Resources << /Font << /F1 5 0 R /F2 6 0 R >> >> /Contents 4 0 R >>"
+            )
+            .into_bytes(),
+            [stream, b"\nendstream".to_vec()].concat(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+            format!(
+                "<< /Title {} /Subject {} /Producer (termplot) >>",
+                ps_string(&figure.title),
+                ps_string(&figure.description)
+            )
+            .into_bytes(),
+        ];
+
+        // The binary comment tells transfer tools the file is not text
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+        // Cross reference entries are exactly 20 bytes, with a two byte end of line
+        let xref = pdf.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            writeln!(table, "{offset:010} 00000 n ")?;
+        }
+        write!(
+            table,
+            "trailer\n<< /Size {} /Root 1 0 R /Info {} 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1,
+            objects.len()
+        )?;
+        pdf.extend_from_slice(table.as_bytes());
+
+        std::fs::write(filename, pdf)?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for PdfWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(&Figure::plot(x_result, y_result, width, height, settings), filename)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(&Figure::parametric(result, width, height, settings), filename)
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(&Figure::surface(result, width, height, settings), filename)
+    }
+}
+
+/// The drawing operators of the page. PDF puts the origin at the bottom left, so
+/// every Y of the figure is turned upside down.
+fn page_content(figure: &Figure) -> Result<String, Box<dyn Error>> {
+    let (width, height) = (figure.width as f64, figure.height as f64);
+    let point = |(x, y): (f64, f64)| format!("{} {}", coordinate(x), coordinate(height - y));
+
+    let mut content = String::new();
+    writeln!(content, "{} rg 0 0 {} {} re f", ps_color(figure.theme.background), coordinate(width), coordinate(height))?;
+    for element in &figure.elements {
+        match element {
+            Element::Path(kind, polylines) => {
+                let style = figure.style(*kind);
+                let dash: Vec<String> = style.dash.iter().map(|&length| coordinate(length)).collect();
+                let round = style.round as u8;
+                writeln!(
+                    content,
+                    "q {} RG {} w {round} J {round} j [{}] 0 d",
+                    ps_color(style.color),
+                    coordinate(style.width),
+                    dash.join(" ")
+                )?;
+                for polyline in polylines {
+                    let Some((&first, rest)) = polyline.split_first() else { continue };
+                    write!(content, "{} m", point(first))?;
+                    // A round capped line of no length is a dot
+                    for &next in if rest.is_empty() { polyline.as_slice() } else { rest } {
+                        write!(content, " {} l", point(next))?;
+                    }
+                    content.push('\n');
+                }
+                writeln!(content, "S Q")?;
+            }
+            Element::Surface(polygons) => {
+                writeln!(content, "q 0.5 w 1 j")?;
+                for (points, color) in polygons {
+                    let Some((&first, rest)) = points.split_first() else { continue };
+                    let color = ps_color(*color);
+                    write!(content, "{color} rg {color} RG {} m", point(first))?;
+                    for &next in rest {
+                        write!(content, " {} l", point(next))?;
+                    }
+                    writeln!(content, " h B")?;
+                }
+                writeln!(content, "Q")?;
+            }
+            Element::Text { kind, position, anchor, vertical, text } => {
+                let size = kind.size();
+                let shift = text_width(text, *kind) * anchor.fraction();
+                let (x, y) = (position.0, height - position.1);
+                let matrix = match vertical {
+                    true => format!("0 1 -1 0 {} {}", coordinate(x), coordinate(y - shift)),
+                    false => format!("1 0 0 1 {} {}", coordinate(x - shift), coordinate(y)),
+                };
+                let font = if kind.monospace() { "F2" } else { "F1" };
+                writeln!(
+                    content,
+                    "BT /{font} {} Tf {} rg {matrix} Tm {} Tj ET",
+                    coordinate(size),
+                    ps_color(figure.theme.text),
+                    ps_string(text)
+                )?;
+            }
+        }
+    }
+    Ok(content)
+}
+
+/// Width of a text in its font, in points. Characters outside ASCII get the
+/// width of a digit.
+fn text_width(text: &str, kind: TextKind) -> f64 {
+    let thousandths: u32 = text
+        .chars()
+        .map(|ch| match (kind.monospace(), ch) {
+            (true, _) => 600,
+            (false, ' '..='~') => HELVETICA_WIDTHS[ch as usize - ' ' as usize] as u32,
+            (false, _) => 556,
+        })
+        .sum();
+    thousandths as f64 * kind.size() / 1000.0
+}
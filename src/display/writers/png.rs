@@ -0,0 +1,58 @@
+use std::error::Error;
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{png, OutputWriter, Bitmap, PlotData, PlotSettings};
+
+/// Writes the bitmap plot as a compressed PNG file, the same image as `ppm=`
+#[derive(Clone, Debug)]
+pub struct PngWriter;
+
+impl PngWriter {
+    fn save(bitmap: &Bitmap, filename: &str) -> Result<(), Box<dyn Error>> {
+        std::fs::write(filename, png::encode_rgb(bitmap.width, bitmap.height, &bitmap.to_rgb()))?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for PngWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Curve { x: x_result, y: y_result }, settings, width, height);
+        Self::save(&bitmap, filename)
+    }
+
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Parametric(result), settings, width, height);
+        Self::save(&bitmap, filename)
+    }
+
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        width: usize,
+        height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bitmap = Bitmap::render(PlotData::Surface(result), settings, width, height);
+        Self::save(&bitmap, filename)
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     parametric2d::Parametric2DResult,
 };
 
-use super::super::{OutputWriter, RegisRenderer, DisplayRenderer};
+use super::super::{OutputWriter, RegisRenderer, DisplayRenderer, PlotSettings};
 
 #[derive(Clone, Debug)]
 pub struct RegisWriter;
@@ -18,9 +18,10 @@ impl OutputWriter for RegisWriter {
         y_result: &ExpressionRange1dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
         let mut file = std::fs::File::create(filename)?;
-        let regis_output = RegisRenderer.render(y_result, width, height, x_result);
+        let regis_output = RegisRenderer.render(y_result, width, height, x_result, settings);
         write!(file, "{regis_output}")?;
         Ok(())
     }
@@ -31,9 +32,10 @@ impl OutputWriter for RegisWriter {
         result: &Parametric2DResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
         let mut file = std::fs::File::create(filename)?;
-        let regis_output = RegisRenderer.render_parametric(result, width, height);
+        let regis_output = RegisRenderer.render_parametric(result, width, height, settings);
         write!(file, "{regis_output}")?;
         Ok(())
     }
@@ -44,9 +46,10 @@ impl OutputWriter for RegisWriter {
         result: &Expression3dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
         let mut file = std::fs::File::create(filename)?;
-        let regis_output = RegisRenderer.render_surface3d(result, width, height);
+        let regis_output = RegisRenderer.render_surface3d(result, width, height, settings);
         write!(file, "{regis_output}")?;
         Ok(())
     }
@@ -0,0 +1,400 @@
+use std::{error::Error, fmt::Write};
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::super::{AxisScale, OutputWriter, PlotBounds, PlotSettings, PlotStyle, Segments, SurfaceBounds};
+
+/// The pieces of a curve to draw without joining them, split where it jumps or
+/// leaves the window as the other outputs split it
+fn curve_pieces(points: &[(f64, f64)], segments: Option<Segments>) -> Vec<Vec<(f64, f64)>> {
+    match segments {
+        Some(segments) => segments.pieces,
+        None => vec![points.to_vec()],
+    }
+}
+
+/// A data value as both gnuplot and Python read it, `None` when it is not finite.
+/// Very large and very small values get an exponent instead of dozens of digits.
+fn script_number(value: f64) -> Option<String> {
+    match value.abs() {
+        magnitude if !magnitude.is_finite() => None,
+        magnitude if magnitude == 0.0 || (1e-4..1e15).contains(&magnitude) => Some(format!("{value}")),
+        _ => Some(format!("{value:e}")),
+    }
+}
+
+/// Writes a gnuplot script with the data in inline `$DATA` blocks, so it runs with
+/// `gnuplot -p plot.gp` and can be restyled there
+#[derive(Clone, Debug)]
+pub struct GnuplotWriter;
+
+impl GnuplotWriter {
+    /// `set` commands for the title and the labels of the axes
+    fn write_labels(script: &mut String, settings: &PlotSettings, include_z: bool) -> Result<(), Box<dyn Error>> {
+        if let Some(title) = &settings.title {
+            writeln!(script, "set title {} noenhanced", gnuplot_string(title))?;
+        }
+        let mut labels = vec![("x", &settings.xlabel), ("y", &settings.ylabel)];
+        if include_z {
+            labels.push(("z", &settings.zlabel));
+        }
+        for (axis, label) in labels {
+            if let Some(label) = label {
+                writeln!(script, "set {axis}label {} noenhanced", gnuplot_string(label))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `set` commands for the window and the log axes
+    fn write_ranges(script: &mut String, ranges: &[(&str, f64, f64, AxisScale)]) -> Result<(), Box<dyn Error>> {
+        for &(axis, min, max, scale) in ranges {
+            if let (Some(min), Some(max)) = (script_number(min), script_number(max)) {
+                writeln!(script, "set {axis}range [{min}:{max}]")?;
+            }
+            if scale == AxisScale::Log {
+                writeln!(script, "set logscale {axis}")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A data block of `x y` rows, with a blank line between pieces so gnuplot does
+    /// not join them
+    fn write_curve_data(script: &mut String, pieces: &[Vec<(f64, f64)>]) -> Result<(), Box<dyn Error>> {
+        writeln!(script, "$DATA << EOD")?;
+        for (index, piece) in pieces.iter().enumerate() {
+            if index > 0 {
+                writeln!(script)?;
+            }
+            for &(x, y) in piece {
+                if let (Some(x), Some(y)) = (script_number(x), script_number(y)) {
+                    writeln!(script, "{x} {y}")?;
+                }
+            }
+        }
+        writeln!(script, "EOD")?;
+        Ok(())
+    }
+
+    fn plot_style(settings: &PlotSettings) -> &'static str {
+        match settings.style {
+            PlotStyle::Lines => "lines",
+            PlotStyle::Points => "points pointtype 7 pointsize 0.5",
+            PlotStyle::Both => "linespoints pointtype 7 pointsize 0.5",
+        }
+    }
+
+    fn save(filename: &str, script: String) -> Result<(), Box<dyn Error>> {
+        std::fs::write(filename, script)?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for GnuplotWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let points: Vec<(f64, f64)> = x_result.0.iter().copied().zip(y_result.0.iter().copied()).collect();
+        let bounds = PlotBounds::for_plot(&x_result.0, &y_result.0, settings);
+        let segments = bounds.map(|bounds| Segments::split(&points, &bounds));
+
+        let mut script = String::from("# Function plot written by termplot\n");
+        if let (Some(bounds), Some(segments)) = (&bounds, &segments) {
+            if settings.asymptotes {
+                for x in segments.asymptotes.iter().filter_map(|&x| script_number(x)) {
+                    writeln!(
+                        script,
+                        "set arrow from {x}, graph 0 to {x}, graph 1 nohead dashtype 2 linecolor \"gray\""
+                    )?;
+                }
+            }
+            Self::write_ranges(
+                &mut script,
+                &[("x", bounds.x_min, bounds.x_max, bounds.x_scale), ("y", bounds.y_min, bounds.y_max, bounds.y_scale)],
+            )?;
+        }
+        Self::write_labels(&mut script, settings, false)?;
+        writeln!(script, "set grid")?;
+        Self::write_curve_data(&mut script, &curve_pieces(&points, segments))?;
+        writeln!(script, "plot $DATA using 1:2 with {} notitle", Self::plot_style(settings))?;
+        Self::save(filename, script)
+    }
+
+    /// Both axes get the same unit, so circles stay round
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let points: Vec<(f64, f64)> = result.x_values.iter().copied().zip(result.y_values.iter().copied()).collect();
+        let bounds = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings);
+        let segments = bounds.map(|bounds| Segments::split_curve(&points, &bounds));
+
+        let mut script = String::from("# Parametric curve written by termplot\n");
+        if let Some(bounds) = &bounds {
+            Self::write_ranges(
+                &mut script,
+                &[("x", bounds.x_min, bounds.x_max, bounds.x_scale), ("y", bounds.y_min, bounds.y_max, bounds.y_scale)],
+            )?;
+        }
+        if (settings.x_scale, settings.y_scale) == (AxisScale::Linear, AxisScale::Linear) {
+            writeln!(script, "set size ratio -1")?;
+        }
+        Self::write_labels(&mut script, settings, false)?;
+        writeln!(script, "set grid")?;
+        Self::write_curve_data(&mut script, &curve_pieces(&points, segments))?;
+        writeln!(script, "plot $DATA using 1:2 with {} notitle", Self::plot_style(settings))?;
+        Self::save(filename, script)
+    }
+
+    /// The grid as one scan per Y value, separated by blank lines, drawn with
+    /// `pm3d`. Undefined points are `NaN`, which leaves holes.
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut script = String::from("# Surface written by termplot\n");
+        if let Some(bounds) = SurfaceBounds::for_surface(result, settings) {
+            Self::write_ranges(
+                &mut script,
+                &[
+                    ("x", bounds.x_min, bounds.x_max, bounds.x_scale),
+                    ("y", bounds.y_min, bounds.y_max, bounds.y_scale),
+                    ("z", bounds.z_min, bounds.z_max, bounds.z_scale),
+                ],
+            )?;
+        }
+        Self::write_labels(&mut script, settings, true)?;
+        writeln!(script, "set grid")?;
+
+        writeln!(script, "$DATA << EOD")?;
+        for (y_idx, &y) in result.y_values.iter().enumerate() {
+            if y_idx > 0 {
+                writeln!(script)?;
+            }
+            for (x_idx, &x) in result.x_values.iter().enumerate() {
+                let z = result.get_z(x_idx, y_idx).and_then(script_number).unwrap_or_else(|| "NaN".to_string());
+                if let (Some(x), Some(y)) = (script_number(x), script_number(y)) {
+                    writeln!(script, "{x} {y} {z}")?;
+                }
+            }
+        }
+        writeln!(script, "EOD")?;
+        writeln!(script, "splot $DATA using 1:2:3 with pm3d notitle")?;
+        Self::save(filename, script)
+    }
+}
+
+/// Double quoted gnuplot string
+fn gnuplot_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Writes a Python script drawing the plot with Matplotlib, the data inlined as
+/// NumPy arrays
+#[derive(Clone, Debug)]
+pub struct MatplotlibWriter;
+
+impl MatplotlibWriter {
+    fn header(kind: &str) -> String {
+        format!(
+            "#!/usr/bin/env python3\n# {kind} written by termplot\nimport matplotlib.pyplot as plt\nimport numpy as np\n\n"
+        )
+    }
+
+    /// Python list of values, with `np.nan` for the ones that are not finite
+    fn list(values: impl IntoIterator<Item = f64>) -> String {
+        let values: Vec<String> =
+            values.into_iter().map(|value| script_number(value).unwrap_or_else(|| "np.nan".to_string())).collect();
+        format!("[{}]", values.join(", "))
+    }
+
+    fn array(values: impl IntoIterator<Item = f64>) -> String {
+        format!("np.array({})", Self::list(values))
+    }
+
+    /// The curve as `x` and `y` arrays, with a `nan` between pieces so Matplotlib
+    /// breaks the line there
+    fn write_curve_data(script: &mut String, pieces: &[Vec<(f64, f64)>]) -> Result<(), Box<dyn Error>> {
+        let mut points = Vec::new();
+        for (index, piece) in pieces.iter().enumerate() {
+            if index > 0 {
+                points.push((f64::NAN, f64::NAN));
+            }
+            points.extend_from_slice(piece);
+        }
+        writeln!(script, "x = {}", Self::array(points.iter().map(|point| point.0)))?;
+        writeln!(script, "y = {}", Self::array(points.iter().map(|point| point.1)))?;
+        writeln!(script)?;
+        Ok(())
+    }
+
+    fn write_curve_plot(script: &mut String, settings: &PlotSettings) -> Result<(), Box<dyn Error>> {
+        let format = match settings.style {
+            PlotStyle::Lines => "-",
+            PlotStyle::Points => "o",
+            PlotStyle::Both => "-o",
+        };
+        writeln!(script, "fig, ax = plt.subplots()")?;
+        writeln!(script, "ax.plot(x, y, \"{format}\", markersize=3)")?;
+        Ok(())
+    }
+
+    /// Titles, labels, limits and log axes, each given as (axis, min, max, scale)
+    fn write_axes(
+        script: &mut String,
+        settings: &PlotSettings,
+        ranges: &[(&str, f64, f64, AxisScale)],
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(title) = &settings.title {
+            writeln!(script, "ax.set_title({})", python_string(title))?;
+        }
+        let labels = [("x", &settings.xlabel), ("y", &settings.ylabel), ("z", &settings.zlabel)];
+        for &(axis, min, max, scale) in ranges {
+            if let Some((_, Some(label))) = labels.iter().find(|(name, _)| *name == axis) {
+                writeln!(script, "ax.set_{axis}label({})", python_string(label))?;
+            }
+            if let (Some(min), Some(max)) = (script_number(min), script_number(max)) {
+                writeln!(script, "ax.set_{axis}lim({min}, {max})")?;
+            }
+            if scale == AxisScale::Log {
+                writeln!(script, "ax.set_{axis}scale(\"log\")")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn save(filename: &str, mut script: String) -> Result<(), Box<dyn Error>> {
+        script.push_str("plt.show()\n");
+        std::fs::write(filename, script)?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for MatplotlibWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let points: Vec<(f64, f64)> = x_result.0.iter().copied().zip(y_result.0.iter().copied()).collect();
+        let bounds = PlotBounds::for_plot(&x_result.0, &y_result.0, settings);
+        let segments = bounds.map(|bounds| Segments::split(&points, &bounds));
+        let asymptotes = match (&segments, settings.asymptotes) {
+            (Some(segments), true) => segments.asymptotes.clone(),
+            _ => Vec::new(),
+        };
+
+        let mut script = Self::header("Function plot");
+        Self::write_curve_data(&mut script, &curve_pieces(&points, segments))?;
+        Self::write_curve_plot(&mut script, settings)?;
+        for x in asymptotes.into_iter().filter_map(script_number) {
+            writeln!(script, "ax.axvline({x}, color=\"gray\", linestyle=\"--\", linewidth=1)")?;
+        }
+        let ranges = match bounds {
+            Some(bounds) => vec![
+                ("x", bounds.x_min, bounds.x_max, bounds.x_scale),
+                ("y", bounds.y_min, bounds.y_max, bounds.y_scale),
+            ],
+            None => vec![("x", f64::NAN, f64::NAN, settings.x_scale), ("y", f64::NAN, f64::NAN, settings.y_scale)],
+        };
+        Self::write_axes(&mut script, settings, &ranges)?;
+        writeln!(script, "ax.grid(True)")?;
+        Self::save(filename, script)
+    }
+
+    /// Both axes get the same unit, so circles stay round
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let points: Vec<(f64, f64)> = result.x_values.iter().copied().zip(result.y_values.iter().copied()).collect();
+        let bounds = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings);
+        let segments = bounds.map(|bounds| Segments::split_curve(&points, &bounds));
+
+        let mut script = Self::header("Parametric curve");
+        Self::write_curve_data(&mut script, &curve_pieces(&points, segments))?;
+        Self::write_curve_plot(&mut script, settings)?;
+        let ranges = match bounds {
+            Some(bounds) => vec![
+                ("x", bounds.x_min, bounds.x_max, bounds.x_scale),
+                ("y", bounds.y_min, bounds.y_max, bounds.y_scale),
+            ],
+            None => vec![("x", f64::NAN, f64::NAN, settings.x_scale), ("y", f64::NAN, f64::NAN, settings.y_scale)],
+        };
+        Self::write_axes(&mut script, settings, &ranges)?;
+        if (settings.x_scale, settings.y_scale) == (AxisScale::Linear, AxisScale::Linear) {
+            writeln!(script, "ax.set_aspect(\"equal\")")?;
+        }
+        writeln!(script, "ax.grid(True)")?;
+        Self::save(filename, script)
+    }
+
+    /// `Z` has one row per Y value, with `nan` for undefined points, which
+    /// `plot_surface` leaves out
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut script = Self::header("Surface");
+        writeln!(script, "x = {}", Self::array(result.x_values.iter().copied()))?;
+        writeln!(script, "y = {}", Self::array(result.y_values.iter().copied()))?;
+        writeln!(script, "Z = np.array([")?;
+        for y_idx in 0..result.y_len() {
+            let row = (0..result.x_len()).map(|x_idx| result.get_z(x_idx, y_idx).unwrap_or(f64::NAN));
+            writeln!(script, "    {},", Self::list(row))?;
+        }
+        writeln!(script, "])")?;
+        writeln!(script, "X, Y = np.meshgrid(x, y)")?;
+        writeln!(script)?;
+        writeln!(script, "fig = plt.figure()")?;
+        writeln!(script, "ax = fig.add_subplot(projection=\"3d\")")?;
+        writeln!(script, "ax.plot_surface(X, Y, Z, cmap=\"viridis\")")?;
+        // Matplotlib has no log scale for 3D axes, so limits are all that carry over
+        if let Some(bounds) = SurfaceBounds::for_surface(result, settings) {
+            let ranges = [
+                ("x", bounds.x_min, bounds.x_max, AxisScale::Linear),
+                ("y", bounds.y_min, bounds.y_max, AxisScale::Linear),
+                ("z", bounds.z_min, bounds.z_max, AxisScale::Linear),
+            ];
+            Self::write_axes(&mut script, settings, &ranges)?;
+        }
+        Self::save(filename, script)
+    }
+}
+
+/// Double quoted Python string. Dollar signs are escaped so Matplotlib does not
+/// read the text as math.
+fn python_string(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"").replace('$', "\\\\$");
+    format!("\"{escaped}\"")
+}
@@ -5,7 +5,7 @@ use crate::{
     parametric2d::Parametric2DResult,
 };
 
-use super::super::{OutputWriter, SixelRenderer, DisplayRenderer};
+use super::super::{OutputWriter, SixelRenderer, DisplayRenderer, PlotSettings};
 
 #[derive(Clone, Debug)]
 pub struct SixelWriter;
@@ -18,9 +18,10 @@ impl OutputWriter for SixelWriter {
         y_result: &ExpressionRange1dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
         let mut file = std::fs::File::create(filename)?;
-        let sixel_output = SixelRenderer.render(y_result, width, height, x_result);
+        let sixel_output = SixelRenderer::default().render(y_result, width, height, x_result, settings);
         write!(file, "{sixel_output}")?;
         Ok(())
     }
@@ -31,9 +32,10 @@ impl OutputWriter for SixelWriter {
         result: &Parametric2DResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
         let mut file = std::fs::File::create(filename)?;
-        let sixel_output = SixelRenderer.render_parametric(result, width, height);
+        let sixel_output = SixelRenderer::default().render_parametric(result, width, height, settings);
         write!(file, "{sixel_output}")?;
         Ok(())
     }
@@ -44,9 +46,10 @@ impl OutputWriter for SixelWriter {
         result: &Expression3dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
         let mut file = std::fs::File::create(filename)?;
-        let sixel_output = SixelRenderer.render_surface3d(result, width, height);
+        let sixel_output = SixelRenderer::default().render_surface3d(result, width, height, settings);
         write!(file, "{sixel_output}")?;
         Ok(())
     }
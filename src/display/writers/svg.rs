@@ -1,15 +1,29 @@
-use std::{error::Error, io::Write};
+use std::{error::Error, fmt::Write};
 
 use crate::{
     values::{ExpressionRange1dResult, Expression3dResult},
     parametric2d::Parametric2DResult,
 };
 
-use super::super::{OutputWriter, Point3D, SurfaceBounds};
+use super::super::{
+    figure::{coordinate, Element, Figure, PathKind, TextKind},
+    OutputWriter, PlotSettings,
+};
 
+/// Writes plots as standalone SVG documents. Every kind of element has a CSS class
+/// (`grid`, `axis`, `series`, `surface`, ...) styled in one `<style>` sheet with the
+/// theme colors, so a plot can be restyled without touching its geometry, and the
+/// `viewBox` lets it scale to any size.
 #[derive(Clone, Debug)]
 pub struct SvgWriter;
 
+impl SvgWriter {
+    fn save(&self, figure: &Figure, filename: &str) -> Result<(), Box<dyn Error>> {
+        std::fs::write(filename, svg_document(figure)?)?;
+        Ok(())
+    }
+}
+
 impl OutputWriter for SvgWriter {
     fn write(
         &self,
@@ -18,128 +32,9 @@ impl OutputWriter for SvgWriter {
         y_result: &ExpressionRange1dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
-        let mut file = std::fs::File::create(filename)?;
-        let margin = 50;
-        let plot_width = width - 2 * margin;
-        let plot_height = height - 2 * margin;
-
-        writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
-        writeln!(
-            file,
-            r#"<svg width="{width}" height="{height}" xmlns="http://www.w3.org/2000/svg">"#
-        )?;
-        writeln!(
-            file,
-            r#"<rect width="{width}" height="{height}" fill="black"/>"#
-        )?;
-
-        if !y_result.0.is_empty() {
-            let min_val = y_result.min();
-            let max_val = y_result.max();
-            let x_min = x_result.min();
-            let x_max = x_result.max();
-            let y_range = max_val - min_val;
-            let x_range_val = x_max - x_min;
-
-            // Draw grid
-            for i in 1..10 {
-                let x = margin + (i * plot_width) / 10;
-                writeln!(
-                    file,
-                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="rgb(64,64,64)" stroke-width="1"/>"#,
-                    x,
-                    margin,
-                    x,
-                    margin + plot_height
-                )?;
-            }
-            for i in 1..8 {
-                let y = margin + (i * plot_height) / 8;
-                writeln!(
-                    file,
-                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="rgb(64,64,64)" stroke-width="1"/>"#,
-                    margin,
-                    y,
-                    margin + plot_width,
-                    y
-                )?;
-            }
-
-            // Draw axes
-            writeln!(
-                file,
-                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="yellow" stroke-width="2"/>"#,
-                margin,
-                margin + plot_height,
-                margin + plot_width,
-                margin + plot_height
-            )?;
-            writeln!(
-                file,
-                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="yellow" stroke-width="2"/>"#,
-                margin,
-                margin,
-                margin,
-                margin + plot_height
-            )?;
-
-            // Y-axis labels
-            for i in 0..=5 {
-                let y = margin + (i * plot_height) / 5;
-                let value = max_val - (i as f64 / 5.0) * (max_val - min_val);
-                writeln!(
-                    file,
-                    r#"<text x="{}" y="{}" fill="rgb(192,192,192)" font-family="monospace" font-size="10" text-anchor="end">{:.1}</text>"#,
-                    margin - 5,
-                    y + 3,
-                    value
-                )?;
-            }
-
-            // X-axis labels
-            for i in 0..=5 {
-                let x = margin + (i * plot_width) / 5;
-                let value = x_min + (i as f64 / 5.0) * (x_max - x_min);
-                writeln!(
-                    file,
-                    r#"<text x="{}" y="{}" fill="rgb(192,192,192)" font-family="monospace" font-size="10" text-anchor="middle">{:.1}</text>"#,
-                    x,
-                    margin + plot_height + 15,
-                    value
-                )?;
-            }
-
-            // Plot data
-            if y_range > f64::EPSILON && x_range_val > f64::EPSILON {
-                let mut path_data = String::new();
-                for (i, (&x_val, &y_val)) in x_result.0.iter().zip(y_result.0.iter()).enumerate() {
-                    let x_svg =
-                        margin + ((x_val - x_min) / x_range_val * plot_width as f64) as usize;
-                    let y_svg =
-                        margin + (((max_val - y_val) / y_range) * plot_height as f64) as usize;
-
-                    if i == 0 {
-                        path_data.push_str(&format!("M {x_svg} {y_svg}"));
-                    } else {
-                        path_data.push_str(&format!(" L {x_svg} {y_svg}"));
-                    }
-
-                    // Draw data points
-                    writeln!(
-                        file,
-                        r#"<circle cx="{x_svg}" cy="{y_svg}" r="2" fill="cyan"/>"#
-                    )?;
-                }
-                writeln!(
-                    file,
-                    r#"<path d="{path_data}" fill="none" stroke="cyan" stroke-width="2"/>"#
-                )?;
-            }
-        }
-
-        writeln!(file, "</svg>")?;
-        Ok(())
+        self.save(&Figure::plot(x_result, y_result, width, height, settings), filename)
     }
 
     fn write_parametric(
@@ -148,57 +43,9 @@ impl OutputWriter for SvgWriter {
         result: &Parametric2DResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
-        let mut file = std::fs::File::create(filename)?;
-        let margin = 50;
-        let plot_width = width - 2 * margin;
-        let plot_height = height - 2 * margin;
-
-        writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
-        writeln!(
-            file,
-            r#"<svg width="{width}" height="{height}" xmlns="http://www.w3.org/2000/svg">"#
-        )?;
-        writeln!(
-            file,
-            r#"<rect width="{width}" height="{height}" fill="black"/>"#
-        )?;
-
-        if !result.is_empty() {
-            let x_min = result.x_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-            let x_max = result.x_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-            let y_min = result.y_values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-            let y_max = result.y_values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-            let x_range = x_max - x_min;
-            let y_range = y_max - y_min;
-
-            // Plot parametric data
-            if x_range > f64::EPSILON && y_range > f64::EPSILON {
-                let mut path_data = String::new();
-                for (i, (&x_val, &y_val)) in result.x_values.iter().zip(result.y_values.iter()).enumerate() {
-                    let x_svg = margin + ((x_val - x_min) / x_range * plot_width as f64) as usize;
-                    let y_svg = margin + (((y_max - y_val) / y_range) * plot_height as f64) as usize;
-
-                    if i == 0 {
-                        path_data.push_str(&format!("M {x_svg} {y_svg}"));
-                    } else {
-                        path_data.push_str(&format!(" L {x_svg} {y_svg}"));
-                    }
-
-                    writeln!(
-                        file,
-                        r#"<circle cx="{x_svg}" cy="{y_svg}" r="2" fill="cyan"/>"#
-                    )?;
-                }
-                writeln!(
-                    file,
-                    r#"<path d="{path_data}" fill="none" stroke="cyan" stroke-width="2"/>"#
-                )?;
-            }
-        }
-
-        writeln!(file, "</svg>")?;
-        Ok(())
+        self.save(&Figure::parametric(result, width, height, settings), filename)
     }
 
     fn write_surface3d(
@@ -207,68 +54,119 @@ impl OutputWriter for SvgWriter {
         result: &Expression3dResult,
         width: usize,
         height: usize,
+        settings: &PlotSettings,
     ) -> Result<(), Box<dyn Error>> {
-        let mut file = std::fs::File::create(filename)?;
-        let margin = 50;
-        let plot_width = width - 2 * margin;
-        let plot_height = height - 2 * margin;
-
-        writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
-        writeln!(
-            file,
-            r#"<svg width="{width}" height="{height}" xmlns="http://www.w3.org/2000/svg">"#
-        )?;
-        writeln!(
-            file,
-            r#"<rect width="{width}" height="{height}" fill="black"/>"#
-        )?;
-
-        if !result.is_empty() {
-            let x_min = result.x_min();
-            let x_max = result.x_max();
-            let y_min = result.y_min();
-            let y_max = result.y_max();
-            let z_min = result.z_min();
-            let z_max = result.z_max();
-            let x_range = x_max - x_min;
-            let y_range = y_max - y_min;
-            let z_range = z_max - z_min;
-
-            // Create isometric 3D projection using proper 3D coordinates
-            if x_range > f64::EPSILON && y_range > f64::EPSILON {
-                let bounds = SurfaceBounds::from_surface(result);
-                
-                for (y_idx, &y_val) in result.y_values.iter().enumerate() {
-                    for (x_idx, &x_val) in result.x_values.iter().enumerate() {
-                        if let Some(z_val) = result.get_z(x_idx, y_idx) {
-                            // Create 3D point and project to isometric coordinates
-                            let point_3d = Point3D::new(x_val, y_val, z_val);
-                            let (iso_x, iso_y) = point_3d.to_isometric(plot_width, plot_height, &bounds);
-                            
-                            // Apply margin offset
-                            let x_svg = margin + iso_x;
-                            let y_svg = margin + iso_y;
-                            
-                            // Color intensity based on z value
-                            let intensity = if z_range > f64::EPSILON {
-                                ((z_val - z_min) / z_range * 255.0) as u8
-                            } else {
-                                128
-                            };
-                            
-                            writeln!(
-                                file,
-                                r#"<rect x="{}" y="{}" width="2" height="2" fill="rgb({},{},{})" opacity="0.8"/>"#,
-                                x_svg.saturating_sub(1), y_svg.saturating_sub(1),
-                                intensity, intensity, 255
-                            )?;
-                        }
+        self.save(&Figure::surface(result, width, height, settings), filename)
+    }
+}
+
+fn svg_document(figure: &Figure) -> Result<String, Box<dyn Error>> {
+    Ok(format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}", svg_element(figure)?))
+}
+
+/// The `<svg>` element on its own, as it is also inlined into HTML
+pub fn svg_element(figure: &Figure) -> Result<String, Box<dyn Error>> {
+    let (width, height) = (figure.width, figure.height);
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" role="img" aria-labelledby="plot-title plot-desc">"#
+    )?;
+    writeln!(svg, r#"<title id="plot-title">{}</title>"#, svg_escape(&figure.title))?;
+    writeln!(svg, r#"<desc id="plot-desc">{}</desc>"#, svg_escape(&figure.description))?;
+    write_style_sheet(&mut svg, figure)?;
+    writeln!(svg, r#"<rect class="background" width="100%" height="100%"/>"#)?;
+
+    for element in &figure.elements {
+        match element {
+            Element::Path(kind, polylines) => {
+                let mut path = String::new();
+                for polyline in polylines {
+                    for (index, &(x, y)) in polyline.iter().enumerate() {
+                        let command = if index == 0 { 'M' } else { 'L' };
+                        write!(path, "{command}{},{}", coordinate(x), coordinate(y))?;
+                    }
+                    // Round caps turn a zero length line into a dot
+                    if polyline.len() == 1 {
+                        path.push_str("h0");
                     }
                 }
+                writeln!(svg, r#"<path class="{}" d="{path}"/>"#, kind.class())?;
+            }
+            Element::Surface(polygons) => {
+                writeln!(svg, r#"<g class="surface">"#)?;
+                for (points, color) in polygons {
+                    let points: Vec<String> =
+                        points.iter().map(|&(x, y)| format!("{},{}", coordinate(x), coordinate(y))).collect();
+                    let color = color.css();
+                    writeln!(svg, r#"<polygon points="{}" fill="{color}" stroke="{color}"/>"#, points.join(" "))?;
+                }
+                writeln!(svg, "</g>")?;
+            }
+            Element::Text { kind, position: (x, y), anchor, vertical, text } => {
+                let (x, y) = (coordinate(*x), coordinate(*y));
+                let transform = match vertical {
+                    true => format!(r#" transform="rotate(-90 {x} {y})""#),
+                    false => String::new(),
+                };
+                writeln!(
+                    svg,
+                    r#"<text class="{}" x="{x}" y="{y}" text-anchor="{}"{transform}>{}</text>"#,
+                    kind.class(),
+                    anchor.name(),
+                    svg_escape(text)
+                )?;
             }
         }
-
-        writeln!(file, "</svg>")?;
-        Ok(())
     }
-}
\ No newline at end of file
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+/// One rule per class, with the colors of the theme
+fn write_style_sheet(svg: &mut String, figure: &Figure) -> Result<(), Box<dyn Error>> {
+    writeln!(svg, "<style>")?;
+    writeln!(svg, "  .background {{ fill: {} }}", figure.theme.background.css())?;
+    for kind in [PathKind::Grid, PathKind::Tick, PathKind::Axis, PathKind::Asymptote, PathKind::Series, PathKind::Markers] {
+        let style = figure.style(kind);
+        let dasharray = match style.dash.is_empty() {
+            true => String::new(),
+            false => {
+                let lengths: Vec<String> = style.dash.iter().map(|&length| coordinate(length)).collect();
+                format!("; stroke-dasharray: {}", lengths.join(" "))
+            }
+        };
+        let round = match style.round {
+            true => "; stroke-linejoin: round; stroke-linecap: round",
+            false => "",
+        };
+        writeln!(
+            svg,
+            "  .{} {{ fill: none; stroke: {}; stroke-width: {}{dasharray}{round} }}",
+            kind.class(),
+            style.color.css(),
+            coordinate(style.width)
+        )?;
+    }
+    for kind in [TextKind::TickLabel, TextKind::Title, TextKind::Label] {
+        let family = if kind.monospace() { "monospace" } else { "sans-serif" };
+        writeln!(
+            svg,
+            "  .{} {{ fill: {}; font-family: {family}; font-size: {}px }}",
+            kind.class(),
+            figure.theme.text.css(),
+            kind.size()
+        )?;
+    }
+    writeln!(svg, "  .surface polygon {{ stroke-width: 0.5; stroke-linejoin: round }}")?;
+    writeln!(svg, "</style>")?;
+    Ok(())
+}
+
+/// Escape text for use as XML character data or attribute values
+pub fn svg_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,245 @@
+use std::{error::Error, fmt::Write};
+
+use crate::{
+    values::{ExpressionRange1dResult, Expression3dResult},
+    parametric2d::Parametric2DResult,
+};
+
+use super::{
+    super::{AxisScale, OutputWriter, PlotBounds, PlotSettings, PlotStyle, Segments, SurfaceBounds},
+    json::{json_number, json_string},
+};
+
+const SCHEMA: &str = "https://vega.github.io/schema/vega-lite/v5.json";
+
+/// Writes a Vega-Lite specification with the data embedded, for dashboards and
+/// notebooks. Curves are `line` or `point` marks in sample order, broken where the
+/// other outputs break them; surfaces are heatmaps of `rect` marks colored by Z, as
+/// Vega-Lite has no 3D marks.
+#[derive(Clone, Debug)]
+pub struct VegaLiteWriter;
+
+impl VegaLiteWriter {
+    /// The members every spec starts with
+    fn header(spec: &mut String, settings: &PlotSettings, (width, height): (usize, usize)) -> Result<(), Box<dyn Error>> {
+        writeln!(spec, "{{")?;
+        writeln!(spec, "  \"$schema\": \"{SCHEMA}\",")?;
+        if let Some(title) = &settings.title {
+            writeln!(spec, "  \"title\": {},", json_string(title))?;
+        }
+        writeln!(spec, "  \"description\": {},", json_string(&settings.expression))?;
+        writeln!(spec, "  \"width\": {width},")?;
+        writeln!(spec, "  \"height\": {height},")?;
+        Ok(())
+    }
+
+    /// A curve as one data row per sample, numbered in drawing order so Vega-Lite
+    /// does not sort them by X, and with the piece each belongs to so it does not
+    /// join the pieces
+    fn write_curve(
+        filename: &str,
+        points: &[(f64, f64)],
+        segments: Option<Segments>,
+        bounds: Option<PlotBounds>,
+        size: (usize, usize),
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let (pieces, asymptotes) = match segments {
+            Some(segments) => (segments.pieces, segments.asymptotes),
+            None => (vec![points.to_vec()], Vec::new()),
+        };
+
+        let mut spec = String::new();
+        Self::header(&mut spec, settings, size)?;
+        let mut rows = Vec::new();
+        for (piece, points) in pieces.iter().enumerate() {
+            for &(x, y) in points {
+                rows.push(format!(
+                    "      {{\"x\": {}, \"y\": {}, \"piece\": {piece}, \"order\": {}}}",
+                    json_number(x),
+                    json_number(y),
+                    rows.len()
+                ));
+            }
+        }
+        writeln!(spec, "  \"data\": {{\"values\": [\n{}\n  ]}},", rows.join(",\n"))?;
+
+        let mark = match settings.style {
+            PlotStyle::Lines => "{\"type\": \"line\", \"clip\": true}",
+            PlotStyle::Points => "{\"type\": \"point\", \"filled\": true, \"size\": 16, \"clip\": true}",
+            PlotStyle::Both => "{\"type\": \"line\", \"point\": {\"filled\": true, \"size\": 16}, \"clip\": true}",
+        };
+        let (x_encoding, y_encoding) = match bounds {
+            Some(bounds) => (
+                axis_encoding("x", &settings.xlabel, Some((bounds.x_min, bounds.x_max)), bounds.x_scale),
+                axis_encoding("y", &settings.ylabel, Some((bounds.y_min, bounds.y_max)), bounds.y_scale),
+            ),
+            None => (
+                axis_encoding("x", &settings.xlabel, None, settings.x_scale),
+                axis_encoding("y", &settings.ylabel, None, settings.y_scale),
+            ),
+        };
+        writeln!(spec, "  \"layer\": [")?;
+        writeln!(spec, "    {{")?;
+        writeln!(spec, "      \"mark\": {mark},")?;
+        writeln!(spec, "      \"encoding\": {{")?;
+        writeln!(spec, "        \"x\": {x_encoding},")?;
+        writeln!(spec, "        \"y\": {y_encoding},")?;
+        writeln!(spec, "        \"detail\": {{\"field\": \"piece\", \"type\": \"nominal\"}},")?;
+        writeln!(spec, "        \"order\": {{\"field\": \"order\", \"type\": \"quantitative\"}}")?;
+        write!(spec, "      }}\n    }}")?;
+        if settings.asymptotes && !asymptotes.is_empty() {
+            let values: Vec<String> = asymptotes.iter().map(|&x| format!("{{\"x\": {}}}", json_number(x))).collect();
+            writeln!(spec, ",\n    {{")?;
+            writeln!(spec, "      \"data\": {{\"values\": [{}]}},", values.join(", "))?;
+            writeln!(spec, "      \"mark\": {{\"type\": \"rule\", \"color\": \"gray\", \"strokeDash\": [6, 4]}},")?;
+            writeln!(spec, "      \"encoding\": {{\"x\": {{\"field\": \"x\", \"type\": \"quantitative\"}}}}")?;
+            write!(spec, "    }}")?;
+        }
+        writeln!(spec, "\n  ]")?;
+        writeln!(spec, "}}")?;
+        std::fs::write(filename, spec)?;
+        Ok(())
+    }
+}
+
+impl OutputWriter for VegaLiteWriter {
+    fn write(
+        &self,
+        filename: &str,
+        x_result: &ExpressionRange1dResult,
+        y_result: &ExpressionRange1dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let points: Vec<(f64, f64)> = x_result.0.iter().copied().zip(y_result.0.iter().copied()).collect();
+        let bounds = PlotBounds::for_plot(&x_result.0, &y_result.0, settings);
+        let segments = bounds.map(|bounds| Segments::split(&points, &bounds));
+        Self::write_curve(filename, &points, segments, bounds, (600, 400), settings)
+    }
+
+    /// The view is sized so both axes get the same unit, keeping circles round, when
+    /// both are linear
+    fn write_parametric(
+        &self,
+        filename: &str,
+        result: &Parametric2DResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let points: Vec<(f64, f64)> = result.x_values.iter().copied().zip(result.y_values.iter().copied()).collect();
+        let bounds = PlotBounds::for_parametric(&result.x_values, &result.y_values, settings);
+        let segments = bounds.map(|bounds| Segments::split_curve(&points, &bounds));
+        let size = match bounds {
+            Some(bounds) if (bounds.x_scale, bounds.y_scale) == (AxisScale::Linear, AxisScale::Linear) => {
+                let ratio = (bounds.y_max - bounds.y_min) / (bounds.x_max - bounds.x_min);
+                (500, (500.0 * ratio).clamp(100.0, 1000.0).round() as usize)
+            }
+            _ => (500, 500),
+        };
+        Self::write_curve(filename, &points, segments, bounds, size, settings)
+    }
+
+    /// One rectangle per grid point, reaching halfway to its neighbours, colored
+    /// with the `viridis` scheme. Undefined points are left out.
+    fn write_surface3d(
+        &self,
+        filename: &str,
+        result: &Expression3dResult,
+        _width: usize,
+        _height: usize,
+        settings: &PlotSettings,
+    ) -> Result<(), Box<dyn Error>> {
+        let bounds = SurfaceBounds::for_surface(result, settings);
+        let x_edges = cell_edges(&result.x_values, settings.x_scale);
+        let y_edges = cell_edges(&result.y_values, settings.y_scale);
+
+        let mut spec = String::new();
+        Self::header(&mut spec, settings, (500, 500))?;
+        let mut rows = Vec::new();
+        for (y_idx, &(y, y2)) in y_edges.iter().enumerate() {
+            for (x_idx, &(x, x2)) in x_edges.iter().enumerate() {
+                let Some(z) = result.get_z(x_idx, y_idx).filter(|z| z.is_finite()) else { continue };
+                rows.push(format!(
+                    "      {{\"x\": {}, \"x2\": {}, \"y\": {}, \"y2\": {}, \"z\": {}}}",
+                    json_number(x),
+                    json_number(x2),
+                    json_number(y),
+                    json_number(y2),
+                    json_number(z)
+                ));
+            }
+        }
+        writeln!(spec, "  \"data\": {{\"values\": [\n{}\n  ]}},", rows.join(",\n"))?;
+
+        let (x_view, y_view, z_view) = match &bounds {
+            Some(bounds) => {
+                (Some((bounds.x_min, bounds.x_max)), Some((bounds.y_min, bounds.y_max)), Some((bounds.z_min, bounds.z_max)))
+            }
+            None => (None, None, None),
+        };
+        let mut z_scale = String::from("{\"scheme\": \"viridis\"");
+        if let Some((min, max)) = z_view {
+            write!(z_scale, ", \"domain\": [{}, {}]", json_number(min), json_number(max))?;
+        }
+        if settings.z_scale == AxisScale::Log {
+            z_scale.push_str(", \"type\": \"log\"");
+        }
+        z_scale.push('}');
+        let z_title = settings.zlabel.as_deref().map_or_else(|| "\"z\"".to_string(), json_string);
+
+        writeln!(spec, "  \"mark\": {{\"type\": \"rect\", \"clip\": true}},")?;
+        writeln!(spec, "  \"encoding\": {{")?;
+        writeln!(spec, "    \"x\": {},", axis_encoding("x", &settings.xlabel, x_view, settings.x_scale))?;
+        writeln!(spec, "    \"x2\": {{\"field\": \"x2\"}},")?;
+        writeln!(spec, "    \"y\": {},", axis_encoding("y", &settings.ylabel, y_view, settings.y_scale))?;
+        writeln!(spec, "    \"y2\": {{\"field\": \"y2\"}},")?;
+        writeln!(spec, "    \"color\": {{\"field\": \"z\", \"type\": \"quantitative\", \"title\": {z_title}, \"scale\": {z_scale}}}")?;
+        writeln!(spec, "  }}")?;
+        writeln!(spec, "}}")?;
+        std::fs::write(filename, spec)?;
+        Ok(())
+    }
+}
+
+/// Encoding of a position channel, fixed to the view window when there is one
+fn axis_encoding(field: &str, title: &Option<String>, view: Option<(f64, f64)>, scale: AxisScale) -> String {
+    let mut encoding = format!("{{\"field\": \"{field}\", \"type\": \"quantitative\"");
+    if let Some(title) = title {
+        encoding.push_str(&format!(", \"title\": {}", json_string(title)));
+    }
+    let mut properties = Vec::new();
+    if let Some((min, max)) = view {
+        properties.push(format!("\"domain\": [{}, {}], \"nice\": false, \"zero\": false", json_number(min), json_number(max)));
+    }
+    if scale == AxisScale::Log {
+        properties.push("\"type\": \"log\"".to_string());
+    }
+    if !properties.is_empty() {
+        encoding.push_str(&format!(", \"scale\": {{{}}}", properties.join(", ")));
+    }
+    encoding.push('}');
+    encoding
+}
+
+/// Where the cell of each grid value starts and ends: halfway to the neighbouring
+/// values, measured along the axis as drawn, and as far out again at both ends
+fn cell_edges(values: &[f64], scale: AxisScale) -> Vec<(f64, f64)> {
+    let drawn: Vec<f64> = values.iter().map(|&value| scale.forward(value)).collect();
+    (0..drawn.len())
+        .map(|index| {
+            let half_step = |other: Option<&f64>| other.map_or(f64::NAN, |other| (other - drawn[index]) / 2.0);
+            let before = half_step(index.checked_sub(1).and_then(|before| drawn.get(before)));
+            let after = half_step(drawn.get(index + 1));
+            let (before, after) = match (before.is_finite(), after.is_finite()) {
+                (true, true) => (before, after),
+                (true, false) => (before, -before),
+                (false, true) => (-after, after),
+                (false, false) => (-0.5, 0.5),
+            };
+            (scale.inverse(drawn[index] + before), scale.inverse(drawn[index] + after))
+        })
+        .collect()
+}
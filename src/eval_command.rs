@@ -11,10 +11,11 @@ use std::collections::HashMap;
 //
 use crate::{
     command::{Command, PlotType},
-    command_options::{DisplayOption, OutputOptions},
+    command_options::{DisplayOption, FormatOption, Geometry, LatexModeOption, OutputOptions, ScaleOption, StyleOption},
     display::{
-        AnsiRenderer, AsciiRenderer, CsvWriter, DisplayRenderer, LatexWriter, OutputWriter,
-        PpmWriter, RegisRenderer, RegisWriter, SixelRenderer, SixelWriter, SvgWriter,
+        AnsiRenderer, AsciiRenderer, Autoscale, BlocksRenderer, BrailleRenderer, ColorDepth, ItermRenderer, KittyRenderer, CsvWriter, DisplayRenderer, LatexMode, LatexWriter, OutputWriter,
+        AxisScale, BmpWriter, EpsWriter, GnuplotWriter, HtmlWriter, JsonWriter, MatplotlibWriter, NpyWriter, ObjWriter, PlyWriter, StlWriter, VegaLiteWriter, NetpbmFormat, PbmWriter, PdfWriter, PgmWriter, PlotSettings, PlotStyle, PngWriter, PpmWriter, RegisRenderer, RegisWriter, SixelRenderer, SixelWriter,
+        renderers::sixel, terminal, SvgWriter, TerminalSize, Theme,
     },
     eval::{Eval, EvaluationError},
     eval_expression,
@@ -139,60 +140,188 @@ impl CommandEvaluator {
         }
     }
 
+    /// Title, axis labels and view window from the command options; the title
+    /// defaults to the plotted expression, which is kept along with the ranges
+    fn plot_settings(&self, command: &Command) -> PlotSettings {
+        let expression = match &command.plot {
+            PlotType::Expression(expr) => format!("{expr}"),
+            PlotType::Parametric(parametric) => format!("{parametric}"),
+            PlotType::Surface3D(expr, _, _) => format!("{expr}"),
+        };
+        let title = command.options.title.clone().or_else(|| Some(expression.clone()));
+
+        let ordered = |(a, b): (f64, f64)| (a.min(b), a.max(b));
+        let scale = |option: ScaleOption| match option {
+            ScaleOption::Linear => AxisScale::Linear,
+            ScaleOption::Log => AxisScale::Log,
+        };
+
+        PlotSettings {
+            ranges: std::iter::once(&command.range).chain(&command.y_range).cloned().collect(),
+            expression,
+            title,
+            xlabel: command.options.xlabel.clone(),
+            ylabel: command.options.ylabel.clone(),
+            zlabel: command.options.zlabel.clone(),
+            x_view: command.options.x_view.map(ordered),
+            y_view: command.options.y_view.map(ordered),
+            autoscale: match command.options.autoscale {
+                Some(percent) if percent > 0.0 => Autoscale::Percentile(percent),
+                _ => Autoscale::Full,
+            },
+            x_scale: scale(command.options.x_scale),
+            y_scale: scale(command.options.y_scale),
+            z_scale: scale(command.options.z_scale),
+            asymptotes: command.options.asymptotes,
+            style: match command.options.style {
+                StyleOption::Points => PlotStyle::Points,
+                StyleOption::Lines => PlotStyle::Lines,
+                StyleOption::Both => PlotStyle::Both,
+            },
+            linewidth: command.options.linewidth,
+            dash: command.options.dash.clone(),
+            theme: Theme::default(),
+        }
+    }
+
+    /// Log axes cannot show zero or negative values; tell the user how many samples
+    /// are left out rather than dropping them silently
+    fn warn_dropped_values(&self, plot_result: &PlotResult, settings: &PlotSettings) {
+        let mut axes: Vec<(&str, AxisScale, Vec<f64>)> = Vec::new();
+        match plot_result {
+            PlotResult::Plot2D(x_result, y_result) => {
+                axes.push(("X", settings.x_scale, x_result.0.clone()));
+                axes.push(("Y", settings.y_scale, y_result.0.clone()));
+            }
+            PlotResult::Parametric2D(parametric_result) => {
+                axes.push(("X", settings.x_scale, parametric_result.x_values.clone()));
+                axes.push(("Y", settings.y_scale, parametric_result.y_values.clone()));
+            }
+            PlotResult::Surface3D(surface3d_result) => {
+                axes.push(("X", settings.x_scale, surface3d_result.x_values.clone()));
+                axes.push(("Y", settings.y_scale, surface3d_result.y_values.clone()));
+                axes.push(("Z", settings.z_scale, surface3d_result.data.concat()));
+            }
+        }
+
+        for (axis, scale, values) in axes {
+            let dropped = values
+                .iter()
+                .filter(|value| value.is_finite() && !scale.accepts(**value))
+                .count();
+            if dropped > 0 {
+                eprintln!("Warning: {dropped} non-positive {axis} values dropped from the log scale");
+            }
+        }
+
+        // Ranges given by the user the log scale cannot show, as `AxisScale::window` handles them
+        let views = [("xrange", "X", settings.x_scale, settings.x_view), ("yrange", "Y", settings.y_scale, settings.y_view)];
+        for (option, axis, scale, view) in views {
+            let Some((lo, hi)) = view else { continue };
+            match (scale.accepts(lo), scale.accepts(hi)) {
+                (true, true) => {}
+                (false, false) => eprintln!("Warning: {option}={lo}:{hi} is not positive, autoscaling the log scale of {axis}"),
+                _ => eprintln!(
+                    "Warning: {option}={lo}:{hi} has a non-positive end, starting the log scale of {axis} at the smallest positive value"
+                ),
+            }
+        }
+    }
+
+    fn render_with(
+        &self,
+        renderer: &impl DisplayRenderer,
+        plot_result: &PlotResult,
+        (width, height): (usize, usize),
+        settings: &PlotSettings,
+    ) -> String {
+        match plot_result {
+            PlotResult::Plot2D(x_result, y_result) => renderer.render(y_result, width, height, x_result, settings),
+            PlotResult::Parametric2D(parametric_result) => renderer.render_parametric(parametric_result, width, height, settings),
+            PlotResult::Surface3D(surface3d_result) => renderer.render_surface3d(surface3d_result, width, height, settings),
+        }
+    }
+
     fn handle_display(
         &self,
         command: &Command,
         plot_result: &PlotResult,
+        settings: &PlotSettings,
     ) {
-        match plot_result {
-            PlotResult::Plot2D(x_result, y_result) => {
-                if command.options.display.is_empty() {
-                    let output = AsciiRenderer.render(y_result, 80, 24, x_result);
-                    println!("{output}");
-                } else {
-                    for display_option in &command.options.display {
-                        let output = match display_option {
-                            DisplayOption::Regis(_) => RegisRenderer.render(y_result, 800, 600, x_result),
-                            DisplayOption::Ascii(_) => AsciiRenderer.render(y_result, 80, 24, x_result),
-                            DisplayOption::Ansi(_) => AnsiRenderer.render(y_result, 80, 24, x_result),
-                            DisplayOption::Sixel(_) => SixelRenderer.render(y_result, 400, 300, x_result),
-                        };
-                        println!("{output}");
-                    }
+        let terminal = if command.options.display.iter().any(DisplayOption::is_graphics) {
+            TerminalSize::detect_with_pixels()
+        } else {
+            TerminalSize::detect()
+        };
+        let text_size = terminal.and_then(|t| t.text_plot_size()).unwrap_or((80, 24));
+        // Colors for the text displays, unless the user turned them off
+        let color_depth = ColorDepth::detect();
+
+        if command.options.display.is_empty() {
+            let output = self.render_with(&AsciiRenderer, plot_result, text_size, settings);
+            println!("{output}");
+            return;
+        }
+
+        for display_option in &command.options.display {
+            let output = match display_option {
+                DisplayOption::Regis(node) => {
+                    let size = display_size(&node.value, || terminal?.graphics_size(), (800, 600), terminal::MIN_GRAPHICS_SIZE);
+                    self.render_with(&RegisRenderer, plot_result, size, settings)
                 }
-            }
-            PlotResult::Parametric2D(parametric_result) => {
-                if command.options.display.is_empty() {
-                    let output = AsciiRenderer.render_parametric(parametric_result, 80, 24);
-                    println!("{output}");
-                } else {
-                    for display_option in &command.options.display {
-                        let output = match display_option {
-                            DisplayOption::Regis(_) => RegisRenderer.render_parametric(parametric_result, 800, 600),
-                            DisplayOption::Ascii(_) => AsciiRenderer.render_parametric(parametric_result, 80, 24),
-                            DisplayOption::Ansi(_) => AnsiRenderer.render_parametric(parametric_result, 80, 24),
-                            DisplayOption::Sixel(_) => SixelRenderer.render_parametric(parametric_result, 400, 300),
-                        };
-                        println!("{output}");
-                    }
+                DisplayOption::Ascii(node) => {
+                    let size = display_size(&node.value, || Some(text_size), (80, 24), terminal::MIN_TEXT_PLOT_SIZE);
+                    self.render_with(&AsciiRenderer, plot_result, size, settings)
                 }
-            }
-            PlotResult::Surface3D(surface3d_result) => {
-                if command.options.display.is_empty() {
-                    let output = AsciiRenderer.render_surface3d(surface3d_result, 80, 24);
-                    println!("{output}");
-                } else {
-                    for display_option in &command.options.display {
-                        let output = match display_option {
-                            DisplayOption::Regis(_) => RegisRenderer.render_surface3d(surface3d_result, 800, 600),
-                            DisplayOption::Ascii(_) => AsciiRenderer.render_surface3d(surface3d_result, 80, 24),
-                            DisplayOption::Ansi(_) => AnsiRenderer.render_surface3d(surface3d_result, 80, 24),
-                            DisplayOption::Sixel(_) => SixelRenderer.render_surface3d(surface3d_result, 400, 300),
-                        };
-                        println!("{output}");
-                    }
+                DisplayOption::Ansi(node) => {
+                    let size = display_size(&node.value, || Some(text_size), (80, 24), terminal::MIN_TEXT_PLOT_SIZE);
+                    self.render_with(&AnsiRenderer::new(color_depth.is_some()), plot_result, size, settings)
                 }
-            }
+                DisplayOption::Braille(node) => {
+                    let size = display_size(&node.value, || Some(text_size), (80, 24), terminal::MIN_TEXT_PLOT_SIZE);
+                    self.render_with(&BrailleRenderer::new(color_depth.is_some()), plot_result, size, settings)
+                }
+                DisplayOption::Blocks(node) => {
+                    let size = display_size(&node.value, || Some(text_size), (80, 24), terminal::MIN_TEXT_PLOT_SIZE);
+                    self.render_with(&BlocksRenderer::new(color_depth), plot_result, size, settings)
+                }
+                DisplayOption::Sixel(node) => {
+                    let size = display_size(&node.value, || terminal?.graphics_size(), (500, 400), terminal::MIN_GRAPHICS_SIZE);
+                    let registers = terminal::sixel_registers().unwrap_or(sixel::DEFAULT_REGISTERS);
+                    self.render_with(&SixelRenderer::new(registers), plot_result, size, settings)
+                }
+                DisplayOption::Kitty(node) => {
+                    let (size, fit) = inline_image_size(&node.value, terminal);
+                    let cell_pixels = terminal.and_then(|t| t.cell_pixels());
+                    self.render_with(&KittyRenderer::new(fit, cell_pixels), plot_result, size, settings)
+                }
+                DisplayOption::Iterm(node) => {
+                    let (size, fit) = inline_image_size(&node.value, terminal);
+                    self.render_with(&ItermRenderer::new(fit), plot_result, size, settings)
+                }
+            };
+            println!("{output}");
+        }
+    }
+
+    fn save_with(
+        &self,
+        writer: &impl OutputWriter,
+        (format, filename): (&str, &str),
+        (width, height): (usize, usize),
+        plot_result: &PlotResult,
+        settings: &PlotSettings,
+    ) {
+        let saved = match plot_result {
+            PlotResult::Plot2D(x_result, y_result) => writer.write(filename, x_result, y_result, width, height, settings),
+            PlotResult::Parametric2D(parametric_result) => writer.write_parametric(filename, parametric_result, width, height, settings),
+            PlotResult::Surface3D(surface3d_result) => writer.write_surface3d(filename, surface3d_result, width, height, settings),
+        };
+
+        if let Err(e) = saved {
+            eprintln!("Error saving {format}: {e}");
+        } else {
+            println!("{format} output saved to {filename}");
         }
     }
 
@@ -200,193 +329,93 @@ impl CommandEvaluator {
         &self,
         command: &Command,
         plot_result: &PlotResult,
+        settings: &PlotSettings,
     ) {
-        match plot_result {
-            PlotResult::Plot2D(x_result, y_result) => {
-                for output_option in &command.options.output {
-                    match output_option {
-                        OutputOptions::Csv(node) => {
-                            if let Err(e) = CsvWriter.write(&node.value, x_result, y_result, 0, 0) {
-                                eprintln!("Error saving CSV: {e}");
-                            } else {
-                                println!("CSV output saved to {}", node.value);
-                            }
-                        }
-                        OutputOptions::Ppm(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) =
-                                PpmWriter.write(&node.value.0, x_result, y_result, geom.width, geom.height)
-                            {
-                                eprintln!("Error saving PPM: {e}");
-                            } else {
-                                println!("PPM output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::Svg(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) =
-                                SvgWriter.write(&node.value.0, x_result, y_result, geom.width, geom.height)
-                            {
-                                eprintln!("Error saving SVG: {e}");
-                            } else {
-                                println!("SVG output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::LaTeX(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) = LatexWriter.write(
-                                &node.value.0,
-                                x_result,
-                                y_result,
-                                geom.width,
-                                geom.height,
-                            ) {
-                                eprintln!("Error saving LaTeX: {e}");
-                            } else {
-                                println!("LaTeX output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::Sixel(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) = SixelWriter.write(
-                                &node.value.0,
-                                x_result,
-                                y_result,
-                                geom.width,
-                                geom.height,
-                            ) {
-                                eprintln!("Error saving Sixel: {e}");
-                            } else {
-                                println!("Sixel output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::Regis(node) => {
-                            if let Err(e) = RegisWriter.write(&node.value.0, x_result, y_result, 800, 800) {
-                                eprintln!("Error saving REGIS: {e}");
-                            } else {
-                                println!("REGIS output saved to {}", node.value.0);
-                            }
-                        }
-                    }
+        for output_option in &command.options.output {
+            match output_option {
+                OutputOptions::Csv(node) => {
+                    self.save_with(&CsvWriter, ("CSV", &node.value), (0, 0), plot_result, settings);
                 }
-            }
-            PlotResult::Parametric2D(parametric_result) => {
-                
-                for output_option in &command.options.output {
-                    match output_option {
-                        OutputOptions::Csv(node) => {
-                            if let Err(e) = CsvWriter.write_parametric(&node.value, parametric_result, 0, 0) {
-                                eprintln!("Error saving CSV: {e}");
-                            } else {
-                                println!("CSV output saved to {}", node.value);
-                            }
-                        }
-                        OutputOptions::Ppm(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) =
-                                PpmWriter.write_parametric(&node.value.0, parametric_result, geom.width, geom.height)
-                            {
-                                eprintln!("Error saving PPM: {e}");
-                            } else {
-                                println!("PPM output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::Svg(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) =
-                                SvgWriter.write_parametric(&node.value.0, parametric_result, geom.width, geom.height)
-                            {
-                                eprintln!("Error saving SVG: {e}");
-                            } else {
-                                println!("SVG output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::LaTeX(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) = LatexWriter.write_parametric(
-                                &node.value.0,
-                                parametric_result,
-                                geom.width,
-                                geom.height,
-                            ) {
-                                eprintln!("Error saving LaTeX: {e}");
-                            } else {
-                                println!("LaTeX output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::Sixel(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) = SixelWriter.write_parametric(
-                                &node.value.0,
-                                parametric_result,
-                                geom.width,
-                                geom.height,
-                            ) {
-                                eprintln!("Error saving Sixel: {e}");
-                            } else {
-                                println!("Sixel output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::Regis(node) => {
-                            if let Err(e) = RegisWriter.write_parametric(&node.value.0, parametric_result, 800, 800) {
-                                eprintln!("Error saving REGIS: {e}");
-                            } else {
-                                println!("REGIS output saved to {}", node.value.0);
-                            }
-                        }
-                    }
+                OutputOptions::Gnuplot(node) => {
+                    self.save_with(&GnuplotWriter, ("Gnuplot script", &node.value), (0, 0), plot_result, settings);
                 }
-            }
-            PlotResult::Surface3D(surface3d_result) => {
-                for output_option in &command.options.output {
-                    match output_option {
-                        OutputOptions::Csv(node) => {
-                            if let Err(e) = CsvWriter.write_surface3d(&node.value, surface3d_result, 0, 0) {
-                                eprintln!("Error saving CSV: {e}");
-                            } else {
-                                println!("CSV output saved to {}", node.value);
-                            }
-                        }
-                        OutputOptions::Ppm(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) = PpmWriter.write_surface3d(&node.value.0, surface3d_result, geom.width, geom.height) {
-                                eprintln!("Error saving PPM: {e}");
-                            } else {
-                                println!("PPM output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::Svg(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) = SvgWriter.write_surface3d(&node.value.0, surface3d_result, geom.width, geom.height) {
-                                eprintln!("Error saving SVG: {e}");
-                            } else {
-                                println!("SVG output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::LaTeX(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) = LatexWriter.write_surface3d(&node.value.0, surface3d_result, geom.width, geom.height) {
-                                eprintln!("Error saving LaTeX: {e}");
-                            } else {
-                                println!("LaTeX output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::Sixel(node) => {
-                            let geom = &node.value.1;
-                            if let Err(e) = SixelWriter.write_surface3d(&node.value.0, surface3d_result, geom.width, geom.height) {
-                                eprintln!("Error saving Sixel: {e}");
-                            } else {
-                                println!("Sixel output saved to {}", node.value.0);
-                            }
-                        }
-                        OutputOptions::Regis(node) => {
-                            if let Err(e) = RegisWriter.write_surface3d(&node.value.0, surface3d_result, 800, 800) {
-                                eprintln!("Error saving REGIS: {e}");
-                            } else {
-                                println!("REGIS output saved to {}", node.value.0);
-                            }
-                        }
+                OutputOptions::Matplotlib(node) => {
+                    self.save_with(&MatplotlibWriter, ("Matplotlib script", &node.value), (0, 0), plot_result, settings);
+                }
+                OutputOptions::Json(node) => {
+                    self.save_with(&JsonWriter, ("JSON", &node.value), (0, 0), plot_result, settings);
+                }
+                OutputOptions::VegaLite(node) => {
+                    self.save_with(&VegaLiteWriter, ("Vega-Lite", &node.value), (0, 0), plot_result, settings);
+                }
+                OutputOptions::Npy(node) => {
+                    self.save_with(&NpyWriter, ("NPY", &node.value), (0, 0), plot_result, settings);
+                }
+                OutputOptions::Obj(node) => {
+                    self.save_with(&ObjWriter, ("OBJ", &node.value), (0, 0), plot_result, settings);
+                }
+                OutputOptions::Stl(node) => {
+                    let (filename, geometry, format) = &node.value;
+                    if geometry.is_some() {
+                        eprintln!("Warning: geometry has no effect on STL output");
                     }
+                    let writer = StlWriter::new(*format == FormatOption::Binary);
+                    self.save_with(&writer, ("STL", filename), (0, 0), plot_result, settings);
+                }
+                OutputOptions::Ply(node) => {
+                    self.save_with(&PlyWriter, ("PLY", &node.value), (0, 0), plot_result, settings);
+                }
+                OutputOptions::Ppm(node) => {
+                    let (filename, geom, format) = &node.value;
+                    let writer = PpmWriter::new(netpbm_format(*format));
+                    self.save_with(&writer, ("PPM", filename), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Pgm(node) => {
+                    let (filename, geom, format) = &node.value;
+                    let writer = PgmWriter::new(netpbm_format(*format));
+                    self.save_with(&writer, ("PGM", filename), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Pbm(node) => {
+                    let (filename, geom, format) = &node.value;
+                    let writer = PbmWriter::new(netpbm_format(*format));
+                    self.save_with(&writer, ("PBM", filename), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Bmp(node) => {
+                    let geom = &node.value.1;
+                    self.save_with(&BmpWriter, ("BMP", &node.value.0), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Png(node) => {
+                    let geom = &node.value.1;
+                    self.save_with(&PngWriter, ("PNG", &node.value.0), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Svg(node) => {
+                    let geom = &node.value.1;
+                    self.save_with(&SvgWriter, ("SVG", &node.value.0), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Pdf(node) => {
+                    let (filename, geom, compress) = &node.value;
+                    self.save_with(&PdfWriter::new(*compress), ("PDF", filename), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Eps(node) => {
+                    let geom = &node.value.1;
+                    self.save_with(&EpsWriter, ("EPS", &node.value.0), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Html(node) => {
+                    let geom = &node.value.1;
+                    self.save_with(&HtmlWriter, ("HTML", &node.value.0), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::LaTeX(node) => {
+                    let (filename, geom, mode) = &node.value;
+                    let writer = LatexWriter::new(latex_mode(*mode));
+                    self.save_with(&writer, ("LaTeX", filename), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Sixel(node) => {
+                    let geom = &node.value.1;
+                    self.save_with(&SixelWriter, ("Sixel", &node.value.0), image_size(geom), plot_result, settings);
+                }
+                OutputOptions::Regis(node) => {
+                    let geom = &node.value.1;
+                    self.save_with(&RegisWriter, ("REGIS", &node.value.0), image_size(geom), plot_result, settings);
                 }
             }
         }
@@ -398,13 +427,78 @@ impl Eval<Command, (), ()> for CommandEvaluator {
         let evaluator = CommandEvaluator::new();
 
         let plot_result = evaluator.evaluate_expression(command)?;
-        evaluator.handle_display(command, &plot_result);
-        evaluator.handle_output(command, &plot_result);
+        let settings = evaluator.plot_settings(command);
+        evaluator.warn_dropped_values(&plot_result, &settings);
+        evaluator.handle_display(command, &plot_result, &settings);
+        evaluator.handle_output(command, &plot_result, &settings);
 
         Ok(())
     }
 }
 
+/// Size to render a display at: the geometry given in the command, else the size
+/// derived from the terminal, else the historical default. A geometry below the
+/// smallest size the renderer can lay out is raised to it, with a warning.
+fn display_size<T>(
+    geometry: &Option<Geometry<T>>,
+    detected: impl FnOnce() -> Option<(usize, usize)>,
+    default: (usize, usize),
+    minimum: (usize, usize),
+) -> (usize, usize) {
+    match geometry {
+        Some(geometry) => at_least("display", (geometry.width, geometry.height), minimum),
+        None => detected().unwrap_or(default),
+    }
+}
+
+/// Size of an image file: its geometry, raised with a warning to the smallest image
+/// that leaves room for the labels
+fn image_size<T>(geometry: &Geometry<T>) -> (usize, usize) {
+    at_least("image", (geometry.width, geometry.height), terminal::MIN_GRAPHICS_SIZE)
+}
+
+/// `size` raised to `minimum`, warning about the `kind` of size when it was below
+fn at_least(kind: &str, size: (usize, usize), minimum: (usize, usize)) -> (usize, usize) {
+    let raised = (size.0.max(minimum.0), size.1.max(minimum.1));
+    if raised != size {
+        eprintln!(
+            "Warning: {kind} size {}x{} is below the {}x{} minimum, using {}x{}",
+            size.0, size.1, minimum.0, minimum.1, raised.0, raised.1
+        );
+    }
+    raised
+}
+
+/// Image size for an inline image display (kitty, iTerm2), and the text area the
+/// terminal should scale the image into when neither a size was given nor the
+/// terminal's pixel size is known
+fn inline_image_size<T>(
+    geometry: &Option<Geometry<T>>,
+    terminal: Option<TerminalSize>,
+) -> ((usize, usize), Option<(usize, usize)>) {
+    let detected = terminal.and_then(|t| t.graphics_size());
+    let fit = match (geometry, detected) {
+        (None, None) => terminal.and_then(|t| t.image_cells()),
+        _ => None,
+    };
+    (display_size(geometry, || detected, (500, 400), terminal::MIN_GRAPHICS_SIZE), fit)
+}
+
+fn netpbm_format(format: FormatOption) -> NetpbmFormat {
+    match format {
+        FormatOption::Ascii => NetpbmFormat::Ascii,
+        FormatOption::Binary => NetpbmFormat::Binary,
+    }
+}
+
+fn latex_mode(mode: LatexModeOption) -> LatexMode {
+    match mode {
+        LatexModeOption::Standalone => LatexMode::Standalone,
+        LatexModeOption::Snippet => LatexMode::Snippet,
+        LatexModeOption::Document => LatexMode::Document,
+    }
+}
+
 pub fn evaluate_command(command: &Command) -> Result<(), EvaluationError> {
     CommandEvaluator::eval(command, &())
 }
@@ -21,6 +21,14 @@ impl<Context> Eval<Range, Context, ExpressionRange1dResult>
             Range::NumericStep(x) => {
                 ExpressionRange1dResult::create_with_step(x.value.1, x.value.2, x.value.3)
             }
+            Range::FileBare(x) if ExpressionRange1dResult::is_npy_file(&x.value.1) => {
+                ExpressionRange1dResult::create_from_npy(x.clone().value.1, None)
+                    .map_err(|a| a(x.location.0, x.location.1))
+            }
+            Range::FileCol(x) if ExpressionRange1dResult::is_npy_file(&x.value.1) => {
+                ExpressionRange1dResult::create_from_npy(x.clone().value.1, Some(x.value.2))
+                    .map_err(|a| a(x.location.0, x.location.1))
+            }
             Range::FileBare(x) => ExpressionRange1dResult::create_from_file(x.clone().value.1)
                 .map_err(|a| a(x.location.0, x.location.1)),
             Range::FileCol(x) => {
@@ -112,6 +112,12 @@ impl HasSameShape for usize {
     }
 }
 
+impl HasSameShape for bool {
+    fn has_same_shape(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
 impl<T: HasSameShape> HasSameShape for Vec<T> {
     fn has_same_shape(&self, other: &Self) -> bool {
         self.iter()
@@ -120,6 +126,16 @@ impl<T: HasSameShape> HasSameShape for Vec<T> {
     }
 }
 
+impl<T: HasSameShape> HasSameShape for Option<T> {
+    fn has_same_shape(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(x), Some(y)) => x.has_same_shape(y),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
 impl HasSameShape for () {
     fn has_same_shape(&self, other: &Self) -> bool {
         self == other
@@ -3,7 +3,7 @@
 //#![allow(dead_code)]
 //#![allow(unused_variables)]
 //#![allow(unused_imports)]
-#![allow(elided_named_lifetimes)]
+#![allow(mismatched_lifetime_syntaxes)]
 
 use std::{collections::HashMap, env, io::stdin};
 
@@ -99,14 +99,56 @@ fn print_help() {
     println!("DISPLAY OPTIONS:");
     println!("  display=regis     - REGIS graphics (default)");
     println!("  display=sixel     - Sixel graphics");
+    println!("  display=kitty     - Kitty graphics protocol (kitty, WezTerm, Konsole)");
+    println!("  display=iterm     - iTerm2 inline images (iTerm2, WezTerm, mintty)");
     println!("  display=ascii     - ASCII art");
     println!("  display=ansi      - ANSI graphics");
+    println!("  display=braille   - Braille dots, 2x4 per character cell");
+    println!("  display=blocks    - Colored half blocks, 1x2 per character cell");
+    println!("  display=ansi:WxH  - Explicit size (also display=ansi geometry=W,H);");
+    println!("                      displays otherwise fill the terminal");
     println!();
     println!("OUTPUT OPTIONS:");
-    println!("  png=filename      - PNG output");
-    println!("  jpg=filename      - JPEG output");
+    println!("  png=filename      - PNG image");
+    println!("  ppm=filename      - PPM color image");
+    println!("  pgm=filename      - PGM grayscale image, 16-bit heightmap for 3D surfaces");
+    println!("  pbm=filename      - PBM black and white image");
+    println!("  ppm=filename format=binary - Raw bytes (P6/P5/P4) instead of text (P3/P2/P1)");
+    println!("  bmp=filename      - 24-bit BMP image");
+    println!("  svg=filename      - SVG vector image, styled with CSS classes");
+    println!("  pdf=filename      - PDF vector image, compress=off for an uncompressed page");
+    println!("  eps=filename      - Encapsulated PostScript vector image");
+    println!("  html=filename     - Standalone HTML page with the SVG, hover readout, zoom and pan");
     println!("  csv=filename      - CSV data output");
-    println!("  latex=filename    - LaTeX output");
+    println!("  json=filename     - JSON data with the expression, ranges and view");
+    println!("  vegalite=filename - Vega-Lite specification with the data embedded");
+    println!("  npy=filename      - NumPy float64 array, Nx2 for curves and the Z grid for surfaces");
+    println!("  obj=filename      - Wavefront OBJ mesh of a 3D surface, with vertex normals");
+    println!("  stl=filename      - STL mesh of a 3D surface, format=binary for binary STL");
+    println!("  ply=filename      - PLY mesh of a 3D surface, colored by height");
+    println!("  gnuplot=filename  - Gnuplot script with the data inlined");
+    println!("  matplotlib=filename - Python script drawing the plot with Matplotlib");
+    println!("  latex=filename    - LaTeX pgfplots figure");
+    println!("  latex=filename latex_mode=snippet - Only the tikzpicture (also standalone, document)");
+    println!("  sixel=filename    - Sixel graphics file");
+    println!("  regis=filename    - REGIS graphics file");
+    println!("  png=filename geometry=W,H - Image size in pixels (also ppm, pgm, pbm, bmp, svg, pdf, eps, html, latex, sixel)");
+    println!();
+    println!("PLOT OPTIONS:");
+    println!("  title=text        - Plot title (defaults to the expression)");
+    println!("  xlabel=text       - X axis label");
+    println!("  ylabel=text       - Y axis label");
+    println!("  zlabel=text       - Z axis label (3D surfaces)");
+    println!("  xrange=a:b        - Only show X between a and b");
+    println!("  yrange=c:d        - Only show Y between c and d");
+    println!("  autoscale=full    - Fit the view to all finite values (default)");
+    println!("  autoscale=percentile:P - Ignore the lowest and highest P% of values");
+    println!("  xscale=log        - Logarithmic X axis (also yscale=, zscale=; default linear)");
+    println!("  asymptotes=on     - Dashed lines where the curve passes through infinity");
+    println!("  style=lines       - Join samples with lines (also points, both)");
+    println!("  linewidth=W       - Curve width in pixels for image outputs (default 2)");
+    println!("  dash=L,G          - Dash and gap lengths in pixels for image outputs (default none)");
+    println!("  Use double quotes for text with spaces, e.g. title=\"Damped wave\"");
     println!();
     println!("EXAMPLES:");
     println!("  x^2                                    - Simple quadratic");
@@ -114,6 +156,8 @@ fn print_help() {
     println!("  x for x in 0:10 with display=ascii    - ASCII plot");
     println!("  x^2 for x in -5:5 with display=ansi   - Colored plot");
     println!("  a=2; a*x^2 + 1 for x in -5:5          - With constant definition");
+    println!("  tan(x) for x in -3:3 with yrange=-5:5 - Clip the view near poles");
+    println!("  exp(-x) for x in 0:20 with yscale=log - Semi-log plot");
     println!();
     println!("DEBUGGING COMMANDS:");
     println!("  test                                   - Run built-in test");
@@ -144,17 +144,72 @@ token_implementer!(RegisToken, "display=regis");
 token_implementer!(SixelToken, "display=sixel");
 token_implementer!(AsciiToken, "display=ascii");
 token_implementer!(AnciToken, "display=ansi");
+token_implementer!(BrailleToken, "display=braille");
+token_implementer!(BlocksToken, "display=blocks");
+token_implementer!(KittyToken, "display=kitty");
+token_implementer!(ItermToken, "display=iterm");
 
 //Output tokens
 token_implementer!(PpmToken, "ppm=");
+token_implementer!(PngToken, "png=");
+token_implementer!(PgmToken, "pgm=");
+token_implementer!(PbmToken, "pbm=");
+token_implementer!(BmpToken, "bmp=");
+token_implementer!(PdfToken, "pdf=");
+token_implementer!(EpsToken, "eps=");
+token_implementer!(HtmlToken, "html=");
+token_implementer!(CompressToken, "compress=");
+token_implementer!(FormatToken, "format=");
+token_implementer!(AsciiFormatToken, "ascii");
+token_implementer!(BinaryFormatToken, "binary");
+token_implementer!(LatexModeToken, "latex_mode=");
+token_implementer!(StandaloneModeToken, "standalone");
+token_implementer!(SnippetModeToken, "snippet");
+token_implementer!(DocumentModeToken, "document");
 token_implementer!(SvgToken, "svg=");
 token_implementer!(LatexToken, "latex=");
 token_implementer!(SixelDToken, "sixel=");
 token_implementer!(RegisDToken, "regis=");
 token_implementer!(CsvToken, "csv=");
+token_implementer!(GnuplotToken, "gnuplot=");
+token_implementer!(MatplotlibToken, "matplotlib=");
+token_implementer!(JsonToken, "json=");
+token_implementer!(VegaLiteToken, "vegalite=");
+token_implementer!(NpyToken, "npy=");
+token_implementer!(ObjToken, "obj=");
+token_implementer!(StlToken, "stl=");
+token_implementer!(PlyToken, "ply=");
 
 //Geometry tokens
 token_implementer!(GeometryToken, "geometry=");
+token_implementer!(ByToken, "x");
+
+//Plot option tokens
+token_implementer!(TitleToken, "title=");
+token_implementer!(XLabelToken, "xlabel=");
+token_implementer!(YLabelToken, "ylabel=");
+token_implementer!(ZLabelToken, "zlabel=");
+token_implementer!(XRangeToken, "xrange=");
+token_implementer!(YRangeToken, "yrange=");
+token_implementer!(AutoscaleToken, "autoscale=");
+token_implementer!(FullToken, "full");
+token_implementer!(PercentileToken, "percentile:");
+token_implementer!(XScaleToken, "xscale=");
+token_implementer!(YScaleToken, "yscale=");
+token_implementer!(ZScaleToken, "zscale=");
+token_implementer!(LinearToken, "linear");
+token_implementer!(LogToken, "log");
+token_implementer!(AsymptotesToken, "asymptotes=");
+token_implementer!(OnToken, "on");
+token_implementer!(OffToken, "off");
+token_implementer!(StyleToken, "style=");
+token_implementer!(PointsToken, "points");
+token_implementer!(LinesToken, "lines");
+token_implementer!(BothToken, "both");
+token_implementer!(LineWidthToken, "linewidth=");
+token_implementer!(DashToken, "dash=");
+token_implementer!(NoneToken, "none");
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct IntegerToken;
 
@@ -164,6 +219,11 @@ pub struct DoubleToken;
 pub struct LowerCaseName;
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct AsciiAnythingUpToSpace;
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuotedString;
+/// A file name followed by `:column`, so it stops at the colon
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileNameUpToColon;
 
 impl<'a> Parse<'a, Chars<'a>, State, String, String> for AsciiAnythingUpToSpace {
     fn parse(&self, input: Chars<'a>, state: State) -> Result<(String, State, Chars<'a>), String> {
@@ -180,6 +240,40 @@ impl<'a> Parse<'a, Chars<'a>, State, String, String> for AsciiAnythingUpToSpace
     }
 }
 
+impl<'a> Parse<'a, Chars<'a>, State, String, String> for FileNameUpToColon {
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<(String, State, Chars<'a>), String> {
+        match_anything(State::transit_generator(1, 0))
+            .validate(
+                |character: &char| character.is_ascii() && !character.is_whitespace() && character != &':',
+                "file name character".to_string(),
+            )
+            .one_or_more()
+            .transform(|x| x.into_iter().collect::<String>())
+            .parse(input, state)
+    }
+}
+
+impl<'a> Parse<'a, Chars<'a>, State, String, String> for QuotedString {
+    fn parse(&self, input: Chars<'a>, state: State) -> Result<(String, State, Chars<'a>), String> {
+        let quote = match_anything(State::transit_generator(1, 0))
+            .validate(|character: &char| character == &'"', "quote".to_string());
+        let contents = match_anything(State::transit_generator(1, 0))
+            .validate(
+                |character: &char| character != &'"' && character != &'\n',
+                "quoted character".to_string(),
+            )
+            .zero_or_more()
+            .transform(|x| x.into_iter().collect::<String>());
+
+        quote
+            .clone()
+            .triple(contents, quote)
+            .second()
+            .with_error(|err, _| err.fold(identity, identity, identity))
+            .parse(input, state)
+    }
+}
+
 impl<'a> Parse<'a, Chars<'a>, State, String, String> for LowerCaseName {
     fn parse(&self, input: Chars<'a>, state: State) -> Result<(String, State, Chars<'a>), String> {
         match_anything(State::transit_generator(1, 0))
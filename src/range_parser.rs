@@ -4,7 +4,7 @@ use parser_combinator::Parse;
 
 use crate::{
     parser_common::{
-        identity, AsciiAnythingUpToSpace, Colon, DoubleToken, For, In, IntegerToken, LowerCaseName,
+        identity, AsciiAnythingUpToSpace, Colon, DoubleToken, FileNameUpToColon, For, In, IntegerToken, LowerCaseName,
         ParseErrors, State,
     },
     range::{ERange, ERangeFile, ERangeFileCol, ERangeNumeric, ERangeNumericStep, Range},
@@ -70,7 +70,7 @@ impl<'a> Parse<'a, Chars<'a>, State, Range, ParseErrors> for ERangeFileCol {
         For.triple(LowerCaseName, In)
             .second()
             .with_error_using_state(|_x, s, _i| ParseErrors::Generic(s.start, s.end))
-            .pair(AsciiAnythingUpToSpace)
+            .pair(FileNameUpToColon)
             .with_error_using_state(|_, s, _| ParseErrors::Generic(s.start, s.end))
             .pair(
                 Colon
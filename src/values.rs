@@ -312,13 +312,126 @@ impl ExpressionRange1dResult {
         }
     }
 
-    pub fn max(&self) -> f64 {
-        self.clone().0.into_iter().reduce(f64::max).unwrap()
+    /// Whether a range file is a NumPy array rather than whitespace separated text
+    pub fn is_npy_file(path: &str) -> bool {
+        Path::new(path).extension().is_some_and(|extension| extension.eq_ignore_ascii_case("npy"))
     }
 
-    pub fn min(&self) -> f64 {
-        self.clone().0.into_iter().reduce(f64::min).unwrap()
+    /// Values of a NumPy `.npy` file: all of them in row-major order, or with `col`
+    /// the values in that column of a 2D array, one per row
+    pub fn create_from_npy(
+        path: String,
+        col: Option<usize>,
+    ) -> Result<Self, impl FnOnce(Localization, Localization) -> EvaluationError> {
+        std::fs::read(&path)
+            .map_err(|e| format!("Couldnt read {path}: {e}"))
+            .and_then(|bytes| NpyArray::parse(&bytes))
+            .and_then(|array| array.column(col))
+            .map(Self)
+            .map_err(|message| move |a, b| EvaluationError::GenericWithString(a, b, message))
+    }
+}
+
+/// Array read from a `.npy` file, with its values converted to `f64` in the order
+/// they are stored
+struct NpyArray {
+    shape: Vec<usize>,
+    fortran_order: bool,
+    values: Vec<f64>,
+}
+
+impl NpyArray {
+    /// Reads the magic string, the header dictionary and the data. Only little-endian
+    /// `f8` and `f4` data is supported.
+    fn parse(bytes: &[u8]) -> Result<Self, String> {
+        if !bytes.starts_with(b"\x93NUMPY") || bytes.len() < 10 {
+            return Err("Not a .npy file".to_string());
+        }
+        let (header_len, header_start) = match bytes[6] {
+            1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+            2 | 3 if bytes.len() >= 12 => (u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize, 12),
+            version => return Err(format!("Unsupported .npy version {version}")),
+        };
+        let data_start = header_start + header_len;
+        let header = bytes
+            .get(header_start..data_start)
+            .and_then(|header| std::str::from_utf8(header).ok())
+            .ok_or("Truncated .npy header")?;
+
+        let descr = npy_header_value(header, "descr")
+            .and_then(|value| {
+                let quote = value.chars().next().filter(|&quote| quote == '\'' || quote == '"')?;
+                value[1..].split(quote).next()
+            })
+            .ok_or("No data type in .npy header")?;
+        let fortran_order = npy_header_value(header, "fortran_order").is_some_and(|value| value.starts_with("True"));
+        let shape = npy_header_value(header, "shape")
+            .and_then(|value| value.strip_prefix('(')?.split(')').next())
+            .ok_or("No shape in .npy header")?
+            .split(',')
+            .map(str::trim)
+            .filter(|dimension| !dimension.is_empty())
+            .map(|dimension| dimension.parse::<usize>().map_err(|_| format!("Bad .npy shape dimension {dimension}")))
+            .collect::<Result<Vec<usize>, String>>()?;
+
+        let data = &bytes[data_start..];
+        let count: usize = shape.iter().product();
+        let values: Vec<f64> = match descr {
+            "<f8" => data.chunks_exact(8).take(count).map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap())).collect(),
+            "<f4" => data.chunks_exact(4).take(count).map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()) as f64).collect(),
+            _ => return Err(format!("Unsupported .npy data type {descr}, expected <f8 or <f4")),
+        };
+        if values.len() < count {
+            return Err(format!("Truncated .npy data, expected {count} values"));
+        }
+        Ok(Self { shape, fortran_order, values })
     }
+
+    /// Every value in row-major order, or the values of one column of a 2D array (a
+    /// 1D array being a single column)
+    fn column(self, col: Option<usize>) -> Result<Vec<f64>, String> {
+        let values = self.row_major();
+        match (col, self.shape.as_slice()) {
+            (None, _) => Ok(values),
+            (Some(0), [_]) => Ok(values),
+            (Some(col), &[_, columns]) if col < columns => Ok(values.into_iter().skip(col).step_by(columns).collect()),
+            (Some(col), shape) => Err(format!("No column {col} in a .npy array of shape {shape:?}")),
+        }
+    }
+
+    /// The values with the last index changing fastest, whatever order they were
+    /// stored in
+    fn row_major(&self) -> Vec<f64> {
+        if !self.fortran_order || self.shape.len() < 2 {
+            return self.values.clone();
+        }
+        let strides: Vec<usize> = self
+            .shape
+            .iter()
+            .scan(1, |stride, &dimension| {
+                let current = *stride;
+                *stride *= dimension;
+                Some(current)
+            })
+            .collect();
+        (0..self.values.len())
+            .map(|index| {
+                // Take the row-major index apart and put it together column-major
+                let (mut rest, mut offset) = (index, 0);
+                for (axis, &dimension) in self.shape.iter().enumerate().rev() {
+                    offset += (rest % dimension) * strides[axis];
+                    rest /= dimension;
+                }
+                self.values[offset]
+            })
+            .collect()
+    }
+}
+
+/// The text following `'key':` in a `.npy` header dictionary
+fn npy_header_value<'h>(header: &'h str, key: &str) -> Option<&'h str> {
+    let start = header.find(&format!("'{key}'"))? + key.len() + 2;
+    Some(header[start..].trim_start().strip_prefix(':')?.trim_start())
 }
 
 impl From<f64> for ExpressionRange1dResult {